@@ -0,0 +1,140 @@
+use clap::Subcommand;
+use tonic::transport::Channel;
+use wdb_grpc::wdb_grpc::{mutation, wide_db_client::WideDbClient, CreateTableRequest, DeleteCell, DeleteColumn, DeleteFamily, MutateRowRequest, Mutation, PutCell, ReadRowRequest, ReadRowsRequest};
+
+use crate::{output::Output, values::{decode_value, ValueEncoding}};
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Creates a table with the given column families.
+    CreateTable {
+        table: String,
+        #[arg(long = "family", short)]
+        families: Vec<String>,
+    },
+    /// Lists tables and their column families.
+    ListTables,
+    /// Writes a single cell.
+    Put {
+        table: String,
+        row: String,
+        family: String,
+        column: String,
+        value: String,
+        /// Cell timestamp, -1 lets the server assign the current time.
+        #[arg(long, default_value_t = -1, allow_negative_numbers = true)]
+        timestamp: i64,
+        /// Encoding of the value argument.
+        #[arg(long, value_enum, default_value_t = ValueEncoding::Text)]
+        encoding: ValueEncoding,
+        /// Visibility expression of the cell, e.g. "admin|(audit&eu)".
+        #[arg(long, default_value = "")]
+        visibility: String,
+    },
+    /// Deletes a family, a column or, with a timestamp, a single cell version.
+    Delete {
+        table: String,
+        row: String,
+        family: String,
+        column: Option<String>,
+        #[arg(long)]
+        timestamp: Option<i64>,
+    },
+    /// Reads a single row.
+    Get {
+        table: String,
+        row: String,
+        #[arg(long)]
+        family: Option<String>,
+        #[arg(long)]
+        column: Option<String>,
+    },
+    /// Reads a range of rows.
+    Scan {
+        table: String,
+        /// First row of the range, inclusive.
+        #[arg(long)]
+        start: Option<String>,
+        /// Last row of the range, exclusive.
+        #[arg(long)]
+        end: Option<String>,
+        #[arg(long)]
+        prefix: Option<String>,
+        #[arg(long)]
+        family: Option<String>,
+        #[arg(long)]
+        column: Option<String>,
+        /// Oldest cell timestamp to return, inclusive.
+        #[arg(long)]
+        start_timestamp: Option<i64>,
+        /// Newest cell timestamp to return, inclusive.
+        #[arg(long)]
+        end_timestamp: Option<i64>,
+        /// Maximum number of rows to return.
+        #[arg(long)]
+        limit: Option<u64>,
+    },
+}
+
+fn mutate_row(table: String, row: Vec<u8>, mutation: mutation::Mutation) -> MutateRowRequest {
+    MutateRowRequest { table_name: table, row, mutations: vec![Mutation { mutation: Some(mutation) }] }
+}
+
+pub async fn execute(client: &mut WideDbClient<Channel>, command: Command, output: &Output) -> Result<(), String> {
+    let status_to_string = |status: tonic::Status| format!("{:?}: {}", status.code(), status.message());
+
+    match command {
+        Command::CreateTable { table, families } => {
+            let table = client.create_table(CreateTableRequest { table_name: table, families, change_stream_config: None })
+                .await.map_err(status_to_string)?.into_inner();
+            output.print_table(&table);
+        },
+        Command::ListTables => {
+            let response = client.list_tables(()).await.map_err(status_to_string)?.into_inner();
+            output.print_tables(&response.tables);
+        },
+        Command::Put { table, row, family, column, value, timestamp, encoding, visibility } => {
+            let value = decode_value(&value, encoding)?;
+            let put = mutation::Mutation::PutCell(PutCell { family_name: family, column_name: output.decode_key(&column)?, timestamp, value, visibility });
+            client.mutate_row(mutate_row(table, output.decode_key(&row)?, put)).await.map_err(status_to_string)?;
+            output.print_ok();
+        },
+        Command::Delete { table, row, family, column, timestamp } => {
+            let column = column.map(|column| output.decode_key(&column)).transpose()?;
+            let delete = match (column, timestamp) {
+                (Some(column), Some(timestamp)) => mutation::Mutation::DeleteCell(DeleteCell { family_name: family, column_name: column, timestamp }),
+                (Some(column), None) => mutation::Mutation::DeleteColumn(DeleteColumn { family_name: family, column_name: column, timestamp: -1 }),
+                (None, timestamp) => mutation::Mutation::DeleteFamily(DeleteFamily { family_name: family, timestamp: timestamp.unwrap_or(-1) }),
+            };
+            client.mutate_row(mutate_row(table, output.decode_key(&row)?, delete)).await.map_err(status_to_string)?;
+            output.print_ok();
+        },
+        Command::Get { table, row, family, column } => {
+            let column = column.map(|column| output.decode_key(&column)).transpose()?;
+            let response = client.read_row(ReadRowRequest { table_name: table, row_key: output.decode_key(&row)? })
+                .await.map_err(status_to_string)?.into_inner();
+            let cells: Vec<_> = response.cells.into_iter().filter(|cell| {
+                family.as_ref().map_or(true, |family| *family == cell.family)
+                    && column.as_ref().map_or(true, |column| *column == cell.column)
+            }).collect();
+            output.print_cells(&cells);
+        },
+        Command::Scan { table, start, end, prefix, family, column, start_timestamp, end_timestamp, limit } => {
+            let decode = |key: Option<String>| key.map(|key| output.decode_key(&key)).transpose().map(Option::unwrap_or_default);
+            let response = client.read_rows(ReadRowsRequest {
+                table_name: table,
+                start_row: decode(start)?,
+                end_row: decode(end)?,
+                row_prefix: decode(prefix)?,
+                family_name: family.unwrap_or_default(),
+                column_name: decode(column)?,
+                start_timestamp: start_timestamp.unwrap_or(0),
+                end_timestamp: end_timestamp.unwrap_or(0),
+                rows_limit: limit.unwrap_or(0),
+            }).await.map_err(status_to_string)?.into_inner();
+            output.print_cells(&response.cells);
+        },
+    }
+
+    Ok(())
+}
\ No newline at end of file
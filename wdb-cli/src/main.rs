@@ -0,0 +1,48 @@
+mod commands;
+mod output;
+mod shell;
+mod values;
+
+use std::process;
+
+use clap::Parser;
+use wdb_grpc::wdb_grpc::wide_db_client::WideDbClient;
+
+use commands::{execute, Command};
+use output::Output;
+
+#[derive(Parser)]
+#[command(name = "wdb-cli", about = "Command line client for WideDB. Starts an interactive shell when no command is given.")]
+struct Args {
+    /// Address of the WideDB server.
+    #[arg(long, default_value = "http://127.0.0.1:50051")]
+    addr: String,
+    #[command(flatten)]
+    output: Output,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    let output = args.output;
+
+    let mut client = match WideDbClient::connect(args.addr.clone()).await {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("Could not connect to {}: {}", args.addr, err);
+            process::exit(1);
+        },
+    };
+
+    match args.command {
+        Some(command) => {
+            if let Err(err) = execute(&mut client, command, &output).await {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        },
+        None => shell::run_shell(&mut client, &output).await,
+    }
+}
\ No newline at end of file
@@ -0,0 +1,109 @@
+use clap::Args;
+use serde_json::json;
+use wdb_grpc::wdb_grpc::{Cell, Table};
+
+use crate::values::{decode_value, display_key, display_value, encode_value, ValueEncoding};
+
+#[derive(Debug, Clone, Copy, Args)]
+pub struct Output {
+    /// Prints results as JSON.
+    #[arg(long, global = true)]
+    pub json: bool,
+    /// Encoding of printed values, by default text or hex for pretty output and base64 for JSON.
+    #[arg(long = "values", value_enum, global = true)]
+    pub encoding: Option<ValueEncoding>,
+    /// Encoding of row keys and columns, both given as arguments and printed.
+    #[arg(long = "keys", value_enum, global = true)]
+    pub key_encoding: Option<ValueEncoding>,
+}
+
+impl Output {
+    // Flags given on a shell line add to the ones the shell was started with.
+    pub fn merge(&self, other: &Output) -> Output {
+        Output {
+            json: self.json || other.json,
+            encoding: other.encoding.or(self.encoding),
+            key_encoding: other.key_encoding.or(self.key_encoding),
+        }
+    }
+
+    pub fn decode_key(&self, key: &str) -> Result<Vec<u8>, String> {
+        decode_value(key, self.key_encoding.unwrap_or(ValueEncoding::Text))
+    }
+
+    fn format_key(&self, key: &[u8]) -> String {
+        match self.key_encoding {
+            Some(encoding) => encode_value(key, encoding),
+            None => display_key(key),
+        }
+    }
+
+    pub fn print_tables(&self, tables: &[Table]) {
+        if self.json {
+            let tables: Vec<_> = tables.iter().map(|table| {
+                json!({ "name": table.name, "families": table.column_families })
+            }).collect();
+            println!("{}", serde_json::to_string_pretty(&tables).unwrap());
+            return;
+        }
+
+        for table in tables {
+            println!("{}", self.format_table(table));
+        }
+    }
+
+    pub fn print_table(&self, table: &Table) {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&json!({ "name": table.name, "families": table.column_families })).unwrap());
+            return;
+        }
+
+        println!("{}", self.format_table(table));
+    }
+
+    pub fn print_cells(&self, cells: &[Cell]) {
+        if self.json {
+            let cells: Vec<_> = cells.iter().map(|cell| {
+                json!({
+                    "row": self.format_key(&cell.row_key),
+                    "family": cell.family,
+                    "column": self.format_key(&cell.column),
+                    "timestamp": cell.timestamp,
+                    "value": encode_value(&cell.value, self.encoding.unwrap_or(ValueEncoding::Base64)),
+                    "visibility": cell.visibility,
+                })
+            }).collect();
+            println!("{}", serde_json::to_string_pretty(&cells).unwrap());
+            return;
+        }
+
+        let mut last_row = None;
+        for cell in cells {
+            if last_row != Some(&cell.row_key) {
+                println!("{}", self.format_key(&cell.row_key));
+                last_row = Some(&cell.row_key);
+            }
+
+            let value = match self.encoding {
+                Some(encoding) => encode_value(&cell.value, encoding),
+                None => display_value(&cell.value),
+            };
+            match cell.visibility.is_empty() {
+                true => println!("  {}:{} @ {}\n    {}", cell.family, self.format_key(&cell.column), cell.timestamp, value),
+                false => println!("  {}:{} @ {} [{}]\n    {}", cell.family, self.format_key(&cell.column), cell.timestamp, cell.visibility, value),
+            }
+        }
+    }
+
+    pub fn print_ok(&self) {
+        if self.json {
+            println!("{}", json!({ "ok": true }));
+        } else {
+            println!("OK");
+        }
+    }
+
+    fn format_table(&self, table: &Table) -> String {
+        format!("{} [{}]", table.name, table.column_families.join(", "))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,71 @@
+use std::{env, path::PathBuf};
+
+use clap::Parser;
+use rustyline::{error::ReadlineError, DefaultEditor};
+use tonic::transport::Channel;
+use wdb_grpc::wdb_grpc::wide_db_client::WideDbClient;
+
+use crate::{commands::{execute, Command}, output::Output};
+
+#[derive(Parser)]
+#[command(name = "", no_binary_name = true, disable_version_flag = true)]
+struct ShellLine {
+    #[command(flatten)]
+    output: Output,
+    #[command(subcommand)]
+    command: Command,
+}
+
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".wdb_cli_history"))
+}
+
+pub async fn run_shell(client: &mut WideDbClient<Channel>, output: &Output) {
+    let mut editor = DefaultEditor::new().unwrap();
+    let history = history_path();
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    loop {
+        let line = match editor.readline("wdb> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("{}", err);
+                break;
+            },
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line).unwrap();
+
+        if line == "exit" || line == "quit" {
+            break;
+        }
+
+        let Some(args) = shlex::split(line) else {
+            eprintln!("Unterminated quote in command.");
+            continue;
+        };
+
+        match ShellLine::try_parse_from(args) {
+            Ok(parsed) => {
+                if let Err(err) = execute(client, parsed.command, &output.merge(&parsed.output)).await {
+                    eprintln!("{}", err);
+                }
+            },
+            Err(err) => {
+                let _ = err.print();
+            },
+        }
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+}
\ No newline at end of file
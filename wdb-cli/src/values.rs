@@ -0,0 +1,44 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ValueEncoding {
+    Text,
+    Hex,
+    Base64,
+}
+
+pub fn decode_value(value: &str, encoding: ValueEncoding) -> Result<Vec<u8>, String> {
+    match encoding {
+        ValueEncoding::Text => Ok(value.as_bytes().to_vec()),
+        ValueEncoding::Hex => hex::decode(value.trim_start_matches("0x")).map_err(|err| format!("Invalid hex value: {}", err)),
+        ValueEncoding::Base64 => STANDARD.decode(value).map_err(|err| format!("Invalid base64 value: {}", err)),
+    }
+}
+
+pub fn encode_value(value: &[u8], encoding: ValueEncoding) -> String {
+    match encoding {
+        ValueEncoding::Text => String::from_utf8_lossy(value).to_string(),
+        ValueEncoding::Hex => hex::encode(value),
+        ValueEncoding::Base64 => STANDARD.encode(value),
+    }
+}
+
+fn printable(value: &[u8]) -> Option<&str> {
+    std::str::from_utf8(value).ok().filter(|text| !text.chars().any(|c| c.is_control()))
+}
+
+// Printable UTF-8 is shown as is, anything else falls back to hex so the terminal is never garbled.
+pub fn display_value(value: &[u8]) -> String {
+    match printable(value) {
+        Some(text) => format!("\"{}\"", text),
+        None => format!("0x{}", hex::encode(value)),
+    }
+}
+
+pub fn display_key(key: &[u8]) -> String {
+    match printable(key) {
+        Some(text) => text.to_string(),
+        None => format!("0x{}", hex::encode(key)),
+    }
+}
\ No newline at end of file
@@ -0,0 +1,233 @@
+use std::{future::Future, sync::{atomic::{AtomicUsize, Ordering}, Arc}, time::Duration};
+
+use bytes::Bytes;
+use tokio::time::{sleep, timeout, Instant};
+use tonic::{metadata::{Ascii, MetadataValue}, transport::{Channel, ClientTlsConfig, Endpoint}, Code, Request, Response, Status};
+use wdb_grpc::wdb_grpc::{wide_db_client::WideDbClient, CreateTableRequest, DeleteTableRequest, ReadRowRequest, Table};
+
+use crate::{row::rows_from_cells, utils::to_string, Error, ReadRowsQuery, RetryPolicy, Row, RowMutation};
+
+pub struct ClientBuilder {
+    endpoint: String,
+    pool_size: usize,
+    retry_policy: RetryPolicy,
+    timeout: Option<Duration>,
+    connect_timeout: Duration,
+    bearer_token: Option<String>,
+    tls: Option<ClientTlsConfig>,
+}
+
+impl ClientBuilder {
+    pub fn pool_size(mut self, pool_size: usize) -> ClientBuilder {
+        self.pool_size = pool_size.max(1);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> ClientBuilder {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Default deadline of a call, covering all of its retries.
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> ClientBuilder {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Sent as `authorization: Bearer <token>` with every call.
+    pub fn bearer_token<T: Into<String>>(mut self, token: T) -> ClientBuilder {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    pub fn tls_config(mut self, tls: ClientTlsConfig) -> ClientBuilder {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Connects every channel of the pool up front and fails if the server is unreachable.
+    pub async fn connect(self) -> Result<WdbClient, Error> {
+        let endpoint = self.endpoint()?;
+
+        let mut channels = vec![];
+        for _ in 0..self.pool_size {
+            channels.push(endpoint.connect().await?);
+        }
+
+        self.build(channels)
+    }
+
+    /// Channels connect on first use, unreachable servers surface as retriable call errors.
+    pub fn connect_lazy(self) -> Result<WdbClient, Error> {
+        let endpoint = self.endpoint()?;
+        let channels = (0..self.pool_size).map(|_| endpoint.connect_lazy()).collect();
+
+        self.build(channels)
+    }
+
+    fn endpoint(&self) -> Result<Endpoint, Error> {
+        let mut endpoint = Endpoint::from_shared(self.endpoint.clone())?.connect_timeout(self.connect_timeout);
+        if let Some(tls) = &self.tls {
+            endpoint = endpoint.tls_config(tls.clone())?;
+        }
+
+        Ok(endpoint)
+    }
+
+    fn build(self, channels: Vec<Channel>) -> Result<WdbClient, Error> {
+        let authorization = match &self.bearer_token {
+            Some(token) => Some(format!("Bearer {}", token).parse().map_err(|_| Error::InvalidArgument("Bearer token must be printable ASCII."))?),
+            None => None,
+        };
+
+        Ok(WdbClient {
+            pool: Arc::new(ChannelPool {
+                clients: channels.into_iter().map(WideDbClient::new).collect(),
+                next: AtomicUsize::new(0),
+            }),
+            retry_policy: self.retry_policy,
+            timeout: self.timeout,
+            authorization,
+        })
+    }
+}
+
+struct ChannelPool {
+    clients: Vec<WideDbClient<Channel>>,
+    next: AtomicUsize,
+}
+
+impl ChannelPool {
+    fn get(&self) -> WideDbClient<Channel> {
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        self.clients[next % self.clients.len()].clone()
+    }
+}
+
+/// Cheap to clone, clones share the channel pool.
+#[derive(Clone)]
+pub struct WdbClient {
+    pool: Arc<ChannelPool>,
+    retry_policy: RetryPolicy,
+    timeout: Option<Duration>,
+    authorization: Option<MetadataValue<Ascii>>,
+}
+
+impl WdbClient {
+    pub fn builder<E: Into<String>>(endpoint: E) -> ClientBuilder {
+        ClientBuilder {
+            endpoint: endpoint.into(),
+            pool_size: 1,
+            retry_policy: RetryPolicy::default(),
+            timeout: None,
+            connect_timeout: Duration::from_secs(5),
+            bearer_token: None,
+            tls: None,
+        }
+    }
+
+    pub async fn connect<E: Into<String>>(endpoint: E) -> Result<WdbClient, Error> {
+        WdbClient::builder(endpoint).connect().await
+    }
+
+    /// Returns a handle sharing the pool whose calls use the given deadline.
+    pub fn with_timeout(&self, timeout: Duration) -> WdbClient {
+        WdbClient { timeout: Some(timeout), ..self.clone() }
+    }
+
+    pub async fn create_table<T: Into<Bytes>>(&self, table: T, families: Vec<Bytes>) -> Result<Table, Error> {
+        let request = CreateTableRequest {
+            table_name: to_string(table.into())?,
+            families: families.into_iter().map(to_string).collect::<Result<Vec<String>, Error>>()?,
+            change_stream_config: None,
+        };
+
+        self.call(request, false, |mut client, request| async move { client.create_table(request).await }).await
+    }
+
+    pub async fn delete_table<T: Into<Bytes>>(&self, table: T) -> Result<(), Error> {
+        let request = DeleteTableRequest { table_name: to_string(table.into())? };
+
+        self.call(request, false, |mut client, request| async move { client.delete_table(request).await }).await
+    }
+
+    pub async fn list_tables(&self) -> Result<Vec<Table>, Error> {
+        let response = self.call((), true, |mut client, request| async move { client.list_tables(request).await }).await?;
+        Ok(response.tables)
+    }
+
+    pub async fn mutate_row(&self, mutation: RowMutation) -> Result<(), Error> {
+        let idempotent = mutation.is_idempotent();
+        let request = mutation.into_request()?;
+
+        self.call(request, idempotent, |mut client, request| async move { client.mutate_row(request).await }).await
+    }
+
+    pub async fn read_row<T: Into<Bytes>, R: Into<Bytes>>(&self, table: T, row: R) -> Result<Option<Row>, Error> {
+        let request = ReadRowRequest { table_name: to_string(table.into())?, row_key: row.into().to_vec() };
+
+        let response = self.call(request, true, |mut client, request| async move { client.read_row(request).await }).await?;
+        Ok(rows_from_cells(response.cells).pop())
+    }
+
+    pub async fn read_rows(&self, query: ReadRowsQuery) -> Result<Vec<Row>, Error> {
+        let request = query.into_request()?;
+
+        let response = self.call(request, true, |mut client, request| async move { client.read_rows(request).await }).await?;
+        Ok(rows_from_cells(response.cells))
+    }
+
+    async fn call<M, T, F, Fut>(&self, message: M, idempotent: bool, f: F) -> Result<T, Error>
+    where
+        M: Clone,
+        F: Fn(WideDbClient<Channel>, Request<M>) -> Fut,
+        Fut: Future<Output = Result<Response<T>, Status>>,
+    {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let mut backoff = self.retry_policy.initial_backoff;
+        let mut attempt = 1;
+
+        loop {
+            let mut request = Request::new(message.clone());
+            if let Some(authorization) = &self.authorization {
+                request.metadata_mut().insert("authorization", authorization.clone());
+            }
+
+            let result = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    request.set_timeout(remaining);
+
+                    // The channel enforces the grpc-timeout header too and reports it as CANCELLED.
+                    match timeout(remaining, f(self.pool.get(), request)).await {
+                        Ok(Err(status)) if status.code() == Code::Cancelled && Instant::now() >= deadline => Err(Status::deadline_exceeded("Deadline exceeded.")),
+                        Ok(result) => result,
+                        Err(_) => Err(Status::deadline_exceeded("Deadline exceeded.")),
+                    }
+                },
+                None => f(self.pool.get(), request).await,
+            };
+
+            let status = match result {
+                Ok(response) => return Ok(response.into_inner()),
+                Err(status) => status,
+            };
+
+            let retriable = idempotent && attempt < self.retry_policy.max_attempts && RetryPolicy::is_retriable(status.code());
+            let wait = RetryPolicy::retry_after(&status).map_or(backoff, |retry_after| retry_after.max(backoff));
+            let expires = deadline.is_some_and(|deadline| Instant::now() + wait >= deadline);
+            if !retriable || expires {
+                return Err(Error::Status(status));
+            }
+
+            sleep(wait).await;
+            backoff = self.retry_policy.next_backoff(backoff);
+            attempt += 1;
+        }
+    }
+}
\ No newline at end of file
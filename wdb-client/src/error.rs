@@ -0,0 +1,43 @@
+use std::fmt::Display;
+
+use tonic::{Code, Status};
+
+#[derive(Debug)]
+pub enum Error {
+    Transport(tonic::transport::Error),
+    Status(Status),
+    InvalidArgument(&'static str),
+}
+
+impl Error {
+    pub fn code(&self) -> Option<Code> {
+        match self {
+            Error::Status(status) => Some(status.code()),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Transport(err) => write!(f, "transport error: {}", err),
+            Error::Status(status) => write!(f, "{:?}: {}", status.code(), status.message()),
+            Error::InvalidArgument(err) => write!(f, "invalid argument: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Status> for Error {
+    fn from(status: Status) -> Self {
+        Error::Status(status)
+    }
+}
+
+impl From<tonic::transport::Error> for Error {
+    fn from(err: tonic::transport::Error) -> Self {
+        Error::Transport(err)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,18 @@
+mod client;
+mod error;
+mod mutation;
+mod query;
+mod retry;
+mod row;
+mod utils;
+
+pub use client::{ClientBuilder, WdbClient};
+pub use error::Error;
+pub use mutation::RowMutation;
+pub use query::ReadRowsQuery;
+pub use retry::RetryPolicy;
+pub use row::{CellVersion, Row};
+
+pub use wdb_grpc::wdb_grpc::Table;
+pub use tonic::Code;
+pub use tonic::transport::{Certificate, ClientTlsConfig, Identity};
\ No newline at end of file
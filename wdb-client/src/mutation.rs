@@ -0,0 +1,99 @@
+use bytes::Bytes;
+use wdb_grpc::wdb_grpc::{mutation::Mutation as MutationKind, DeleteCell, DeleteColumn, DeleteFamily, MutateRowRequest, Mutation, PutCell};
+
+use crate::{utils::to_string, Error};
+
+#[derive(Debug, Clone)]
+enum MutationOp {
+    Put { family: Bytes, column: Bytes, timestamp: Option<u64>, value: Bytes, visibility: String },
+    DeleteCell { family: Bytes, column: Bytes, timestamp: u64 },
+    DeleteColumn { family: Bytes, column: Bytes },
+    DeleteFamily { family: Bytes },
+}
+
+#[derive(Debug, Clone)]
+pub struct RowMutation {
+    table: Bytes,
+    row: Bytes,
+    ops: Vec<MutationOp>,
+}
+
+impl RowMutation {
+    pub fn new<T: Into<Bytes>, R: Into<Bytes>>(table: T, row: R) -> RowMutation {
+        RowMutation { table: table.into(), row: row.into(), ops: vec![] }
+    }
+
+    /// Writes a cell with the server assigned timestamp.
+    pub fn put<F: Into<Bytes>, C: Into<Bytes>, V: Into<Bytes>>(mut self, family: F, column: C, value: V) -> RowMutation {
+        self.ops.push(MutationOp::Put { family: family.into(), column: column.into(), timestamp: None, value: value.into(), visibility: String::new() });
+        self
+    }
+
+    pub fn put_at<F: Into<Bytes>, C: Into<Bytes>, V: Into<Bytes>>(mut self, family: F, column: C, timestamp: u64, value: V) -> RowMutation {
+        self.ops.push(MutationOp::Put { family: family.into(), column: column.into(), timestamp: Some(timestamp), value: value.into(), visibility: String::new() });
+        self
+    }
+
+    /// Writes a cell readable only by callers whose authorizations satisfy the visibility expression, e.g. `admin|(audit&eu)`.
+    pub fn put_with_visibility<F: Into<Bytes>, C: Into<Bytes>, V: Into<Bytes>, L: Into<String>>(mut self, family: F, column: C, value: V, visibility: L) -> RowMutation {
+        self.ops.push(MutationOp::Put { family: family.into(), column: column.into(), timestamp: None, value: value.into(), visibility: visibility.into() });
+        self
+    }
+
+    pub fn delete_cell<F: Into<Bytes>, C: Into<Bytes>>(mut self, family: F, column: C, timestamp: u64) -> RowMutation {
+        self.ops.push(MutationOp::DeleteCell { family: family.into(), column: column.into(), timestamp });
+        self
+    }
+
+    pub fn delete_column<F: Into<Bytes>, C: Into<Bytes>>(mut self, family: F, column: C) -> RowMutation {
+        self.ops.push(MutationOp::DeleteColumn { family: family.into(), column: column.into() });
+        self
+    }
+
+    pub fn delete_family<F: Into<Bytes>>(mut self, family: F) -> RowMutation {
+        self.ops.push(MutationOp::DeleteFamily { family: family.into() });
+        self
+    }
+
+    // Only ops with explicit client timestamps can be applied twice without changing the result,
+    // column and family deletes take the server timestamp and would delete cells written in between.
+    pub fn is_idempotent(&self) -> bool {
+        self.ops.iter().all(|op| matches!(op, MutationOp::Put { timestamp: Some(_), .. } | MutationOp::DeleteCell { .. }))
+    }
+
+    pub(crate) fn into_request(self) -> Result<MutateRowRequest, Error> {
+        if self.ops.is_empty() {
+            return Err(Error::InvalidArgument("Row mutation has no operations."));
+        }
+
+        let mutations = self.ops.into_iter().map(|op| -> Result<Mutation, Error> {
+            let mutation = match op {
+                MutationOp::Put { family, column, timestamp, value, visibility } => MutationKind::PutCell(PutCell {
+                    family_name: to_string(family)?,
+                    column_name: column.to_vec(),
+                    timestamp: timestamp.map_or(-1, |ts| ts as i64),
+                    value: value.to_vec(),
+                    visibility,
+                }),
+                MutationOp::DeleteCell { family, column, timestamp } => MutationKind::DeleteCell(DeleteCell {
+                    family_name: to_string(family)?,
+                    column_name: column.to_vec(),
+                    timestamp: timestamp as i64,
+                }),
+                MutationOp::DeleteColumn { family, column } => MutationKind::DeleteColumn(DeleteColumn {
+                    family_name: to_string(family)?,
+                    column_name: column.to_vec(),
+                    timestamp: -1,
+                }),
+                MutationOp::DeleteFamily { family } => MutationKind::DeleteFamily(DeleteFamily {
+                    family_name: to_string(family)?,
+                    timestamp: -1,
+                }),
+            };
+
+            Ok(Mutation { mutation: Some(mutation) })
+        }).collect::<Result<Vec<Mutation>, Error>>()?;
+
+        Ok(MutateRowRequest { table_name: to_string(self.table)?, row: self.row.to_vec(), mutations })
+    }
+}
\ No newline at end of file
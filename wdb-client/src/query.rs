@@ -0,0 +1,79 @@
+use bytes::Bytes;
+use wdb_grpc::wdb_grpc::ReadRowsRequest;
+
+use crate::{utils::to_string, Error};
+
+#[derive(Debug, Clone, Default)]
+pub struct ReadRowsQuery {
+    table: Bytes,
+    start_row: Option<Bytes>,
+    end_row: Option<Bytes>,
+    prefix: Option<Bytes>,
+    family: Option<Bytes>,
+    column: Option<Bytes>,
+    time_range: Option<(u64, u64)>,
+    limit: Option<u64>,
+}
+
+impl ReadRowsQuery {
+    pub fn new<T: Into<Bytes>>(table: T) -> ReadRowsQuery {
+        ReadRowsQuery { table: table.into(), ..ReadRowsQuery::default() }
+    }
+
+    /// First row of the range, inclusive.
+    pub fn start<R: Into<Bytes>>(mut self, row: R) -> ReadRowsQuery {
+        self.start_row = Some(row.into());
+        self
+    }
+
+    /// Last row of the range, exclusive.
+    pub fn end<R: Into<Bytes>>(mut self, row: R) -> ReadRowsQuery {
+        self.end_row = Some(row.into());
+        self
+    }
+
+    pub fn prefix<R: Into<Bytes>>(mut self, prefix: R) -> ReadRowsQuery {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn family<F: Into<Bytes>>(mut self, family: F) -> ReadRowsQuery {
+        self.family = Some(family.into());
+        self
+    }
+
+    pub fn column<C: Into<Bytes>>(mut self, column: C) -> ReadRowsQuery {
+        self.column = Some(column.into());
+        self
+    }
+
+    /// Both bounds are inclusive.
+    pub fn time_range(mut self, start: u64, end: u64) -> ReadRowsQuery {
+        self.time_range = Some((start, end));
+        self
+    }
+
+    pub fn limit(mut self, rows: u64) -> ReadRowsQuery {
+        self.limit = Some(rows);
+        self
+    }
+
+    pub(crate) fn into_request(self) -> Result<ReadRowsRequest, Error> {
+        let (start_timestamp, end_timestamp) = match self.time_range {
+            Some((start, end)) => (start as i64, end as i64),
+            None => (0, 0),
+        };
+
+        Ok(ReadRowsRequest {
+            table_name: to_string(self.table)?,
+            start_row: self.start_row.map_or(vec![], |row| row.to_vec()),
+            end_row: self.end_row.map_or(vec![], |row| row.to_vec()),
+            row_prefix: self.prefix.map_or(vec![], |prefix| prefix.to_vec()),
+            family_name: self.family.map(to_string).transpose()?.unwrap_or_default(),
+            column_name: self.column.map_or(vec![], |column| column.to_vec()),
+            start_timestamp,
+            end_timestamp,
+            rows_limit: self.limit.unwrap_or(0),
+        })
+    }
+}
\ No newline at end of file
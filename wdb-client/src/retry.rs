@@ -0,0 +1,42 @@
+use std::{cmp::min, time::Duration};
+
+use tonic::{Code, Status};
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+}
+
+impl RetryPolicy {
+    pub fn none() -> RetryPolicy {
+        RetryPolicy { max_attempts: 1, ..RetryPolicy::default() }
+    }
+
+    pub fn is_retriable(code: Code) -> bool {
+        matches!(code, Code::Unavailable | Code::Aborted | Code::ResourceExhausted)
+    }
+
+    /// Wait the server asks for before the call may pass, sent along RESOURCE_EXHAUSTED by quotas.
+    pub(crate) fn retry_after(status: &Status) -> Option<Duration> {
+        let retry_after = status.metadata().get("retry-after-ms")?.to_str().ok()?;
+        retry_after.parse().ok().map(Duration::from_millis)
+    }
+
+    pub(crate) fn next_backoff(&self, backoff: Duration) -> Duration {
+        min(backoff.mul_f64(self.multiplier), self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+            multiplier: 2.0,
+        }
+    }
+}
\ No newline at end of file
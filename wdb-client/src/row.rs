@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use wdb_grpc::wdb_grpc::Cell;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellVersion {
+    pub timestamp: u64,
+    pub value: Bytes,
+    pub visibility: String,
+}
+
+/// Cells of a single row grouped by family and column, versions ordered newest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    key: Bytes,
+    families: BTreeMap<Bytes, BTreeMap<Bytes, Vec<CellVersion>>>,
+}
+
+impl Row {
+    pub fn get_key(&self) -> &Bytes {
+        &self.key
+    }
+
+    pub fn get_families(&self) -> &BTreeMap<Bytes, BTreeMap<Bytes, Vec<CellVersion>>> {
+        &self.families
+    }
+
+    pub fn get_family(&self, family: &[u8]) -> Option<&BTreeMap<Bytes, Vec<CellVersion>>> {
+        self.families.get(family)
+    }
+
+    pub fn get_versions(&self, family: &[u8], column: &[u8]) -> &[CellVersion] {
+        self.families.get(family)
+            .and_then(|columns| columns.get(column))
+            .map_or(&[], |versions| versions.as_slice())
+    }
+
+    pub fn get_latest(&self, family: &[u8], column: &[u8]) -> Option<&CellVersion> {
+        self.get_versions(family, column).first()
+    }
+
+    pub fn get_value(&self, family: &[u8], column: &[u8]) -> Option<&Bytes> {
+        self.get_latest(family, column).map(|version| &version.value)
+    }
+
+    pub fn get_cells_count(&self) -> usize {
+        self.families.values().flat_map(|columns| columns.values()).map(|versions| versions.len()).sum()
+    }
+}
+
+// Cells arrive ordered by row, so consecutive cells of the same row make up one result row.
+pub(crate) fn rows_from_cells(cells: Vec<Cell>) -> Vec<Row> {
+    let mut rows: Vec<Row> = vec![];
+
+    for cell in cells {
+        let key = Bytes::from(cell.row_key);
+        if rows.last().map_or(true, |row| row.key != key) {
+            rows.push(Row { key, families: BTreeMap::new() });
+        }
+
+        let row = rows.last_mut().unwrap();
+        row.families.entry(Bytes::from(cell.family)).or_default()
+            .entry(Bytes::from(cell.column)).or_default()
+            .push(CellVersion { timestamp: cell.timestamp as u64, value: Bytes::from(cell.value), visibility: cell.visibility });
+    }
+
+    for row in rows.iter_mut() {
+        for versions in row.families.values_mut().flat_map(|columns| columns.values_mut()) {
+            versions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        }
+    }
+
+    rows
+}
\ No newline at end of file
@@ -0,0 +1,8 @@
+use bytes::Bytes;
+
+use crate::Error;
+
+// Table and family names are proto strings, row keys and columns are bytes.
+pub(crate) fn to_string(bytes: Bytes) -> Result<String, Error> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| Error::InvalidArgument("Table and family names must be valid UTF-8."))
+}
\ No newline at end of file
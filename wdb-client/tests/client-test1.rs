@@ -0,0 +1,638 @@
+use std::{collections::{BTreeMap, HashMap}, fs, path::Path, sync::{atomic::{AtomicUsize, Ordering}, Arc}, time::{Duration, Instant}};
+
+use bytes::Bytes;
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::{TcpListener, TcpStream}};
+use tokio_stream::wrappers::TcpListenerStream;
+use wdb_client::{Certificate, ClientTlsConfig, Code, ReadRowsQuery, RetryPolicy, RowMutation, WdbClient};
+use wdb_grpc::wdb_grpc::{wide_db_admin_client::WideDbAdminClient, wide_db_client::WideDbClient, wide_db_server::WideDbServer, AclEntry, CompactRangeRequest, CreateBackupRequest, FlushTableRequest, IamPolicy, IngestSsTablesRequest, ListSegmentsRequest, Permission, ReadRowRequest, ReadRowsRequest, SetIamPolicyRequest, WatchRequest};
+use wdb_server::{auth::{Principal, StaticTokenAuthenticator}, config::{AdminConfig, QuotaConfig, QuotaLimits, ServerConfig}, grpc::{GrpcApi, GrpcOptions, HandlersService, TlsOptions}, metrics::MetricsServer, server::Server};
+use wdb_storage_engine::{EngineOptions, FSPersistance, SSTableBuilder, StorageEngine, Timestamp};
+use tonic::transport::Channel;
+use tonic_health::pb::{health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest};
+
+async fn start_server(base: &Path, listener: TcpListener) {
+    start_server_with_options(base, listener, &GrpcOptions::default()).await;
+}
+
+async fn start_server_with_options(base: &Path, listener: TcpListener, options: &GrpcOptions) {
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(base), false);
+    let server = Server::init(storage_engine);
+    GrpcApi::init_with_listener(server.get_ctx().clone(), options, listener).unwrap();
+}
+
+#[tokio::test]
+async fn client_read_write_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    start_server(dir.path(), listener).await;
+
+    let client = WdbClient::builder(format!("http://{}", addr)).pool_size(3).connect().await.unwrap();
+    client.create_table("users", vec![Bytes::from("info"), Bytes::from("stats")]).await.unwrap();
+    assert_eq!(client.create_table("users", vec![]).await.unwrap_err().code(), Some(Code::AlreadyExists));
+
+    for user in ["user1", "user2", "user3", "admin1"] {
+        client.mutate_row(RowMutation::new("users", user)
+            .put_at("info", "name", 10, "John")
+            .put_at("info", "name", 20, "Johnny")
+            .put_at("stats", "logins", 10, vec![0u8, 1])
+        ).await.unwrap();
+    }
+
+    let row = client.read_row("users", "user2").await.unwrap().unwrap();
+    assert_eq!(row.get_key(), &Bytes::from("user2"));
+    assert_eq!(row.get_cells_count(), 3);
+    assert_eq!(row.get_versions(b"info", b"name").iter().map(|version| version.timestamp).collect::<Vec<_>>(), vec![20, 10]);
+    assert_eq!(row.get_value(b"info", b"name"), Some(&Bytes::from("Johnny")));
+    assert_eq!(row.get_value(b"stats", b"logins"), Some(&Bytes::from(vec![0u8, 1])));
+    assert!(client.read_row("users", "user9").await.unwrap().is_none());
+
+    let rows = client.read_rows(ReadRowsQuery::new("users").prefix("user").family("info").time_range(15, 30).limit(2)).await.unwrap();
+    assert_eq!(rows.iter().map(|row| row.get_key().clone()).collect::<Vec<_>>(), vec![Bytes::from("user1"), Bytes::from("user2")]);
+    assert!(rows.iter().all(|row| row.get_cells_count() == 1 && row.get_family(b"stats").is_none()));
+
+    client.mutate_row(RowMutation::new("users", "user1").delete_column("info", "name")).await.unwrap();
+    let row = client.read_row("users", "user1").await.unwrap().unwrap();
+    assert!(row.get_latest(b"info", b"name").is_none());
+
+    let tables = client.list_tables().await.unwrap();
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].name, "users");
+}
+
+// Serves the handlers behind an interceptor failing the first calls with UNAVAILABLE, returns the counter of calls.
+async fn start_flaky_server(base: &Path, listener: TcpListener, failures: usize) -> Arc<AtomicUsize> {
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(base), false);
+    let server = Server::init(storage_engine);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let service = WideDbServer::with_interceptor(HandlersService::new(server.get_ctx().clone()), move |request| {
+        match counter.fetch_add(1, Ordering::SeqCst) < failures {
+            true => Err(tonic::Status::unavailable("Server is not accepting requests.")),
+            false => Ok(request),
+        }
+    });
+    tokio::spawn(tonic::transport::Server::builder().add_service(service).serve_with_incoming(TcpListenerStream::new(listener)));
+    calls
+}
+
+// Accepts connections and never answers them.
+fn stall_connections(listener: TcpListener) {
+    tokio::spawn(async move {
+        let mut streams = vec![];
+        while let Ok((stream, _)) = listener.accept().await {
+            streams.push(stream);
+        }
+    });
+}
+
+#[tokio::test]
+async fn client_retry_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let calls = start_flaky_server(dir.path(), listener, 4).await;
+
+    let client = WdbClient::builder(format!("http://{}", addr))
+        .retry_policy(RetryPolicy { max_attempts: 3, initial_backoff: Duration::from_millis(1), max_backoff: Duration::from_millis(1), multiplier: 1.0 })
+        .connect()
+        .await
+        .unwrap();
+
+    assert!(RowMutation::new("users", "user1").put_at("info", "name", 1, "John").delete_cell("info", "name", 1).is_idempotent());
+    let mutations = [
+        RowMutation::new("users", "user1").put("info", "name", "John"),
+        RowMutation::new("users", "user1").delete_column("info", "name"),
+        RowMutation::new("users", "user1").delete_family("info"),
+    ];
+
+    // Ops with server timestamps are not idempotent, so they fail after the first attempt.
+    for (i, mutation) in mutations.into_iter().enumerate() {
+        assert!(!mutation.is_idempotent());
+        assert_eq!(client.mutate_row(mutation).await.unwrap_err().code(), Some(Code::Unavailable));
+        assert_eq!(calls.load(Ordering::SeqCst), i + 1);
+    }
+
+    // The fourth call fails once more and the retry gets through.
+    assert!(client.list_tables().await.unwrap().is_empty());
+    assert_eq!(calls.load(Ordering::SeqCst), 5);
+}
+
+#[tokio::test]
+async fn client_deadline_test() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    stall_connections(listener);
+
+    let client = WdbClient::builder(format!("http://{}", addr)).connect_lazy().unwrap();
+    assert_eq!(client.with_timeout(Duration::from_millis(50)).list_tables().await.unwrap_err().code(), Some(Code::DeadlineExceeded));
+
+    let client = WdbClient::builder(format!("http://{}", addr)).timeout(Duration::from_millis(50)).connect_lazy().unwrap();
+    assert_eq!(client.list_tables().await.unwrap_err().code(), Some(Code::DeadlineExceeded));
+}
+
+#[tokio::test]
+async fn client_binary_keys_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    start_server(dir.path(), listener).await;
+
+    let client = WdbClient::connect(format!("http://{}", addr)).await.unwrap();
+    client.create_table("events", vec![Bytes::from("data")]).await.unwrap();
+
+    let rows = [vec![0xffu8, 0x00, 0x01], vec![0xff, 0xff, 0x02], vec![0xfe, 0x80]];
+    for row in rows.iter() {
+        client.mutate_row(RowMutation::new("events", row.clone()).put_at("data", vec![0xc3u8, 0x28], 1, "v")).await.unwrap();
+    }
+
+    let row = client.read_row("events", vec![0xffu8, 0x00, 0x01]).await.unwrap().unwrap();
+    assert_eq!(row.get_value(b"data", &[0xc3, 0x28]), Some(&Bytes::from("v")));
+
+    let rows = client.read_rows(ReadRowsQuery::new("events").prefix(vec![0xffu8])).await.unwrap();
+    assert_eq!(rows.iter().map(|row| row.get_key().to_vec()).collect::<Vec<_>>(), vec![vec![0xffu8, 0x00, 0x01], vec![0xff, 0xff, 0x02]]);
+}
+
+#[tokio::test]
+async fn client_auth_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let authenticator = StaticTokenAuthenticator::new(HashMap::from([("secret".to_string(), Principal::new("alice"))]));
+    let options = GrpcOptions { tls: None, authenticator: Some(Arc::new(authenticator)) };
+    start_server_with_options(dir.path(), listener, &options).await;
+
+    let endpoint = format!("http://{}", addr);
+
+    let client = WdbClient::connect(endpoint.clone()).await.unwrap();
+    assert_eq!(client.list_tables().await.unwrap_err().code(), Some(Code::Unauthenticated));
+
+    let client = WdbClient::builder(endpoint.clone()).bearer_token("wrong").connect().await.unwrap();
+    assert_eq!(client.list_tables().await.unwrap_err().code(), Some(Code::Unauthenticated));
+
+    let client = WdbClient::builder(endpoint).bearer_token("secret").connect().await.unwrap();
+    assert!(client.list_tables().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn client_tls_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let cert_pem = cert.serialize_pem().unwrap();
+    fs::write(dir.path().join("server.crt"), &cert_pem).unwrap();
+    fs::write(dir.path().join("server.key"), cert.serialize_private_key_pem()).unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let tls = TlsOptions { cert_path: dir.path().join("server.crt"), key_path: dir.path().join("server.key"), client_ca_path: None, client_auth_optional: false };
+    let data = dir.path().join("data");
+    fs::create_dir_all(&data).unwrap();
+    start_server_with_options(&data, listener, &GrpcOptions { tls: Some(tls), authenticator: None }).await;
+
+    let tls = ClientTlsConfig::new().ca_certificate(Certificate::from_pem(cert_pem)).domain_name("localhost");
+    let client = WdbClient::builder(format!("https://{}", addr)).tls_config(tls).connect().await.unwrap();
+    assert!(client.list_tables().await.unwrap().is_empty());
+
+    let client = WdbClient::builder(format!("http://{}", addr)).retry_policy(RetryPolicy::none()).connect_lazy().unwrap();
+    assert!(client.list_tables().await.is_err());
+}
+
+#[tokio::test]
+async fn client_acl_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let authenticator = StaticTokenAuthenticator::new(HashMap::from([
+        ("alice-token".to_string(), Principal::with_roles("alice", vec!["admins".to_string()])),
+        ("bob-token".to_string(), Principal::new("bob")),
+    ]));
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(dir.path()), false);
+    let config = ServerConfig { admin: AdminConfig { admins: vec!["role:admins".to_string()], ..AdminConfig::default() }, ..ServerConfig::default() };
+    let server = Server::init_with_config(storage_engine, &config);
+    GrpcApi::init_with_listener(server.get_ctx().clone(), &GrpcOptions { tls: None, authenticator: Some(Arc::new(authenticator)) }, listener).unwrap();
+
+    let endpoint = format!("http://{}", addr);
+    let alice = WdbClient::builder(endpoint.clone()).bearer_token("alice-token").connect().await.unwrap();
+    let bob = WdbClient::builder(endpoint.clone()).bearer_token("bob-token").connect().await.unwrap();
+
+    let set_policy = |table: &str, entries: Vec<(&str, Permission)>| SetIamPolicyRequest {
+        table_name: table.to_string(),
+        policy: Some(IamPolicy { entries: entries.into_iter().map(|(member, permission)| AclEntry { member: member.to_string(), permission: permission as i32 }).collect() }),
+    };
+    let mut admin = WideDbAdminClient::connect(endpoint.clone()).await.unwrap();
+
+    // Without a catalog policy only bootstrap admins may set one, or back up the catalog.
+    let mut request = tonic::Request::new(set_policy("", vec![("user:bob", Permission::Admin)]));
+    request.metadata_mut().insert("authorization", "Bearer bob-token".parse().unwrap());
+    assert_eq!(admin.set_iam_policy(request).await.unwrap_err().code(), Code::PermissionDenied);
+    let mut request = tonic::Request::new(CreateBackupRequest { backup_root: String::new(), parent_backup_id: String::new() });
+    request.metadata_mut().insert("authorization", "Bearer bob-token".parse().unwrap());
+    assert_eq!(admin.create_backup(request).await.unwrap_err().code(), Code::PermissionDenied);
+
+    let mut request = tonic::Request::new(set_policy("", vec![("role:admins", Permission::Admin)]));
+    request.metadata_mut().insert("authorization", "Bearer alice-token".parse().unwrap());
+    admin.set_iam_policy(request).await.unwrap();
+
+    alice.create_table("users", vec![Bytes::from("info")]).await.unwrap();
+    alice.create_table("secrets", vec![Bytes::from("info")]).await.unwrap();
+    assert_eq!(bob.create_table("bobs", vec![]).await.unwrap_err().code(), Some(Code::PermissionDenied));
+
+    let mut request = tonic::Request::new(set_policy("users", vec![("user:bob", Permission::Read)]));
+    request.metadata_mut().insert("authorization", "Bearer bob-token".parse().unwrap());
+    assert_eq!(admin.set_iam_policy(request).await.unwrap_err().code(), Code::PermissionDenied);
+
+    let mut request = tonic::Request::new(set_policy("users", vec![("user:bob", Permission::Read)]));
+    request.metadata_mut().insert("authorization", "Bearer alice-token".parse().unwrap());
+    admin.set_iam_policy(request).await.unwrap();
+
+    alice.mutate_row(RowMutation::new("users", "user1").put_at("info", "name", 1, "John")).await.unwrap();
+    assert!(bob.read_row("users", "user1").await.unwrap().is_some());
+    assert_eq!(bob.mutate_row(RowMutation::new("users", "user1").put_at("info", "name", 2, "Bob")).await.unwrap_err().code(), Some(Code::PermissionDenied));
+    assert_eq!(bob.read_row("secrets", "user1").await.unwrap_err().code(), Some(Code::PermissionDenied));
+    assert_eq!(bob.delete_table("users").await.unwrap_err().code(), Some(Code::PermissionDenied));
+    let mut request = tonic::Request::new(CompactRangeRequest { table_name: "users".to_string(), ..Default::default() });
+    request.metadata_mut().insert("authorization", "Bearer bob-token".parse().unwrap());
+    assert_eq!(admin.compact_range(request).await.unwrap_err().code(), Code::PermissionDenied);
+
+    let tables = bob.list_tables().await.unwrap();
+    assert_eq!(tables.iter().map(|table| table.name.as_str()).collect::<Vec<_>>(), vec!["users"]);
+    assert_eq!(alice.list_tables().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn client_visibility_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let authenticator = StaticTokenAuthenticator::new(HashMap::from([
+        ("alice-token".to_string(), Principal::with_roles("alice", vec!["audit".to_string()])),
+        ("bob-token".to_string(), Principal::new("bob")),
+    ]));
+    start_server_with_options(dir.path(), listener, &GrpcOptions { tls: None, authenticator: Some(Arc::new(authenticator)) }).await;
+
+    let endpoint = format!("http://{}", addr);
+    let alice = WdbClient::builder(endpoint.clone()).bearer_token("alice-token").connect().await.unwrap();
+    let bob = WdbClient::builder(endpoint.clone()).bearer_token("bob-token").connect().await.unwrap();
+
+    alice.create_table("users", vec![Bytes::from("info")]).await.unwrap();
+    alice.mutate_row(RowMutation::new("users", "user1")
+        .put("info", "name", "John")
+        .put_with_visibility("info", "saldo", "1234", "audit|admin")).await.unwrap();
+
+    let row = alice.read_row("users", "user1").await.unwrap().unwrap();
+    assert_eq!(row.get_cells_count(), 2);
+    assert_eq!(row.get_latest(b"info", b"saldo").unwrap().visibility, "audit|admin");
+
+    let row = bob.read_row("users", "user1").await.unwrap().unwrap();
+    assert_eq!(row.get_cells_count(), 1);
+    assert!(row.get_latest(b"info", b"saldo").is_none());
+    assert_eq!(bob.read_rows(ReadRowsQuery::new("users")).await.unwrap()[0].get_cells_count(), 1);
+
+    let invalid = alice.mutate_row(RowMutation::new("users", "user1").put_with_visibility("info", "saldo", "1", "audit&|admin")).await;
+    assert_eq!(invalid.unwrap_err().code(), Some(Code::InvalidArgument));
+    let too_long = alice.mutate_row(RowMutation::new("users", "user1").put_with_visibility("info", "saldo", "1", "a".repeat(u16::MAX as usize + 1))).await;
+    assert_eq!(too_long.unwrap_err().code(), Some(Code::InvalidArgument));
+}
+
+#[tokio::test]
+async fn client_unauthenticated_visibility_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    start_server(dir.path(), listener).await;
+
+    // Without authentication there are no labels to match, so only unlabelled cells are visible.
+    let client = WdbClient::builder(format!("http://{}", addr)).connect().await.unwrap();
+    client.create_table("users", vec![Bytes::from("info")]).await.unwrap();
+    client.mutate_row(RowMutation::new("users", "user1")
+        .put("info", "name", "John")
+        .put_with_visibility("info", "saldo", "1234", "audit")).await.unwrap();
+
+    let row = client.read_row("users", "user1").await.unwrap().unwrap();
+    assert_eq!(row.get_cells_count(), 1);
+    assert!(row.get_latest(b"info", b"saldo").is_none());
+    assert_eq!(client.read_rows(ReadRowsQuery::new("users")).await.unwrap()[0].get_cells_count(), 1);
+    assert_eq!(client.read_row("missing", "user1").await.unwrap_err().code(), Some(Code::NotFound));
+}
+
+#[tokio::test]
+async fn client_metrics_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(dir.path()), false);
+    let server = Server::init(storage_engine.clone());
+    GrpcApi::init_with_listener(server.get_ctx().clone(), &GrpcOptions::default(), listener).unwrap();
+
+    let metrics_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let metrics_addr = metrics_listener.local_addr().unwrap();
+    MetricsServer::init_with_listener(storage_engine, metrics_listener).unwrap();
+
+    let client = WdbClient::builder(format!("http://{}", addr)).connect().await.unwrap();
+    client.create_table("metrics_users", vec![Bytes::from("info")]).await.unwrap();
+    client.mutate_row(RowMutation::new("metrics_users", "user1").put("info", "name", "John")).await.unwrap();
+    let invalid = client.mutate_row(RowMutation::new("metrics_users", "user1").put_with_visibility("info", "name", "Johnny", "(audit")).await;
+    assert_eq!(invalid.unwrap_err().code(), Some(Code::InvalidArgument));
+
+    let mut stream = TcpStream::connect(metrics_addr).await.unwrap();
+    stream.write_all(b"GET /metrics HTTP/1.0\r\n\r\n").await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    assert!(response.starts_with("HTTP/1.0 200"));
+    assert!(response.contains("wdb_rpc_duration_seconds_count{method=\"/widedb.WideDB/MutateRow\"}"));
+    assert!(response.contains("wdb_rpc_errors_total{code=\"InvalidArgument\",method=\"/widedb.WideDB/MutateRow\"}"));
+    assert!(response.contains("wdb_memtable_bytes{family=\"info\",table=\"metrics_users\"}"));
+    assert!(response.contains("wdb_mvcc_lag{table=\"metrics_users\"} 0"));
+    assert!(response.contains("wdb_mutations_total"));
+}
+
+#[tokio::test]
+async fn client_health_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let storage_engine = StorageEngine::open(FSPersistance::with_base(dir.path()), EngineOptions { flush_agent: false, ..EngineOptions::default() });
+    let server = Server::init(storage_engine.clone());
+    let grpc_api = GrpcApi::init_with_listener(server.get_ctx().clone(), &GrpcOptions::default(), listener).unwrap();
+
+    let endpoint = format!("http://{}", addr);
+    let health = HealthClient::new(Channel::from_shared(endpoint.clone()).unwrap().connect().await.unwrap());
+    let client = WdbClient::builder(endpoint).retry_policy(RetryPolicy::none()).connect().await.unwrap();
+    let status = |service: &'static str| {
+        let mut health = health.clone();
+        async move { health.check(HealthCheckRequest { service: service.to_string() }).await.unwrap().into_inner().status() }
+    };
+
+    assert_eq!(status("").await, ServingStatus::NotServing);
+    assert_eq!(status("widedb.recovery").await, ServingStatus::NotServing);
+    assert_eq!(status("widedb.storage").await, ServingStatus::Serving);
+    assert_eq!(client.list_tables().await.unwrap_err().code(), Some(Code::Unavailable));
+
+    storage_engine.recover();
+    grpc_api.get_health().update().await;
+    assert_eq!(status("").await, ServingStatus::Serving);
+    assert_eq!(status("widedb.WideDB").await, ServingStatus::Serving);
+    assert_eq!(status("widedb.flush_agent").await, ServingStatus::Serving);
+    client.create_table("users", vec![Bytes::from("info")]).await.unwrap();
+
+    grpc_api.get_health().set_shutting_down().await;
+    assert_eq!(status("").await, ServingStatus::NotServing);
+    assert_eq!(status("widedb.recovery").await, ServingStatus::Serving);
+    assert_eq!(client.list_tables().await.unwrap_err().code(), Some(Code::Unavailable));
+    assert!(health.clone().check(HealthCheckRequest { service: "unknown".to_string() }).await.is_err());
+}
+
+#[tokio::test]
+async fn client_shutdown_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(dir.path()), false);
+    let server = Server::init(storage_engine.clone());
+    let grpc_api = GrpcApi::init_with_listener(server.get_ctx().clone(), &GrpcOptions::default(), listener).unwrap();
+
+    let endpoint = format!("http://{}", addr);
+    let client = WdbClient::builder(endpoint.clone()).retry_policy(RetryPolicy::none()).connect().await.unwrap();
+    client.create_table("users", vec![Bytes::from("info")]).await.unwrap();
+    client.mutate_row(RowMutation::new("users", "user1").put("info", "name", "John")).await.unwrap();
+
+    // Watch never finishes on its own, so it is cancelled once the drain timeout passes.
+    let mut raw = WideDbClient::connect(endpoint.clone()).await.unwrap();
+    let _watch = raw.watch(WatchRequest { table_name: "users".to_string(), ..Default::default() }).await.unwrap().into_inner();
+
+    let start = Instant::now();
+    assert!(!grpc_api.shutdown(Duration::from_millis(200)).await);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(client.list_tables().await.unwrap_err().code(), Some(Code::Unavailable));
+
+    storage_engine.stop_flush_agent().await;
+    storage_engine.flush_all();
+    drop(server);
+    drop(storage_engine);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(dir.path()), false);
+    let server = Server::init(storage_engine);
+    let grpc_api = GrpcApi::init_with_listener(server.get_ctx().clone(), &GrpcOptions::default(), listener).unwrap();
+
+    let client = WdbClient::builder(format!("http://{}", addr)).connect().await.unwrap();
+    let row = client.read_row("users", "user1").await.unwrap().unwrap();
+    assert_eq!(row.get_value(b"info", b"name"), Some(&Bytes::from("John")));
+    assert!(grpc_api.shutdown(Duration::from_secs(5)).await);
+}
+
+#[tokio::test]
+async fn client_segments_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    start_server(dir.path(), listener).await;
+
+    let endpoint = format!("http://{}", addr);
+    let client = WdbClient::builder(endpoint.clone()).connect().await.unwrap();
+    client.create_table("users", vec![Bytes::from("info")]).await.unwrap();
+    let mut admin = WideDbAdminClient::connect(endpoint).await.unwrap();
+
+    for (row, value) in [("user1", "John"), ("user2", "Jan")] {
+        client.mutate_row(RowMutation::new("users", row).put_at("info", "name", 1, value)).await.unwrap();
+        let flushed = admin.flush_table(FlushTableRequest { table_name: "users".to_string(), family_name: None }).await.unwrap().into_inner();
+        assert_eq!(flushed.families[0].memtable_bytes, 0);
+    }
+    client.mutate_row(RowMutation::new("users", "user1").put_at("info", "name", 1, "Johnny")).await.unwrap();
+    admin.flush_table(FlushTableRequest { table_name: "users".to_string(), family_name: Some("info".to_string()) }).await.unwrap();
+
+    let listed = admin.list_segments(ListSegmentsRequest { table_name: "users".to_string(), family_name: None }).await.unwrap().into_inner();
+    assert_eq!(listed.families.len(), 1);
+    assert_eq!(listed.families[0].segments.len(), 3);
+    assert_eq!(listed.families[0].segments[1].min_row_key, Some(b"user2".to_vec()));
+
+    let compacted = admin.compact_range(CompactRangeRequest { table_name: "users".to_string(), ..Default::default() }).await.unwrap().into_inner();
+    assert_eq!(compacted.compactions[0].removed_segments.len(), 3);
+    assert_eq!(compacted.compactions[0].cells_written, 2);
+    let segments = &compacted.families[0].segments;
+    assert_eq!(segments.len(), 1);
+    assert_eq!(Some(&segments[0].name), compacted.compactions[0].added_segment.as_ref());
+    assert_eq!((segments[0].min_row_key.as_deref(), segments[0].max_row_key.as_deref()), (Some(&b"user1"[..]), Some(&b"user2"[..])));
+    assert_eq!(segments[0].max_mvcc_id, listed.families[0].segments.iter().map(|segment| segment.max_mvcc_id).max().unwrap());
+
+    let row = client.read_row("users", "user1").await.unwrap().unwrap();
+    assert_eq!(row.get_value(b"info", b"name"), Some(&Bytes::from("Johnny")));
+
+    let missing = admin.list_segments(ListSegmentsRequest { table_name: "users".to_string(), family_name: Some("missing".to_string()) }).await;
+    assert_eq!(missing.unwrap_err().code(), Code::NotFound);
+    let missing = admin.compact_range(CompactRangeRequest { table_name: "missing".to_string(), ..Default::default() }).await;
+    assert_eq!(missing.unwrap_err().code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn client_quota_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let quotas = QuotaConfig {
+        tables: BTreeMap::from([("quota_users".to_string(), QuotaLimits { requests_per_sec: Some(2), write_bytes_per_sec: Some(40) })]),
+        ..QuotaConfig::default()
+    };
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(dir.path()), false);
+    let server = Server::init_with_quotas(storage_engine.clone(), quotas);
+    GrpcApi::init_with_listener(server.get_ctx().clone(), &GrpcOptions::default(), listener).unwrap();
+
+    let metrics_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let metrics_addr = metrics_listener.local_addr().unwrap();
+    MetricsServer::init_with_listener(storage_engine, metrics_listener).unwrap();
+
+    let endpoint = format!("http://{}", addr);
+    let client = WdbClient::builder(endpoint.clone()).retry_policy(RetryPolicy::none()).connect().await.unwrap();
+    client.create_table("quota_users", vec![Bytes::from("info")]).await.unwrap();
+
+    // Row, family, column and value add up to the whole write quota.
+    client.mutate_row(RowMutation::new("quota_users", "user1").put_at("info", "name", 1, "x".repeat(27))).await.unwrap();
+    let exhausted = client.mutate_row(RowMutation::new("quota_users", "user2").put_at("info", "name", 1, "John")).await.unwrap_err();
+    assert_eq!(exhausted.code(), Some(Code::ResourceExhausted));
+
+    let mut raw = WideDbClient::connect(endpoint.clone()).await.unwrap();
+    let read = || ReadRowRequest { table_name: "quota_users".to_string(), row_key: b"user1".to_vec() };
+    raw.read_row(read()).await.unwrap();
+    let status = raw.read_row(read()).await.unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    let retry_after: u64 = status.metadata().get("retry-after-ms").unwrap().to_str().unwrap().parse().unwrap();
+    assert!(retry_after > 0 && retry_after <= 1000);
+
+    // The retrying client waits as long as the server asks and then gets through.
+    let client = WdbClient::builder(endpoint).connect().await.unwrap();
+    assert!(client.read_row("quota_users", "user1").await.unwrap().is_some());
+
+    let mut stream = TcpStream::connect(metrics_addr).await.unwrap();
+    stream.write_all(b"GET /metrics HTTP/1.0\r\n\r\n").await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.contains("wdb_quota_limit{name=\"quota_users\",resource=\"write_bytes\",scope=\"table\"} 40"));
+    assert!(response.contains("wdb_quota_throttled_total{name=\"quota_users\",resource=\"write_bytes\",scope=\"table\"} 1"));
+    assert!(response.contains("wdb_quota_throttled_total{name=\"quota_users\",resource=\"requests\",scope=\"table\"}"));
+    assert!(response.contains("wdb_quota_consumed_total{name=\"quota_users\",resource=\"write_bytes\",scope=\"table\"} 40"));
+}
+
+#[tokio::test]
+async fn client_backup_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let backup_root = dir.path().join("backups");
+    let config = ServerConfig { admin: AdminConfig { backup_root: Some(backup_root.clone()), ..AdminConfig::default() }, ..ServerConfig::default() };
+    fs::create_dir(dir.path().join("data")).unwrap();
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(dir.path().join("data")), false);
+    let server = Server::init_with_config(storage_engine, &config);
+    GrpcApi::init_with_listener(server.get_ctx().clone(), &GrpcOptions::default(), listener).unwrap();
+
+    let endpoint = format!("http://{}", addr);
+    let client = WdbClient::builder(endpoint.clone()).connect().await.unwrap();
+    client.create_table("users", vec![Bytes::from("info")]).await.unwrap();
+    client.mutate_row(RowMutation::new("users", "user1").put("info", "name", "John")).await.unwrap();
+    let mut admin = WideDbAdminClient::connect(endpoint).await.unwrap();
+
+    let backup = |root: &str, parent: &str| CreateBackupRequest { backup_root: root.to_string(), parent_backup_id: parent.to_string() };
+    let full = admin.create_backup(backup("nightly", "")).await.unwrap().into_inner();
+    assert!(backup_root.join("nightly").join(&full.backup_id).join("manifest.json").is_file());
+    let incremental = admin.create_backup(backup("nightly", &full.backup_id)).await.unwrap().into_inner();
+    assert_eq!(incremental.parent_backup_id, full.backup_id);
+
+    let outside = dir.path().join("outside");
+    fs::create_dir(&outside).unwrap();
+    std::os::unix::fs::symlink(&outside, backup_root.join("escape")).unwrap();
+    for (root, parent, code) in [
+        ("../outside", "", Code::InvalidArgument),
+        (outside.to_str().unwrap(), "", Code::InvalidArgument),
+        ("escape", "", Code::PermissionDenied),
+        ("escape/nested", "", Code::PermissionDenied),
+        ("nightly", "../nightly", Code::InvalidArgument),
+        ("nightly", "missing", Code::NotFound),
+    ] {
+        assert_eq!(admin.create_backup(backup(root, parent)).await.unwrap_err().code(), code, "{} {}", root, parent);
+    }
+    assert_eq!(fs::read_dir(&outside).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn client_ingest_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let ingest_dir = dir.path().join("ingest");
+    fs::create_dir_all(ingest_dir.join("batch")).unwrap();
+    fs::create_dir(dir.path().join("data")).unwrap();
+    let config = ServerConfig { admin: AdminConfig { ingest_dir: Some(ingest_dir.clone()), ..AdminConfig::default() }, ..ServerConfig::default() };
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(dir.path().join("data")), false);
+    let server = Server::init_with_config(storage_engine, &config);
+    GrpcApi::init_with_listener(server.get_ctx().clone(), &GrpcOptions::default(), listener).unwrap();
+
+    let mut builder = SSTableBuilder::new(fs::File::create(ingest_dir.join("batch").join("users.sst")).unwrap(), Bytes::from("info"));
+    builder.put(&Bytes::from("user1"), &Bytes::from("name"), Timestamp::from(100), &Bytes::from("John")).unwrap();
+    builder.finish().unwrap();
+    fs::copy(ingest_dir.join("batch").join("users.sst"), dir.path().join("outside.sst")).unwrap();
+    std::os::unix::fs::symlink(dir.path().join("outside.sst"), ingest_dir.join("escape.sst")).unwrap();
+
+    let endpoint = format!("http://{}", addr);
+    let client = WdbClient::builder(endpoint.clone()).connect().await.unwrap();
+    client.create_table("users", vec![Bytes::from("info")]).await.unwrap();
+    let mut admin = WideDbAdminClient::connect(endpoint).await.unwrap();
+
+    let ingest = |path: &str| IngestSsTablesRequest { table_name: "users".to_string(), family_name: "info".to_string(), paths: vec![path.to_string()] };
+    let ingested = admin.ingest_ss_tables(ingest("batch/users.sst")).await.unwrap().into_inner();
+    assert_eq!(ingested.cell_count, 1);
+    assert!(client.read_row("users", "user1").await.unwrap().is_some());
+
+    let outside = dir.path().join("outside.sst");
+    for (path, code) in [
+        ("../outside.sst", Code::InvalidArgument),
+        ("batch/../../outside.sst", Code::InvalidArgument),
+        (outside.to_str().unwrap(), Code::InvalidArgument),
+        ("escape.sst", Code::PermissionDenied),
+        ("batch/missing.sst", Code::NotFound),
+    ] {
+        assert_eq!(admin.ingest_ss_tables(ingest(path)).await.unwrap_err().code(), code, "{}", path);
+    }
+}
+
+#[tokio::test]
+async fn client_read_rows_limit_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    start_server(dir.path(), listener).await;
+
+    let endpoint = format!("http://{}", addr);
+    let client = WdbClient::connect(endpoint.clone()).await.unwrap();
+    client.create_table("events", vec![Bytes::from("data"), Bytes::from("meta")]).await.unwrap();
+
+    let rows = [vec![0x01u8, 0xfe], vec![0x01, 0xff], vec![0x01, 0xff, 0x00], vec![0x02], vec![0xff, 0xff], vec![0xff, 0xff, 0x01]];
+    for row in rows.iter() {
+        client.mutate_row(RowMutation::new("events", row.clone()).put_at("data", "v", 1, "1").put_at("data", "w", 1, "2")).await.unwrap();
+    }
+    client.mutate_row(RowMutation::new("events", vec![0x00u8]).put_at("meta", "v", 1, "1")).await.unwrap();
+
+    let raw = WideDbClient::connect(endpoint).await.unwrap();
+    let read = |request: ReadRowsRequest| {
+        let mut raw = raw.clone();
+        async move {
+            let cells = raw.read_rows(request).await.unwrap().into_inner().cells;
+            let mut rows = cells.iter().map(|cell| cell.row_key.clone()).collect::<Vec<_>>();
+            rows.dedup();
+            (rows, cells.len())
+        }
+    };
+    let request = |prefix: &[u8], family: &str, limit: u64| ReadRowsRequest { table_name: "events".to_string(), row_prefix: prefix.to_vec(), family_name: family.to_string(), rows_limit: limit, ..Default::default() };
+
+    // Rows without cells in the requested family don't count towards the limit.
+    assert_eq!(read(request(b"", "data", 2)).await, (vec![rows[0].clone(), rows[1].clone()], 4));
+    assert_eq!(read(request(b"", "", 1)).await, (vec![vec![0x00u8]], 1));
+    assert_eq!(read(request(b"", "", 0)).await.0.len(), 7);
+
+    // Prefix end carries past 0xff bytes, and a prefix of only 0xff bytes has no end.
+    assert_eq!(read(request(&[0x01, 0xff], "", 0)).await.0, vec![rows[1].clone(), rows[2].clone()]);
+    assert_eq!(read(request(&[0x01], "", 2)).await.0, vec![rows[0].clone(), rows[1].clone()]);
+    assert_eq!(read(request(&[0xff, 0xff], "", 0)).await.0, vec![rows[4].clone(), rows[5].clone()]);
+    let bounded = ReadRowsRequest { end_row: vec![0xff, 0xff, 0x01], ..request(&[0xff], "", 0) };
+    assert_eq!(read(bounded).await.0, vec![rows[4].clone()]);
+}
\ No newline at end of file
@@ -10,7 +10,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "protos/list-tables.proto",
         "protos/mutate-row.proto",
         "protos/read-row.proto",
-        "protos/widedb.proto"
+        "protos/read-rows.proto",
+        "protos/delete-table.proto",
+        "protos/modify-column-families.proto",
+        "protos/get-table.proto",
+        "protos/sample-row-keys.proto",
+        "protos/table-stats.proto",
+        "protos/read-change-stream.proto",
+        "protos/watch.proto",
+        "protos/backup.proto",
+        "protos/ingest-sstables.proto",
+        "protos/iam-policy.proto",
+        "protos/segments.proto",
+        "protos/widedb.proto",
+        "protos/admin.proto"
     ], &["protos/"])
     .unwrap();
 
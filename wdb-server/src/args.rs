@@ -0,0 +1,179 @@
+use std::{net::SocketAddr, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use wdb_server::{config::ServerConfig, grpc::TlsOptions};
+
+#[derive(Parser)]
+#[command(name = "wdb-server", about = "WideDB server. Flags override environment variables, which override the config file.")]
+pub struct Args {
+    /// TOML configuration file.
+    #[arg(long, env = "WDB_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// Prints the effective configuration and exits.
+    #[arg(long)]
+    pub print_config: bool,
+    #[arg(long, env = "WDB_LISTEN_ADDR")]
+    pub listen_addr: Option<SocketAddr>,
+    #[arg(long, env = "WDB_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// Log filter, e.g. `info` or `warn,wdb_storage_engine=debug`.
+    #[arg(long, env = "WDB_LOG_LEVEL")]
+    pub log_level: Option<String>,
+    #[arg(long, env = "WDB_SHUTDOWN_TIMEOUT_MS")]
+    pub shutdown_timeout_ms: Option<u64>,
+    #[arg(long, env = "WDB_FLUSH_AGENT")]
+    pub flush_agent: Option<bool>,
+    #[arg(long, env = "WDB_FLUSH_INTERVAL_MS")]
+    pub flush_interval_ms: Option<u64>,
+    #[arg(long, env = "WDB_MEMTABLE_FLUSH_BYTES")]
+    pub memtable_flush_bytes: Option<u64>,
+    #[arg(long, env = "WDB_TLS_CERT")]
+    pub tls_cert: Option<PathBuf>,
+    #[arg(long, env = "WDB_TLS_KEY")]
+    pub tls_key: Option<PathBuf>,
+    /// Enables mTLS with clients verified against this CA.
+    #[arg(long, env = "WDB_TLS_CLIENT_CA")]
+    pub tls_client_ca: Option<PathBuf>,
+    #[arg(long, env = "WDB_TLS_CLIENT_AUTH_OPTIONAL")]
+    pub tls_client_auth_optional: Option<bool>,
+    /// Enables bearer token authentication, one `<token> <principal> [role,role]` per line.
+    #[arg(long, env = "WDB_AUTH_TOKENS_FILE")]
+    pub auth_tokens_file: Option<PathBuf>,
+    #[arg(long, env = "WDB_METRICS")]
+    pub metrics: Option<bool>,
+    /// Address of the Prometheus metrics endpoint.
+    #[arg(long, env = "WDB_METRICS_LISTEN_ADDR")]
+    pub metrics_listen_addr: Option<SocketAddr>,
+    /// Directory backups are created in.
+    #[arg(long, env = "WDB_BACKUP_ROOT")]
+    pub backup_root: Option<PathBuf>,
+    /// Directory SSTables are ingested from.
+    #[arg(long, env = "WDB_INGEST_DIR")]
+    pub ingest_dir: Option<PathBuf>,
+    /// Bootstrap admins, e.g. `user:alice,role:admins`.
+    #[arg(long, env = "WDB_ADMINS", value_delimiter = ',')]
+    pub admins: Option<Vec<String>>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Restores a backup into the data directory.
+    Restore {
+        backup_dir: PathBuf,
+    },
+    /// Exports a table as JSON Lines, `-` writes to stdout.
+    Export {
+        table: String,
+        file: String,
+        start_row: Option<String>,
+        end_row: Option<String>,
+    },
+    /// Imports a table exported with `export`, `-` reads from stdin.
+    Import {
+        table: String,
+        file: String,
+    },
+}
+
+impl Args {
+    pub fn apply(&self, config: &mut ServerConfig) -> Result<(), String> {
+        if let Some(listen_addr) = self.listen_addr {
+            config.listen_addr = listen_addr;
+        }
+        if let Some(data_dir) = &self.data_dir {
+            config.data_dir = data_dir.clone();
+        }
+        if let Some(log_level) = &self.log_level {
+            config.log_level = log_level.clone();
+        }
+        if let Some(shutdown_timeout_ms) = self.shutdown_timeout_ms {
+            config.shutdown_timeout_ms = shutdown_timeout_ms;
+        }
+        if let Some(flush_agent) = self.flush_agent {
+            config.engine.flush_agent = flush_agent;
+        }
+        if let Some(flush_interval_ms) = self.flush_interval_ms {
+            config.engine.flush_interval_ms = flush_interval_ms;
+        }
+        if let Some(memtable_flush_bytes) = self.memtable_flush_bytes {
+            config.engine.memtable_flush_bytes = memtable_flush_bytes;
+        }
+
+        // Certificate and key may override the ones from the config file one at a time.
+        match (&mut config.tls, &self.tls_cert, &self.tls_key) {
+            (_, None, None) => {},
+            (Some(tls), cert_path, key_path) => {
+                tls.cert_path = cert_path.clone().unwrap_or(tls.cert_path.clone());
+                tls.key_path = key_path.clone().unwrap_or(tls.key_path.clone());
+            },
+            (None, Some(cert_path), Some(key_path)) => {
+                config.tls = Some(TlsOptions { cert_path: cert_path.clone(), key_path: key_path.clone(), client_ca_path: None, client_auth_optional: false });
+            },
+            (None, _, _) => return Err("Both TLS certificate and key must be set to enable TLS.".to_string()),
+        }
+        if self.tls_client_ca.is_some() || self.tls_client_auth_optional.is_some() {
+            let tls = config.tls.as_mut().ok_or("Client authentication options require TLS to be enabled.")?;
+            if let Some(client_ca) = &self.tls_client_ca {
+                tls.client_ca_path = Some(client_ca.clone());
+            }
+            if let Some(client_auth_optional) = self.tls_client_auth_optional {
+                tls.client_auth_optional = client_auth_optional;
+            }
+        }
+
+        if let Some(tokens_file) = &self.auth_tokens_file {
+            config.auth.tokens_file = Some(tokens_file.clone());
+        }
+
+        if let Some(metrics) = self.metrics {
+            config.metrics.enabled = metrics;
+        }
+        if let Some(metrics_listen_addr) = self.metrics_listen_addr {
+            config.metrics.listen_addr = metrics_listen_addr;
+        }
+
+        if let Some(backup_root) = &self.backup_root {
+            config.admin.backup_root = Some(backup_root.clone());
+        }
+        if let Some(ingest_dir) = &self.ingest_dir {
+            config.admin.ingest_dir = Some(ingest_dir.clone());
+        }
+        if let Some(admins) = &self.admins {
+            config.admin.admins = admins.clone();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use clap::Parser;
+    use wdb_server::config::ServerConfig;
+    use wdb_storage_engine::EngineOptions;
+
+    use super::Args;
+
+    #[test]
+    fn config_precedence_test() {
+        let path = std::env::temp_dir().join(format!("wdb-config-test-{}.toml", std::process::id()));
+        fs::write(&path, "log_level = \"debug\"\nshutdown_timeout_ms = 100\n\n[engine]\nflush_interval_ms = 200\n").unwrap();
+        let mut config = ServerConfig::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        std::env::set_var("WDB_SHUTDOWN_TIMEOUT_MS", "300");
+        std::env::set_var("WDB_FLUSH_INTERVAL_MS", "400");
+        let args = Args::try_parse_from(["wdb-server", "--flush-interval-ms", "500"]).unwrap();
+        args.apply(&mut config).unwrap();
+
+        assert_eq!(config.engine.flush_interval_ms, 500);
+        assert_eq!(config.shutdown_timeout_ms, 300);
+        assert_eq!(config.log_level, "debug");
+        assert_eq!(config.engine.memtable_flush_bytes, EngineOptions::default().memtable_flush_bytes);
+        assert_eq!(config.listen_addr, ServerConfig::default().listen_addr);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use tonic::{service::Interceptor, Request, Status};
+
+use super::Authenticator;
+
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    authenticator: Option<Arc<dyn Authenticator>>,
+}
+
+impl AuthInterceptor {
+    pub fn new(authenticator: Option<Arc<dyn Authenticator>>) -> AuthInterceptor {
+        AuthInterceptor { authenticator }
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    // The principal travels in the request extensions until the service moves it into the ServerCtx.
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(authenticator) = &self.authenticator {
+            let principal = authenticator.authenticate(request.metadata())?;
+            request.extensions_mut().insert(principal);
+        }
+
+        Ok(request)
+    }
+}
\ No newline at end of file
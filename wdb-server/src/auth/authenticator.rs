@@ -0,0 +1,13 @@
+use tonic::{metadata::MetadataMap, Status};
+
+use super::Principal;
+
+pub trait Authenticator: Send + Sync + 'static {
+    /// Resolves the caller of a request, rejected calls should return UNAUTHENTICATED.
+    fn authenticate(&self, metadata: &MetadataMap) -> Result<Principal, Status>;
+}
+
+pub fn bearer_token(metadata: &MetadataMap) -> Option<&str> {
+    let value = metadata.get("authorization")?.to_str().ok()?;
+    value.strip_prefix("Bearer ").map(|token| token.trim())
+}
\ No newline at end of file
@@ -0,0 +1,9 @@
+mod principal;
+mod authenticator;
+mod static_token_authenticator;
+mod auth_interceptor;
+
+pub use principal::Principal;
+pub use authenticator::{Authenticator, bearer_token};
+pub use static_token_authenticator::StaticTokenAuthenticator;
+pub use auth_interceptor::AuthInterceptor;
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Principal {
+    pub name: String,
+    pub roles: Vec<String>,
+}
+
+impl Principal {
+    pub fn new<T: Into<String>>(name: T) -> Principal {
+        Principal { name: name.into(), roles: vec![] }
+    }
+
+    pub fn with_roles<T: Into<String>>(name: T, roles: Vec<String>) -> Principal {
+        Principal { name: name.into(), roles }
+    }
+
+    /// ACL members matching this principal, `user:<name>` followed by `role:<role>` for each role.
+    pub fn get_members(&self) -> Vec<String> {
+        let mut members = vec![format!("user:{}", self.name)];
+        members.extend(self.roles.iter().map(|role| format!("role:{}", role)));
+        members
+    }
+}
+
+impl Display for Principal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
\ No newline at end of file
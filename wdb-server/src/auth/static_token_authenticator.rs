@@ -0,0 +1,46 @@
+use std::{collections::HashMap, fs, io::{self, ErrorKind}, path::Path};
+
+use tonic::{metadata::MetadataMap, Status};
+
+use super::{bearer_token, Authenticator, Principal};
+
+pub struct StaticTokenAuthenticator {
+    tokens: HashMap<String, Principal>,
+}
+
+impl StaticTokenAuthenticator {
+    pub fn new(tokens: HashMap<String, Principal>) -> StaticTokenAuthenticator {
+        StaticTokenAuthenticator { tokens }
+    }
+
+    // File format: one `<token> <principal> [role,role]` entry per line, empty lines and lines starting with # are skipped.
+    pub fn from_file(path: &Path) -> io::Result<StaticTokenAuthenticator> {
+        let mut tokens = HashMap::new();
+
+        for line in fs::read_to_string(path)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+                [token, principal] => tokens.insert(token.to_string(), Principal::new(*principal)),
+                [token, principal, roles] => {
+                    let roles = roles.split(',').filter(|role| !role.is_empty()).map(|role| role.to_string()).collect();
+                    tokens.insert(token.to_string(), Principal::with_roles(*principal, roles))
+                },
+                _ => return Err(io::Error::new(ErrorKind::InvalidData, "Invalid token line. Expected `<token> <principal> [role,role]`.")),
+            };
+        }
+
+        Ok(StaticTokenAuthenticator::new(tokens))
+    }
+}
+
+impl Authenticator for StaticTokenAuthenticator {
+    fn authenticate(&self, metadata: &MetadataMap) -> Result<Principal, Status> {
+        let token = bearer_token(metadata).ok_or(Status::unauthenticated("Missing bearer token."))?;
+
+        self.tokens.get(token).cloned().ok_or(Status::unauthenticated("Invalid bearer token."))
+    }
+}
\ No newline at end of file
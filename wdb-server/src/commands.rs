@@ -0,0 +1,59 @@
+use std::{fs::File, io::{self, BufReader, BufWriter}, path::Path, process};
+
+use bytes::Bytes;
+use log::info;
+use wdb_storage_engine::{restore_backup, FSPersistance, StorageEngine};
+
+use crate::args::Command;
+
+pub fn run(command: Command, data_dir: &Path) {
+    match command {
+        Command::Restore { backup_dir } => restore(&backup_dir, data_dir),
+        Command::Export { table, file, start_row, end_row } => export(table, &file, start_row, end_row, data_dir),
+        Command::Import { table, file } => import(table, &file, data_dir),
+    }
+}
+
+fn restore(backup_dir: &Path, data_dir: &Path) {
+    match restore_backup(backup_dir, data_dir) {
+        Ok(manifest) => info!("Backup {} restored into {:?}.", manifest.id, data_dir),
+        Err(err) => exit_with_error("Unable to restore backup", &err.to_string()),
+    }
+}
+
+fn export(table: String, file: &str, start_row: Option<String>, end_row: Option<String>, data_dir: &Path) {
+    let table = Bytes::from(table);
+    let start = start_row.map(Bytes::from);
+    let end = end_row.map(Bytes::from);
+
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(data_dir), false);
+    let res = match file {
+        "-" => storage_engine.export_table(table, start, end, BufWriter::new(io::stdout().lock())),
+        file => storage_engine.export_table(table, start, end, BufWriter::new(File::create(file).unwrap())),
+    };
+
+    match res {
+        Ok(count) => info!("Exported {} cells.", count),
+        Err(err) => exit_with_error("Unable to export table", err),
+    }
+}
+
+fn import(table: String, file: &str, data_dir: &Path) {
+    let table = Bytes::from(table);
+
+    let storage_engine = StorageEngine::empty(FSPersistance::with_base(data_dir), false);
+    let res = match file {
+        "-" => storage_engine.import_table(table, io::stdin().lock()),
+        file => storage_engine.import_table(table, BufReader::new(File::open(file).unwrap())),
+    };
+
+    match res {
+        Ok(count) => info!("Imported {} cells.", count),
+        Err(err) => exit_with_error("Unable to import table", err),
+    }
+}
+
+fn exit_with_error(msg: &str, err: &str) -> ! {
+    eprintln!("{}: {}", msg, err);
+    process::exit(1);
+}
\ No newline at end of file
@@ -0,0 +1,233 @@
+use std::{collections::BTreeMap, fs, net::SocketAddr, path::{Path, PathBuf}, str::FromStr, sync::Arc};
+
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+use wdb_storage_engine::{EngineOptions, FSPersistance};
+
+use crate::{auth::StaticTokenAuthenticator, grpc::{GrpcOptions, TlsOptions}};
+
+/*
+Configuration sources, later ones override earlier ones:
+- built-in defaults,
+- TOML configuration file,
+- environment variables,
+- command line flags.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub listen_addr: SocketAddr,
+    pub data_dir: PathBuf,
+    /// `env_logger` filter, e.g. `info` or `warn,wdb_storage_engine=debug`.
+    pub log_level: String,
+    /// Time given to calls in flight to finish on shutdown.
+    pub shutdown_timeout_ms: u64,
+    pub engine: EngineOptions,
+    pub tls: Option<TlsOptions>,
+    pub auth: AuthConfig,
+    pub metrics: MetricsConfig,
+    pub quotas: QuotaConfig,
+    pub admin: AdminConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// Enables bearer token authentication with tokens listed in this file.
+    pub tokens_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// Serves Prometheus metrics over HTTP on `listen_addr`.
+    pub enabled: bool,
+    pub listen_addr: SocketAddr,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuotaConfig {
+    /// Limits of every table without an entry in `tables`.
+    pub default_table: QuotaLimits,
+    /// Limits of every authenticated principal without an entry in `principals`.
+    pub default_principal: QuotaLimits,
+    /// An entry replaces the default limits as a whole.
+    pub tables: BTreeMap<String, QuotaLimits>,
+    pub principals: BTreeMap<String, QuotaLimits>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    /// Backups are written only under this directory, none can be created while it is unset.
+    pub backup_root: Option<PathBuf>,
+    /// SSTables are ingested only from this directory, none can be ingested while it is unset.
+    pub ingest_dir: Option<PathBuf>,
+    /// Members holding Admin on the catalog and every table regardless of ACLs, e.g. `role:admins`.
+    /// They are the only ones able to set the first catalog policy.
+    pub admins: Vec<String>,
+}
+
+/// Unset limits are unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuotaLimits {
+    pub requests_per_sec: Option<u64>,
+    pub write_bytes_per_sec: Option<u64>,
+}
+
+impl ServerConfig {
+    pub const DEFAULT_LISTEN_ADDR: &'static str = "0.0.0.0:50051";
+    pub const DEFAULT_METRICS_LISTEN_ADDR: &'static str = "127.0.0.1:9464";
+
+    pub fn from_file<T: AsRef<Path>>(path: T) -> Result<ServerConfig, String> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|err| format!("Unable to read config file {:?}: {}", path, err))?;
+
+        toml::from_str(&content).map_err(|err| format!("Invalid config file {:?}: {}", path, err))
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).unwrap()
+    }
+
+    // Checks everything that can be checked before startup, so bad values fail fast instead of mid-run.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.data_dir.as_os_str().is_empty() {
+            return Err("Data directory must not be empty.".to_string());
+        }
+        if self.data_dir.exists() && !self.data_dir.is_dir() {
+            return Err(format!("Data directory {:?} is not a directory.", self.data_dir));
+        }
+
+        ServerConfig::validate_log_level(&self.log_level)?;
+        self.engine.validate().map_err(|err| err.to_string())?;
+
+        if let Some(tls) = &self.tls {
+            if tls.client_auth_optional && tls.client_ca_path.is_none() {
+                return Err("Optional client authentication requires a client CA.".to_string());
+            }
+            tls.load()?;
+        }
+
+        if let Some(tokens_file) = &self.auth.tokens_file {
+            if !tokens_file.is_file() {
+                return Err(format!("Tokens file {:?} does not exist.", tokens_file));
+            }
+        }
+
+        if self.metrics.enabled && self.metrics.listen_addr.port() == self.listen_addr.port() {
+            return Err("Metrics must listen on a different port than the grpc server.".to_string());
+        }
+
+        self.quotas.validate()?;
+
+        if let Some(backup_root) = &self.admin.backup_root {
+            if backup_root.exists() && !backup_root.is_dir() {
+                return Err(format!("Backup root {:?} is not a directory.", backup_root));
+            }
+        }
+        if let Some(ingest_dir) = &self.admin.ingest_dir {
+            if !ingest_dir.is_dir() {
+                return Err(format!("Ingest directory {:?} does not exist.", ingest_dir));
+            }
+        }
+        for admin in self.admin.admins.iter() {
+            let valid = ["user:", "role:"].iter().any(|prefix| admin.len() > prefix.len() && admin.starts_with(prefix));
+            if !valid {
+                return Err(format!("Invalid admin {:?}. Allowed formats are user:<name> and role:<name>.", admin));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn grpc_options(&self) -> Result<GrpcOptions, String> {
+        let authenticator = match &self.auth.tokens_file {
+            Some(path) => {
+                let authenticator = StaticTokenAuthenticator::from_file(path)
+                    .map_err(|err| format!("Unable to load tokens from {:?}: {}", path, err))?;
+                Some(Arc::new(authenticator) as Arc<_>)
+            },
+            None => None,
+        };
+
+        Ok(GrpcOptions { tls: self.tls.clone(), authenticator })
+    }
+
+    // Every directive must name a level, either alone or as `module=level`.
+    fn validate_log_level(log_level: &str) -> Result<(), String> {
+        for directive in log_level.split(',') {
+            let level = directive.rsplit('=').next().unwrap().trim();
+            if LevelFilter::from_str(level).is_err() {
+                return Err(format!("Invalid log level {:?}.", directive));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            listen_addr: SocketAddr::from_str(ServerConfig::DEFAULT_LISTEN_ADDR).unwrap(),
+            data_dir: FSPersistance::new().get_base(),
+            log_level: "info".to_string(),
+            shutdown_timeout_ms: 30000,
+            engine: EngineOptions::default(),
+            tls: None,
+            auth: AuthConfig::default(),
+            metrics: MetricsConfig::default(),
+            quotas: QuotaConfig::default(),
+            admin: AdminConfig::default(),
+        }
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            enabled: true,
+            listen_addr: SocketAddr::from_str(ServerConfig::DEFAULT_METRICS_LISTEN_ADDR).unwrap(),
+        }
+    }
+}
+
+impl QuotaConfig {
+    pub fn get_table_limits(&self, table: &str) -> &QuotaLimits {
+        self.tables.get(table).unwrap_or(&self.default_table)
+    }
+
+    pub fn get_principal_limits(&self, principal: &str) -> &QuotaLimits {
+        self.principals.get(principal).unwrap_or(&self.default_principal)
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        [&self.default_table, &self.default_principal].into_iter()
+            .chain(self.tables.values())
+            .chain(self.principals.values())
+            .all(|limits| limits.is_unlimited())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let limits = [("the default table".to_string(), &self.default_table), ("the default principal".to_string(), &self.default_principal)].into_iter()
+            .chain(self.tables.iter().map(|(name, limits)| (format!("table {}", name), limits)))
+            .chain(self.principals.iter().map(|(name, limits)| (format!("principal {}", name), limits)));
+
+        for (name, limits) in limits {
+            if limits.requests_per_sec == Some(0) || limits.write_bytes_per_sec == Some(0) {
+                return Err(format!("Quota of {} must be positive, leave it unset for no limit.", name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl QuotaLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.requests_per_sec.is_none() && self.write_bytes_per_sec.is_none()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{wide_db_admin_server::WideDbAdmin, *};
+use wdb_storage_engine::PersistanceLayer;
+
+use crate::server_ctx::ServerCtx;
+
+use super::handlers;
+
+pub struct AdminHandlersService<P: PersistanceLayer> {
+    server_ctx: ServerCtx<P>,
+}
+
+impl<P: PersistanceLayer> AdminHandlersService<P> {
+    pub fn new(ctx: ServerCtx<P>) -> AdminHandlersService<P> {
+        AdminHandlersService { server_ctx: ctx }
+    }
+}
+
+#[tonic::async_trait]
+impl<P: PersistanceLayer> WideDbAdmin for AdminHandlersService<P> {
+    async fn get_table_stats(&self, request: Request<GetTableStatsRequest>) -> Result<Response<TableStats>, Status> {
+        handlers::get_table_stats(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn create_backup(&self, request: Request<CreateBackupRequest>) -> Result<Response<BackupInfo>, Status> {
+        handlers::create_backup(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn ingest_ss_tables(&self, request: Request<IngestSsTablesRequest>) -> Result<Response<IngestSsTablesResponse>, Status> {
+        handlers::ingest_sstables(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn set_iam_policy(&self, request: Request<SetIamPolicyRequest>) -> Result<Response<IamPolicy>, Status> {
+        handlers::set_iam_policy(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn get_iam_policy(&self, request: Request<GetIamPolicyRequest>) -> Result<Response<IamPolicy>, Status> {
+        handlers::get_iam_policy(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn list_segments(&self, request: Request<ListSegmentsRequest>) -> Result<Response<ListSegmentsResponse>, Status> {
+        handlers::list_segments(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn flush_table(&self, request: Request<FlushTableRequest>) -> Result<Response<FlushTableResponse>, Status> {
+        handlers::flush_table(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn compact_range(&self, request: Request<CompactRangeRequest>) -> Result<Response<CompactRangeResponse>, Status> {
+        handlers::compact_range(&self.server_ctx.with_request(&request), request).await
+    }
+}
\ No newline at end of file
@@ -1,35 +1,104 @@
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{net::SocketAddr, sync::{atomic::AtomicBool, Arc}, time::Duration};
 
-use tonic::transport::Server;
-use wdb_grpc::wdb_grpc::{wide_db_server::WideDbServer, FILE_DESCRIPTOR_SET};
+use tokio::{net::TcpListener, sync::oneshot, task::JoinHandle, time::timeout};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{server::Router, Server};
+use tonic_health::pb::health_server::{Health, HealthServer};
+use tower::layer::util::{Identity, Stack};
+use wdb_grpc::wdb_grpc::{wide_db_admin_server::WideDbAdminServer, wide_db_server::WideDbServer, FILE_DESCRIPTOR_SET};
 use wdb_storage_engine::PersistanceLayer;
 
-use crate::{grpc::HandlersService, server_ctx::ServerCtx};
+use crate::{auth::AuthInterceptor, grpc::{AdminHandlersService, GrpcOptions, HandlersService}, health::{ReadinessLayer, ServerHealth}, metrics::RpcMetricsLayer, server_ctx::ServerCtx};
 
-pub struct GrpcApi {
-    
+type GrpcRouter = Router<Stack<ReadinessLayer, Stack<RpcMetricsLayer, Identity>>>;
+
+pub struct GrpcApi<P: PersistanceLayer> {
+    health: Arc<ServerHealth<P>>,
+    shutdown: oneshot::Sender<()>,
+    server: JoinHandle<()>,
 }
 
-impl GrpcApi {
-    pub fn init<P: PersistanceLayer>(server_ctx: ServerCtx<P>) -> GrpcApi {
-        const DEFAULT_PORT: u16 = 50051;
-        const IP_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0));
-        const SOCKET_ADDR: SocketAddr = SocketAddr::new(IP_ADDR, DEFAULT_PORT);
+impl<P: PersistanceLayer> GrpcApi<P> {
+    // Binds before returning, so an address in use is reported instead of failing inside the server task.
+    pub fn init(server_ctx: ServerCtx<P>, addr: SocketAddr, options: &GrpcOptions) -> Result<GrpcApi<P>, String> {
+        let listener = std::net::TcpListener::bind(addr).map_err(|err| format!("Unable to bind {}: {}", addr, err))?;
+        listener.set_nonblocking(true).map_err(|err| format!("Unable to configure listener on {}: {}", addr, err))?;
+        let listener = TcpListener::from_std(listener).map_err(|err| format!("Unable to register listener on {}: {}", addr, err))?;
+
+        GrpcApi::init_with_listener(server_ctx, options, listener)
+    }
+
+    // Serves on an already bound listener, so embedders and tests can pick the port themselves.
+    pub fn init_with_listener(server_ctx: ServerCtx<P>, options: &GrpcOptions, listener: TcpListener) -> Result<GrpcApi<P>, String> {
+        let (reporter, health_service) = tonic_health::server::health_reporter();
+        let health = Arc::new(ServerHealth::new(server_ctx.storage_engine.clone(), reporter));
+        let router = GrpcApi::router(server_ctx, options, health_service, health.get_accepting_flag())?;
 
+        let (shutdown, shutdown_signal) = oneshot::channel::<()>();
+        let monitor = health.clone();
+        let server = tokio::spawn(async move {
+            // Statuses are in place before the first connection is accepted.
+            monitor.update().await;
+            monitor.start_monitor();
+
+            // Dropping the api handle keeps the server running, only an explicit shutdown stops it.
+            let shutdown_signal = async {
+                if shutdown_signal.await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            };
+            router.serve_with_incoming_shutdown(TcpListenerStream::new(listener), shutdown_signal).await.unwrap();
+        });
+
+        Ok(GrpcApi { health, shutdown, server })
+    }
+
+    /*
+    Shutdown steps:
+    - health turns NOT_SERVING and new calls are rejected with UNAVAILABLE,
+    - the listener is closed and clients are asked to go away,
+    - calls in flight get `drain_timeout` to finish, then are cancelled.
+    Returns false when calls had to be cancelled.
+     */
+    pub async fn shutdown(mut self, drain_timeout: Duration) -> bool {
+        self.health.set_shutting_down().await;
+        let _ = self.shutdown.send(());
+
+        match timeout(drain_timeout, &mut self.server).await {
+            Ok(_) => true,
+            Err(_) => {
+                self.server.abort();
+                false
+            },
+        }
+    }
+
+    pub fn get_health(&self) -> &Arc<ServerHealth<P>> {
+        &self.health
+    }
+
+    fn router(server_ctx: ServerCtx<P>, options: &GrpcOptions, health_service: HealthServer<impl Health>, accepting: Arc<AtomicBool>) -> Result<GrpcRouter, String> {
         let grpc_service = tonic_reflection::server::Builder::configure()
             .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
             .build()
             .unwrap();
 
-        let handlers_service = HandlersService::new(server_ctx);
+        let handlers_service = HandlersService::new(server_ctx.clone());
+        let admin_handlers_service = AdminHandlersService::new(server_ctx);
 
-        tokio::spawn(async {
-            Server::builder()
-                .add_service(WideDbServer::new(handlers_service))
-                .add_service(grpc_service)
-                .serve(SOCKET_ADDR).await.unwrap();
-        });
+        let mut builder = Server::builder();
+        if let Some(tls) = &options.tls {
+            builder = builder.tls_config(tls.load()?).map_err(|err| format!("Invalid TLS configuration: {}", err))?;
+        }
+        let mut builder = builder.layer(RpcMetricsLayer::default()).layer(ReadinessLayer::new(accepting));
 
-        GrpcApi {  }
+        // Reflection and health only describe the server, so they stay reachable without credentials.
+        let interceptor = AuthInterceptor::new(options.authenticator.clone());
+        Ok(builder
+            .add_service(WideDbServer::with_interceptor(handlers_service, interceptor.clone()))
+            .add_service(WideDbAdminServer::with_interceptor(admin_handlers_service, interceptor))
+            .add_service(health_service)
+            .add_service(grpc_service))
     }
 }
\ No newline at end of file
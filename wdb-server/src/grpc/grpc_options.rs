@@ -0,0 +1,41 @@
+use std::{fs, path::PathBuf, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
+
+use crate::auth::Authenticator;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsOptions {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// Enables mTLS, clients must present a certificate signed by this CA.
+    pub client_ca_path: Option<PathBuf>,
+    /// Accepts clients without a certificate while still verifying the ones that present one.
+    #[serde(default)]
+    pub client_auth_optional: bool,
+}
+
+impl TlsOptions {
+    pub fn load(&self) -> Result<ServerTlsConfig, String> {
+        let read = |path: &PathBuf| fs::read(path).map_err(|err| format!("Unable to read {:?}: {}", path, err));
+
+        let mut config = ServerTlsConfig::new()
+            .identity(Identity::from_pem(read(&self.cert_path)?, read(&self.key_path)?));
+
+        if let Some(client_ca_path) = &self.client_ca_path {
+            config = config
+                .client_ca_root(Certificate::from_pem(read(client_ca_path)?))
+                .client_auth_optional(self.client_auth_optional);
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct GrpcOptions {
+    pub tls: Option<TlsOptions>,
+    pub authenticator: Option<Arc<dyn Authenticator>>,
+}
\ No newline at end of file
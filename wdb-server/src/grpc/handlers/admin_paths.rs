@@ -0,0 +1,44 @@
+use std::{io, path::{Component, Path, PathBuf}};
+
+use tonic::Status;
+
+/*
+Paths sent by callers are relative to a root configured on the server:
+- absolute paths and `..` are rejected,
+- symlinks are resolved, the path must still be under the root afterwards,
+- trailing components that don't exist yet are kept as they are, they are created later.
+ */
+pub fn resolve_admin_path(root: &Path, path: &str) -> Result<PathBuf, Status> {
+    let relative = Path::new(path);
+    if relative.components().any(|component| !matches!(component, Component::Normal(_) | Component::CurDir)) {
+        return Err(Status::invalid_argument(format!("Invalid path {:?}. Path must be relative to the configured root and cannot contain `..`.", path)));
+    }
+
+    let root = root.canonicalize().map_err(|err| Status::failed_precondition(format!("Configured root {:?} is not accessible: {}", root, err)))?;
+    let path = root.join(relative);
+
+    // Dangling symlinks count as existing, canonicalize then refuses them.
+    let mut existing = path.as_path();
+    while existing.symlink_metadata().is_err() {
+        existing = existing.parent().unwrap();
+    }
+    let resolved = existing.canonicalize().map_err(|err| Status::invalid_argument(format!("Invalid path {:?}: {}", path, err)))?;
+    if !resolved.starts_with(&root) {
+        return Err(Status::permission_denied(format!("Path {:?} is outside of the configured root.", path)));
+    }
+
+    match path.strip_prefix(existing).unwrap() {
+        missing if missing.as_os_str().is_empty() => Ok(resolved),
+        missing => Ok(resolved.join(missing)),
+    }
+}
+
+pub fn io_error_to_status(err: io::Error) -> Status {
+    match err.kind() {
+        io::ErrorKind::NotFound => Status::not_found(err.to_string()),
+        io::ErrorKind::InvalidInput => Status::invalid_argument(err.to_string()),
+        io::ErrorKind::AlreadyExists => Status::already_exists(err.to_string()),
+        io::ErrorKind::InvalidData => Status::data_loss(err.to_string()),
+        _ => Status::internal(err.to_string()),
+    }
+}
\ No newline at end of file
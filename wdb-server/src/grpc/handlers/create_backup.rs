@@ -0,0 +1,37 @@
+use std::fs;
+
+use log::info;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{BackupInfo, CreateBackupRequest};
+use wdb_storage_engine::{Permission, PersistanceLayer};
+
+use crate::server_ctx::ServerCtx;
+
+use super::admin_paths::{io_error_to_status, resolve_admin_path};
+
+pub async fn create_backup<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<CreateBackupRequest>) -> Result<Response<BackupInfo>, Status> {
+    let request = request.into_inner();
+    ctx.check_catalog_permission(Permission::Admin)?;
+    ctx.check_principal_quota()?;
+
+    let backup_root = ctx.admin.backup_root.as_ref()
+        .ok_or(Status::failed_precondition("Backups are disabled, backup root is not configured."))?;
+    fs::create_dir_all(backup_root).map_err(io_error_to_status)?;
+    let backup_root = resolve_admin_path(backup_root, &request.backup_root)?;
+
+    let parent = Some(request.parent_backup_id).filter(|parent| !parent.is_empty());
+
+    let storage_engine = ctx.storage_engine.clone();
+    let manifest = tokio::task::spawn_blocking(move || storage_engine.create_backup(&backup_root, parent.as_deref())).await.unwrap()
+        .map_err(io_error_to_status)?;
+    info!("Backup {} created by {}", manifest.id, ctx.get_principal_name());
+
+    Ok(Response::new(BackupInfo {
+        backup_id: manifest.id.clone(),
+        parent_backup_id: manifest.parent.clone().unwrap_or_default(),
+        created_at: manifest.created_at,
+        tables_count: manifest.tables.len() as u64,
+        segments_count: manifest.get_segments_count() as u64,
+        size_bytes: manifest.get_size(),
+    }))
+}
\ No newline at end of file
@@ -3,12 +3,16 @@ use std::collections::HashSet;
 use bytes::Bytes;
 use tonic::{Request, Response, Status};
 use wdb_grpc::wdb_grpc::{CreateTableRequest, Table};
-use wdb_storage_engine::PersistanceLayer;
+use wdb_storage_engine::{FamilyOptions, Permission, PersistanceLayer, TableOptions};
 
 use crate::server_ctx::ServerCtx;
 
+use super::set_change_stream_config::change_feed_options_from_proto;
+
 pub async fn create_table<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<CreateTableRequest>) -> Result<Response<Table>, Status> {
     let request = request.into_inner();
+    ctx.check_catalog_permission(Permission::Write)?;
+    ctx.check_principal_quota()?;
     
     if request.table_name.len() <= 0 || request.table_name.len() > 64 {
         return Err(Status::invalid_argument(
@@ -27,17 +31,25 @@ pub async fn create_table<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Requ
         families_set.insert(name);
     }
     
-    ctx.storage_engine.create_table(table_name.clone()).unwrap();
-    let mut table = ctx.storage_engine.get_table(table_name).unwrap();
+    let options = TableOptions {
+        change_feed: request.change_stream_config.map(change_feed_options_from_proto),
+        acl: None,
+    };
+
+    ctx.storage_engine.create_table_with_options(table_name.clone(), options)
+        .map_err(Status::already_exists)?;
+    // A concurrent DeleteTable may already have dropped the new table.
+    let table = ctx.storage_engine.get_table(table_name)
+        .ok_or_else(|| Status::not_found("Table with this name does not exist."))?;
 
     for family_name in families_set {
-        table.create_family(Bytes::from(family_name)).unwrap();
+        table.add_family(ctx.storage_engine.get_persitance_layer(), Bytes::from(family_name), FamilyOptions::default()).unwrap();
     }
 
     Ok(Response::new(Table { 
-        name: std::str::from_utf8(&table.get_name()).unwrap().to_string(),
+        name: String::from_utf8_lossy(&table.get_name()).to_string(),
         column_families: table.get_families_iter().map(|family| {
-            std::str::from_utf8(&family.get_name()).unwrap().to_string()
+            String::from_utf8_lossy(&family.get_name()).to_string()
         }).collect()
     }))
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use bytes::Bytes;
+use log::info;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::DeleteTableRequest;
+use wdb_storage_engine::{Permission, PersistanceLayer};
+
+use crate::server_ctx::ServerCtx;
+
+pub async fn delete_table<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<DeleteTableRequest>) -> Result<Response<()>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Admin)?;
+    ctx.check_quota(&request.table_name, 0)?;
+
+    ctx.storage_engine.drop_table(Bytes::from(request.table_name.clone()))
+        .map_err(Status::not_found)?;
+    info!("Table {} deleted by {}", request.table_name, ctx.get_principal_name());
+
+    Ok(Response::new(()))
+}
\ No newline at end of file
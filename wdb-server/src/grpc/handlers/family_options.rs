@@ -0,0 +1,51 @@
+use wdb_grpc::wdb_grpc::{BloomFilter, ColumnFamilyOptions, Compression as CompressionProto, GcPolicy as GcPolicyProto};
+use wdb_storage_engine::{BloomFilterOptions, Compression, FamilyOptions, GcPolicy};
+
+pub fn family_options_from_proto(options: Option<ColumnFamilyOptions>) -> FamilyOptions {
+    let options = match options {
+        Some(options) => options,
+        None => return FamilyOptions::default(),
+    };
+
+    let gc_policy = options.gc_policy.map(|gc_policy| GcPolicy {
+        max_versions: if gc_policy.max_versions > 0 { Some(gc_policy.max_versions) } else { None },
+        max_age_ms: if gc_policy.max_age_ms > 0 { Some(gc_policy.max_age_ms) } else { None },
+    }).unwrap_or_default();
+
+    let compression = match CompressionProto::try_from(options.compression).unwrap_or(CompressionProto::None) {
+        CompressionProto::None => Compression::None,
+        CompressionProto::Lz4 => Compression::Lz4,
+        CompressionProto::Zstd => Compression::Zstd,
+    };
+
+    // Bits per key left unset keep the default.
+    let bloom_filter = options.bloom_filter.map(|bloom_filter| BloomFilterOptions {
+        enabled: bloom_filter.enabled,
+        bits_per_key: match bloom_filter.bits_per_key {
+            0 => BloomFilterOptions::default().bits_per_key,
+            bits_per_key => bits_per_key,
+        },
+    }).unwrap_or_default();
+
+    FamilyOptions { gc_policy, compression, bloom_filter }
+}
+
+pub fn family_options_to_proto(options: &FamilyOptions) -> ColumnFamilyOptions {
+    let compression = match options.compression {
+        Compression::None => CompressionProto::None,
+        Compression::Lz4 => CompressionProto::Lz4,
+        Compression::Zstd => CompressionProto::Zstd,
+    };
+
+    ColumnFamilyOptions {
+        gc_policy: Some(GcPolicyProto {
+            max_versions: options.gc_policy.max_versions.unwrap_or(0),
+            max_age_ms: options.gc_policy.max_age_ms.unwrap_or(0),
+        }),
+        compression: compression as i32,
+        bloom_filter: Some(BloomFilter {
+            enabled: options.bloom_filter.enabled,
+            bits_per_key: options.bloom_filter.bits_per_key,
+        }),
+    }
+}
\ No newline at end of file
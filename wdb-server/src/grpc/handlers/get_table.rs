@@ -0,0 +1,37 @@
+use bytes::Bytes;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{ColumnFamilyDetails, GetTableRequest, TableDetails};
+use wdb_storage_engine::{Permission, PersistanceLayer};
+
+use crate::server_ctx::ServerCtx;
+
+use super::family_options::family_options_to_proto;
+
+pub async fn get_table<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<GetTableRequest>) -> Result<Response<TableDetails>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Read)?;
+    ctx.check_quota(&request.table_name, 0)?;
+
+    let table = ctx.storage_engine.get_table(Bytes::from(request.table_name))
+        .ok_or(Status::not_found("Table with this name does not exist."))?;
+
+    let column_families: Vec<ColumnFamilyDetails> = table.get_families_iter().map(|family| {
+        ColumnFamilyDetails {
+            name: String::from_utf8_lossy(&family.get_name()).to_string(),
+            options: Some(family_options_to_proto(&family.get_options())),
+            segments_count: family.get_segments_count() as u64,
+            disk_size: family.get_disk_size(),
+            memtable_size: family.get_memtable_size(),
+        }
+    }).collect();
+
+    Ok(Response::new(TableDetails {
+        name: String::from_utf8_lossy(&table.get_name()).to_string(),
+        segments_count: column_families.iter().map(|family| family.segments_count).sum(),
+        disk_size: column_families.iter().map(|family| family.disk_size).sum(),
+        memtable_size: column_families.iter().map(|family| family.memtable_size).sum(),
+        mvcc_read_point: table.mvcc_get_read_point(),
+        mvcc_write_point: table.mvcc_get_write_point(),
+        column_families,
+    }))
+}
\ No newline at end of file
@@ -0,0 +1,43 @@
+use bytes::Bytes;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{ColumnFamilyStats, GetTableStatsRequest, Statistics as StatisticsProto, TableStats};
+use wdb_storage_engine::{Permission, PersistanceLayer, Statistics};
+
+use crate::server_ctx::ServerCtx;
+
+pub async fn get_table_stats<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<GetTableStatsRequest>) -> Result<Response<TableStats>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Read)?;
+    ctx.check_quota(&request.table_name, 0)?;
+
+    let (stats, families_stats) = ctx.storage_engine.get_table_stats(Bytes::from(request.table_name.clone()))
+        .map_err(Status::not_found)?;
+
+    Ok(Response::new(TableStats {
+        name: request.table_name,
+        stats: Some(statistics_to_proto(stats)),
+        column_families: families_stats.into_iter().map(|(name, stats)| {
+            ColumnFamilyStats {
+                name: String::from_utf8_lossy(&name).to_string(),
+                stats: Some(statistics_to_proto(stats)),
+            }
+        }).collect()
+    }))
+}
+
+fn statistics_to_proto(stats: Statistics) -> StatisticsProto {
+    StatisticsProto {
+        cell_count: stats.cell_count,
+        row_count: stats.row_count,
+        put_count: stats.put_count,
+        delete_count: stats.delete_count,
+        delete_column_count: stats.delete_column_count,
+        delete_family_count: stats.delete_family_count,
+        min_timestamp: stats.min_timestamp,
+        max_timestamp: stats.max_timestamp,
+        min_row_key: stats.min_row.map(|row| row.to_vec()),
+        max_row_key: stats.max_row.map(|row| row.to_vec()),
+        raw_bytes: stats.raw_bytes,
+        disk_bytes: stats.disk_bytes,
+    }
+}
\ No newline at end of file
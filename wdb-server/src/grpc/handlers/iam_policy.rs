@@ -0,0 +1,80 @@
+use bytes::Bytes;
+use log::info;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{AclEntry as ProtoAclEntry, GetIamPolicyRequest, IamPolicy, Permission as ProtoPermission, SetIamPolicyRequest};
+use wdb_storage_engine::{Acl, AclEntry, PersistanceLayer, Permission};
+
+use crate::server_ctx::ServerCtx;
+
+pub async fn set_iam_policy<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<SetIamPolicyRequest>) -> Result<Response<IamPolicy>, Status> {
+    let request = request.into_inner();
+
+    let acl = request.policy.map(acl_from_proto).transpose()?;
+
+    if request.table_name.is_empty() {
+        ctx.check_catalog_permission(Permission::Admin)?;
+        ctx.check_principal_quota()?;
+        ctx.storage_engine.set_catalog_acl(acl.clone());
+        info!("Catalog policy changed by {}", ctx.get_principal_name());
+    } else {
+        ctx.check_table_permission(&request.table_name, Permission::Admin)?;
+        ctx.check_quota(&request.table_name, 0)?;
+        ctx.storage_engine.set_table_acl(Bytes::from(request.table_name.clone()), acl.clone())
+            .map_err(Status::not_found)?;
+        info!("Table {} policy changed by {}", request.table_name, ctx.get_principal_name());
+    }
+
+    Ok(Response::new(acl_to_proto(acl.as_ref())))
+}
+
+pub async fn get_iam_policy<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<GetIamPolicyRequest>) -> Result<Response<IamPolicy>, Status> {
+    let request = request.into_inner();
+
+    let acl = if request.table_name.is_empty() {
+        ctx.check_catalog_permission(Permission::Admin)?;
+        ctx.check_principal_quota()?;
+        ctx.storage_engine.get_catalog_acl().map(|acl| (*acl).clone())
+    } else {
+        ctx.check_table_permission(&request.table_name, Permission::Admin)?;
+        ctx.check_quota(&request.table_name, 0)?;
+        ctx.storage_engine.get_table_acl(Bytes::from(request.table_name))
+            .map_err(Status::not_found)?
+    };
+
+    Ok(Response::new(acl_to_proto(acl.as_ref())))
+}
+
+fn acl_from_proto(policy: IamPolicy) -> Result<Acl, Status> {
+    let entries = policy.entries.into_iter().map(|entry| {
+        let valid_member = entry.member == Acl::ALL_MEMBERS
+            || ["user:", "role:"].iter().any(|prefix| entry.member.len() > prefix.len() && entry.member.starts_with(prefix));
+        if !valid_member {
+            return Err(Status::invalid_argument("Invalid member. Allowed formats are user:<name>, role:<name> and *."));
+        }
+
+        let permission = match entry.permission() {
+            ProtoPermission::Read => Permission::Read,
+            ProtoPermission::Write => Permission::Write,
+            ProtoPermission::Admin => Permission::Admin,
+            ProtoPermission::Unspecified => return Err(Status::invalid_argument("Invalid permission. Permission must be specified.")),
+        };
+
+        Ok(AclEntry { member: entry.member, permission })
+    }).collect::<Result<Vec<AclEntry>, Status>>()?;
+
+    Ok(Acl::new(entries))
+}
+
+fn acl_to_proto(acl: Option<&Acl>) -> IamPolicy {
+    IamPolicy {
+        entries: acl.map_or(vec![], |acl| acl.entries.iter().map(|entry| {
+            let permission = match entry.permission {
+                Permission::Read => ProtoPermission::Read,
+                Permission::Write => ProtoPermission::Write,
+                Permission::Admin => ProtoPermission::Admin,
+            };
+
+            ProtoAclEntry { member: entry.member.clone(), permission: permission as i32 }
+        }).collect()),
+    }
+}
\ No newline at end of file
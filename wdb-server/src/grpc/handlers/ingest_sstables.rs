@@ -0,0 +1,41 @@
+use std::fs::File;
+
+use bytes::Bytes;
+use log::info;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{IngestSsTablesRequest, IngestSsTablesResponse};
+use wdb_storage_engine::{Permission, PersistanceLayer};
+
+use crate::server_ctx::ServerCtx;
+
+use super::admin_paths::resolve_admin_path;
+
+pub async fn ingest_sstables<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<IngestSsTablesRequest>) -> Result<Response<IngestSsTablesResponse>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Admin)?;
+
+    if request.paths.is_empty() {
+        return Err(Status::invalid_argument("At least one SSTable path is required."));
+    }
+
+    let ingest_dir = ctx.admin.ingest_dir.as_ref()
+        .ok_or(Status::failed_precondition("Ingestion is disabled, ingest directory is not configured."))?;
+    let files = request.paths.iter().map(|path| {
+        let resolved = resolve_admin_path(ingest_dir, path)?;
+        File::open(resolved).map_err(|err| Status::not_found(format!("Unable to open {}: {}", path, err)))
+    }).collect::<Result<Vec<File>, Status>>()?;
+
+    // Ingested segments are charged as written bytes, like the cells they carry.
+    let write_bytes = files.iter().map(|file| file.metadata().map_or(0, |metadata| metadata.len())).sum();
+    ctx.check_quota(&request.table_name, write_bytes)?;
+
+    let sstables = ctx.storage_engine.ingest_sstables(Bytes::from(request.table_name.clone()), Bytes::from(request.family_name), files)
+        .map_err(Status::failed_precondition)?;
+    info!("{} segments ingested into table {} by {}", sstables.len(), request.table_name, ctx.get_principal_name());
+
+    Ok(Response::new(IngestSsTablesResponse {
+        segments: sstables.iter().map(|sstable| String::from_utf8_lossy(sstable.get_segment()).to_string()).collect(),
+        cell_count: sstables.iter().map(|sstable| sstable.get_stats().map_or(0, |stats| stats.cell_count)).sum(),
+        size_bytes: sstables.iter().map(|sstable| sstable.get_size()).sum(),
+    }))
+}
\ No newline at end of file
@@ -0,0 +1,24 @@
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{ListTablesResponse, Table};
+use wdb_storage_engine::{Permission, PersistanceLayer};
+
+use crate::server_ctx::ServerCtx;
+
+pub async fn list_tables<P: PersistanceLayer>(ctx: &ServerCtx<P>, _request: Request<()>) -> Result<Response<ListTablesResponse>, Status> {
+    ctx.check_principal_quota()?;
+
+    // Only tables the caller can read are listed.
+    let tables = ctx.storage_engine.get_tables_iter().filter_map(|table| {
+        let name = String::from_utf8_lossy(&table.get_name()).to_string();
+        ctx.check_table_acl(&name, table.get_options().acl.as_ref(), Permission::Read).ok()?;
+
+        Some(Table {
+            name,
+            column_families: table.get_families_iter().map(|family| {
+                String::from_utf8_lossy(&family.get_name()).to_string()
+            }).collect()
+        })
+    }).collect();
+
+    Ok(Response::new(ListTablesResponse { tables }))
+}
\ No newline at end of file
@@ -1,7 +1,37 @@
 mod create_table;
 mod row_mutate;
 mod read_row;
+mod read_rows;
+mod delete_table;
+mod modify_column_families;
+mod family_options;
+mod list_tables;
+mod get_table;
+mod sample_row_keys;
+mod get_table_stats;
+mod set_change_stream_config;
+mod read_change_stream;
+mod watch;
+mod create_backup;
+mod ingest_sstables;
+mod iam_policy;
+mod segments;
+mod admin_paths;
 
 pub use create_table::create_table;
 pub use row_mutate::row_mutate;
-pub use read_row::read_row;
\ No newline at end of file
+pub use read_row::read_row;
+pub use read_rows::read_rows;
+pub use delete_table::delete_table;
+pub use modify_column_families::modify_column_families;
+pub use list_tables::list_tables;
+pub use get_table::get_table;
+pub use sample_row_keys::sample_row_keys;
+pub use get_table_stats::get_table_stats;
+pub use set_change_stream_config::set_change_stream_config;
+pub use read_change_stream::read_change_stream;
+pub use watch::watch;
+pub use create_backup::create_backup;
+pub use ingest_sstables::ingest_sstables;
+pub use iam_policy::{set_iam_policy, get_iam_policy};
+pub use segments::{list_segments, flush_table, compact_range};
\ No newline at end of file
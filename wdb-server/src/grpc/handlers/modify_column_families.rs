@@ -0,0 +1,57 @@
+use bytes::Bytes;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{column_family_modification::Mod, ModifyColumnFamiliesRequest, Table};
+use wdb_storage_engine::{FamilyModification, Permission, PersistanceLayer};
+
+use crate::server_ctx::ServerCtx;
+
+use super::family_options::family_options_from_proto;
+
+pub async fn modify_column_families<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<ModifyColumnFamiliesRequest>) -> Result<Response<Table>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Admin)?;
+    ctx.check_quota(&request.table_name, 0)?;
+    let table_name = Bytes::from(request.table_name);
+
+    let mut modifications = vec![];
+    for modification in request.modifications {
+        if modification.family_name.len() > 64 {
+            return Err(Status::invalid_argument(
+                "Invalid family name. Family name cannot be bigger than 64 bytes."
+            ));
+        }
+        let family = Bytes::from(modification.family_name);
+
+        match modification.r#mod {
+            Some(Mod::Drop(true)) => {
+                modifications.push(FamilyModification::Drop { family });
+            },
+            Some(Mod::Drop(false)) => {},
+            Some(Mod::Create(options)) => {
+                modifications.push(FamilyModification::Create { family, options: family_options_from_proto(Some(options)) });
+            },
+            Some(Mod::Update(options)) => {
+                modifications.push(FamilyModification::Update { family, options: family_options_from_proto(Some(options)) });
+            },
+            None => {
+                return Err(Status::invalid_argument("Column family modification is missing."));
+            },
+        }
+    }
+
+    if ctx.storage_engine.get_table(table_name.clone()).is_none() {
+        return Err(Status::not_found("Table with this name does not exist."));
+    }
+    ctx.storage_engine.modify_column_families(table_name.clone(), modifications)
+        .map_err(Status::failed_precondition)?;
+
+    let table = ctx.storage_engine.get_table(table_name)
+        .ok_or(Status::not_found("Table with this name does not exist."))?;
+
+    Ok(Response::new(Table { 
+        name: String::from_utf8_lossy(&table.get_name()).to_string(),
+        column_families: table.get_families_iter().map(|family| {
+            String::from_utf8_lossy(&family.get_name()).to_string()
+        }).collect()
+    }))
+}
\ No newline at end of file
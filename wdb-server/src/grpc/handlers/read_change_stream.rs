@@ -0,0 +1,119 @@
+use bytes::Bytes;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{mutation, DataChange, DeleteCell, DeleteColumn, DeleteFamily, Mutation, PutCell, ReadChangeStreamRequest, ReadChangeStreamResponse};
+use wdb_storage_engine::{Authorizations, ColumnVisibility, Permission, PersistanceLayer, RowMutationOp, Timestamp};
+
+use crate::server_ctx::ServerCtx;
+
+const BATCH_SIZE: usize = 256;
+
+pub async fn read_change_stream<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<ReadChangeStreamRequest>) -> Result<Response<ReceiverStream<Result<ReadChangeStreamResponse, Status>>>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Read)?;
+    ctx.check_quota(&request.table_name, 0)?;
+    let table_name = Bytes::from(request.table_name);
+
+    let mut cursor = match request.continuation_token.as_str() {
+        "" => 0,
+        token => token.parse::<u64>().map_err(|_| Status::invalid_argument("Invalid continuation token."))?,
+    };
+
+    if ctx.storage_engine.get_table(table_name.clone()).is_none() {
+        return Err(Status::not_found("Table with this name does not exist."));
+    }
+    let mut changed = ctx.storage_engine.watch_change_stream(table_name.clone())
+        .map_err(Status::failed_precondition)?;
+    ctx.storage_engine.read_change_stream(table_name.clone(), cursor, 0)
+        .map_err(Status::out_of_range)?;
+
+    let storage_engine = ctx.storage_engine.clone();
+    let authorizations = ctx.get_authorizations();
+    let (tx, rx) = mpsc::channel(BATCH_SIZE);
+    tokio::spawn(async move {
+        loop {
+            changed.borrow_and_update();
+
+            let records = match storage_engine.read_change_stream(table_name.clone(), cursor, BATCH_SIZE) {
+                Ok(records) => records,
+                Err(err) => {
+                    let _ = tx.send(Err(Status::out_of_range(err))).await;
+                    return;
+                }
+            };
+
+            if records.is_empty() {
+                tokio::select! {
+                    res = changed.changed() => if res.is_err() { return; },
+                    _ = tx.closed() => return,
+                }
+                continue;
+            }
+
+            for record in records {
+                cursor = record.write_num;
+                let response = ReadChangeStreamResponse {
+                    continuation_token: record.write_num.to_string(),
+                    data_change: Some(data_change_to_proto(record.row, record.write_num, record.commit_ts, record.ops, &authorizations)),
+                };
+                if tx.send(Ok(response)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    Ok(Response::new(ReceiverStream::new(rx)))
+}
+
+// Puts the caller is not cleared for are left out, the same way scans filter them.
+pub fn data_change_to_proto(row: Bytes, write_num: u64, commit_ts: Timestamp, ops: Vec<RowMutationOp>, authorizations: &Authorizations) -> DataChange {
+    let get_timestamp = |ts: Option<Timestamp>| -> i64 {
+        let ts: u64 = Timestamp::ensure_timestamp(ts).into();
+        ts as i64
+    };
+
+    let is_visible = |op: &RowMutationOp| -> bool {
+        match op {
+            RowMutationOp::Put { visibility: Some(visibility), .. } => 
+                ColumnVisibility::parse(visibility).is_ok_and(|visibility| visibility.evaluate(authorizations)),
+            _ => true,
+        }
+    };
+
+    let mutations = ops.into_iter().filter(is_visible).map(|op| {
+        let mutation = match op {
+            RowMutationOp::Put { family, column, timestamp, value, visibility } => mutation::Mutation::PutCell(PutCell {
+                family_name: String::from_utf8_lossy(&family).to_string(),
+                column_name: column.to_vec(),
+                timestamp: get_timestamp(timestamp),
+                value: value.to_vec(),
+                visibility: String::from_utf8_lossy(&visibility.unwrap_or_default()).to_string(),
+            }),
+            RowMutationOp::DeleteCell { family, column, timestamp } => mutation::Mutation::DeleteCell(DeleteCell {
+                family_name: String::from_utf8_lossy(&family).to_string(),
+                column_name: column.to_vec(),
+                timestamp: get_timestamp(timestamp),
+            }),
+            RowMutationOp::DeleteColumn { family, column, timestamp } => mutation::Mutation::DeleteColumn(DeleteColumn {
+                family_name: String::from_utf8_lossy(&family).to_string(),
+                column_name: column.to_vec(),
+                timestamp: get_timestamp(timestamp),
+            }),
+            RowMutationOp::DeleteFamily { family, timestamp } => mutation::Mutation::DeleteFamily(DeleteFamily {
+                family_name: String::from_utf8_lossy(&family).to_string(),
+                timestamp: get_timestamp(timestamp),
+            }),
+        };
+        Mutation { mutation: Some(mutation) }
+    }).collect();
+
+    let commit_ts: u64 = commit_ts.into();
+    DataChange {
+        row_key: row.to_vec(),
+        write_num,
+        commit_timestamp: commit_ts as i64,
+        mutations,
+    }
+}
\ No newline at end of file
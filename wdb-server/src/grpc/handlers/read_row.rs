@@ -1,29 +1,33 @@
 use bytes::Bytes;
 use tonic::{Request, Response, Status};
 use wdb_grpc::wdb_grpc::{Cell, ReadRowRequest, ReadRowResponse};
-use wdb_storage_engine::{Cell as CellTrait, PersistanceLayer};
+use wdb_storage_engine::{Cell as CellTrait, Permission, PersistanceLayer};
 
 use crate::server_ctx::ServerCtx;
 
 pub async fn read_row<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<ReadRowRequest>) -> Result<Response<ReadRowResponse>, Status> {
     let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Read)?;
+    ctx.check_quota(&request.table_name, 0)?;
 
-    let result = ctx.storage_engine.read_row(
+    let result = ctx.storage_engine.read_row_with_authorizations(
         Bytes::from(request.table_name), 
         Bytes::from(request.row_key),
-        None
-    );
+        None,
+        ctx.get_authorizations()
+    ).map_err(Status::not_found)?;
     
     Ok(Response::new(ReadRowResponse { 
         cells: result.cells.iter().map(|cell| {
             let ts: u64 = cell.get_timestamp().into();
 
             Cell {
-                row_key: std::str::from_utf8(&cell.get_row()).unwrap().to_string(),
-                family: std::str::from_utf8(&cell.get_cf()).unwrap().to_string(),
-                column: std::str::from_utf8(&cell.get_col()).unwrap().to_string(),
+                row_key: cell.get_row().to_vec(),
+                family: String::from_utf8_lossy(cell.get_cf()).to_string(),
+                column: cell.get_col().to_vec(),
                 timestamp: ts as i64,
                 value: cell.get_value().to_vec(),
+                visibility: String::from_utf8_lossy(cell.get_visibility()).to_string(),
             }
         }).collect()
     }))
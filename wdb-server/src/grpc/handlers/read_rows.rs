@@ -0,0 +1,89 @@
+use bytes::Bytes;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{Cell, ReadRowsRequest, ReadRowsResponse};
+use wdb_storage_engine::{Cell as CellTrait, Permission, PersistanceLayer, TimeRange, Timestamp};
+
+use crate::server_ctx::ServerCtx;
+
+// Smallest row key greater than every key starting with the prefix, None when no such key exists.
+fn prefix_end(prefix: &[u8]) -> Option<Bytes> {
+    let mut end = prefix.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u8::MAX {
+            end.push(last + 1);
+            return Some(Bytes::from(end));
+        }
+    }
+
+    None
+}
+
+pub async fn read_rows<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<ReadRowsRequest>) -> Result<Response<ReadRowsResponse>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Read)?;
+    ctx.check_quota(&request.table_name, 0)?;
+
+    let mut start = match request.start_row.is_empty() {
+        true => None,
+        false => Some(Bytes::from(request.start_row)),
+    };
+    let mut end = match request.end_row.is_empty() {
+        true => None,
+        false => Some(Bytes::from(request.end_row)),
+    };
+
+    if !request.row_prefix.is_empty() {
+        let prefix = Bytes::from(request.row_prefix);
+        if start.as_ref().is_none_or(|start| *start < prefix) {
+            start = Some(prefix.clone());
+        }
+        if let Some(prefix_end) = prefix_end(&prefix) {
+            if end.as_ref().is_none_or(|end| *end > prefix_end) {
+                end = Some(prefix_end);
+            }
+        }
+    }
+
+    if request.start_timestamp < 0 || request.end_timestamp < 0 {
+        return Err(Status::invalid_argument("Timestamps must not be negative."));
+    }
+
+    let time_range = match (request.start_timestamp, request.end_timestamp) {
+        (0, 0) => None,
+        (start, 0) => Some(TimeRange::new(Timestamp::from(start as u64), Timestamp::MAX)),
+        (start, end) => Some(TimeRange::new(Timestamp::from(start as u64), Timestamp::from(end as u64))),
+    };
+
+    // The scan stops at the first cell past rows_limit matching rows, instead of reading the whole range.
+    let mut cells: Vec<Cell> = vec![];
+    let mut rows_count = 0;
+    ctx.storage_engine.read_rows_while(Bytes::from(request.table_name), start, end, time_range, ctx.get_authorizations(), |cell| {
+        if !request.family_name.is_empty() && cell.get_cf() != request.family_name.as_bytes() {
+            return true;
+        }
+        if !request.column_name.is_empty() && cell.get_col() != request.column_name.as_slice() {
+            return true;
+        }
+
+        let row = cell.get_row();
+        if cells.last().is_none_or(|last| last.row_key != row) {
+            if request.rows_limit > 0 && rows_count == request.rows_limit {
+                return false;
+            }
+            rows_count += 1;
+        }
+
+        let ts: u64 = cell.get_timestamp().into();
+        cells.push(Cell {
+            row_key: row.to_vec(),
+            family: String::from_utf8_lossy(cell.get_cf()).to_string(),
+            column: cell.get_col().to_vec(),
+            timestamp: ts as i64,
+            value: cell.get_value().to_vec(),
+            visibility: String::from_utf8_lossy(cell.get_visibility()).to_string(),
+        });
+        true
+    }).map_err(Status::not_found)?;
+
+    Ok(Response::new(ReadRowsResponse { cells }))
+}
\ No newline at end of file
@@ -1,12 +1,13 @@
 use bytes::Bytes;
 use tonic::{Request, Response, Status};
 use wdb_grpc::wdb_grpc::MutateRowRequest;
-use wdb_storage_engine::{PersistanceLayer, RowMutation, RowMutationOp, Timestamp};
+use wdb_storage_engine::{ColumnVisibility, Permission, PersistanceLayer, RowMutation, RowMutationOp, Timestamp};
 
 use crate::server_ctx::ServerCtx;
 
 pub async fn row_mutate<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<MutateRowRequest>) -> Result<Response<()>, Status> {
     let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Write)?;
 
     let get_timestamp = |val: i64| -> Result<Option<Timestamp>, Status> {
         if val == -1 {
@@ -23,11 +24,17 @@ pub async fn row_mutate<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Reques
     }).flatten().map(|mutation| -> Result<RowMutationOp, Status> {
         match mutation {
             wdb_grpc::wdb_grpc::mutation::Mutation::PutCell(put_cell) => {
+                ColumnVisibility::validate(put_cell.visibility.as_bytes()).map_err(Status::invalid_argument)?;
+
                 Ok(RowMutationOp::Put { 
                     family: Bytes::from(put_cell.family_name), 
                     column: Bytes::from(put_cell.column_name), 
                     timestamp: get_timestamp(put_cell.timestamp)?, 
                     value: Bytes::from(put_cell.value), 
+                    visibility: match put_cell.visibility.is_empty() {
+                        true => None,
+                        false => Some(Bytes::from(put_cell.visibility)),
+                    },
                 })
             },
             wdb_grpc::wdb_grpc::mutation::Mutation::DeleteCell(delete_cell) => {
@@ -53,11 +60,25 @@ pub async fn row_mutate<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Reques
         }
     }).collect::<Result<Vec<RowMutationOp>, Status>>()?;
 
-    ctx.storage_engine.execute_row_mutation(RowMutation {
+    let write_bytes = request.row.len() + ops.iter().map(mutation_op_size).sum::<usize>();
+    ctx.check_quota(&request.table_name, write_bytes as u64)?;
+
+    // Visibility is validated above, so what is left to fail is a missing table or family.
+    ctx.storage_engine.try_execute_row_mutation(RowMutation {
         table: Bytes::from(request.table_name), 
         row: Bytes::from(request.row), 
-        ops: ops, 
-    });
+        ops, 
+    }).map_err(Status::not_found)?;
 
     Ok(Response::new(()))
+}
+
+// Bytes the mutation adds to the table, the quota does not charge protocol overhead.
+fn mutation_op_size(op: &RowMutationOp) -> usize {
+    match op {
+        RowMutationOp::Put { family, column, value, visibility, .. } => family.len() + column.len() + value.len() + visibility.as_ref().map_or(0, |visibility| visibility.len()),
+        RowMutationOp::DeleteCell { family, column, .. } => family.len() + column.len(),
+        RowMutationOp::DeleteColumn { family, column, .. } => family.len() + column.len(),
+        RowMutationOp::DeleteFamily { family, .. } => family.len(),
+    }
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{RowKeySample, SampleRowKeysRequest, SampleRowKeysResponse};
+use wdb_storage_engine::{Permission, PersistanceLayer};
+
+use crate::server_ctx::ServerCtx;
+
+pub async fn sample_row_keys<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<SampleRowKeysRequest>) -> Result<Response<SampleRowKeysResponse>, Status> {
+    const DEFAULT_INTERVAL_BYTES: u64 = 64 * 1024 * 1024;
+
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Read)?;
+    ctx.check_quota(&request.table_name, 0)?;
+
+    let interval = match request.interval_bytes {
+        0 => DEFAULT_INTERVAL_BYTES,
+        interval => interval,
+    };
+
+    let samples = ctx.storage_engine.sample_row_keys(Bytes::from(request.table_name), interval)
+        .map_err(Status::not_found)?;
+
+    Ok(Response::new(SampleRowKeysResponse {
+        samples: samples.into_iter().map(|(row_key, offset_bytes)| {
+            RowKeySample {
+                row_key: row_key.to_vec(),
+                offset_bytes,
+            }
+        }).collect()
+    }))
+}
\ No newline at end of file
@@ -0,0 +1,90 @@
+use bytes::Bytes;
+use log::info;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{CompactRangeRequest, CompactRangeResponse, FamilyCompaction, FamilySegments, FlushTableRequest, FlushTableResponse, ListSegmentsRequest, ListSegmentsResponse, SegmentInfo};
+use wdb_storage_engine::{CompactionResult, Permission, PersistanceLayer};
+
+use crate::server_ctx::ServerCtx;
+
+pub async fn list_segments<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<ListSegmentsRequest>) -> Result<Response<ListSegmentsResponse>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Admin)?;
+    ctx.check_quota(&request.table_name, 0)?;
+
+    let families = get_family_segments(ctx, &request.table_name, request.family_name)?;
+    Ok(Response::new(ListSegmentsResponse { families }))
+}
+
+pub async fn flush_table<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<FlushTableRequest>) -> Result<Response<FlushTableResponse>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Admin)?;
+    ctx.check_quota(&request.table_name, 0)?;
+
+    let storage_engine = ctx.storage_engine.clone();
+    let (table, family) = (Bytes::from(request.table_name.clone()), request.family_name.clone().map(Bytes::from));
+    tokio::task::spawn_blocking(move || storage_engine.flush_table(table, family)).await.unwrap()
+        .map_err(Status::not_found)?;
+    info!("Table {} flushed by {}", request.table_name, ctx.get_principal_name());
+
+    let families = get_family_segments(ctx, &request.table_name, request.family_name)?;
+    Ok(Response::new(FlushTableResponse { families }))
+}
+
+pub async fn compact_range<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<CompactRangeRequest>) -> Result<Response<CompactRangeResponse>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Admin)?;
+    ctx.check_quota(&request.table_name, 0)?;
+
+    let start = Some(Bytes::from(request.start_row)).filter(|row| !row.is_empty());
+    let end = Some(Bytes::from(request.end_row)).filter(|row| !row.is_empty());
+    if let (Some(start), Some(end)) = (&start, &end) {
+        if start > end {
+            return Err(Status::invalid_argument("Start row cannot be after end row."));
+        }
+    }
+
+    let storage_engine = ctx.storage_engine.clone();
+    let (table, family) = (Bytes::from(request.table_name.clone()), request.family_name.clone().map(Bytes::from));
+    let results = tokio::task::spawn_blocking(move || storage_engine.compact_range(table, family, start, end)).await.unwrap()
+        .map_err(Status::not_found)?;
+    info!("Table {} compacted by {}", request.table_name, ctx.get_principal_name());
+
+    let families = get_family_segments(ctx, &request.table_name, request.family_name)?;
+    Ok(Response::new(CompactRangeResponse {
+        compactions: results.into_iter().map(compaction_to_proto).collect(),
+        families,
+    }))
+}
+
+fn get_family_segments<P: PersistanceLayer>(ctx: &ServerCtx<P>, table: &str, family: Option<String>) -> Result<Vec<FamilySegments>, Status> {
+    let families = ctx.storage_engine.list_segments(Bytes::from(table.to_string()), family.map(Bytes::from))
+        .map_err(Status::not_found)?;
+
+    Ok(families.into_iter().map(|(name, memtable_bytes, sstables)| {
+        FamilySegments {
+            family_name: String::from_utf8_lossy(&name).to_string(),
+            memtable_bytes,
+            segments: sstables.iter().map(|sstable| {
+                let stats = sstable.get_stats();
+                SegmentInfo {
+                    name: String::from_utf8_lossy(sstable.get_segment()).to_string(),
+                    size_bytes: sstable.get_size(),
+                    cell_count: stats.map_or(0, |stats| stats.cell_count),
+                    min_row_key: stats.and_then(|stats| stats.min_row.as_ref()).map(|row| row.to_vec()),
+                    max_row_key: stats.and_then(|stats| stats.max_row.as_ref()).map(|row| row.to_vec()),
+                    max_mvcc_id: sstable.get_max_mvcc_id(),
+                }
+            }).collect(),
+        }
+    }).collect())
+}
+
+fn compaction_to_proto(result: CompactionResult) -> FamilyCompaction {
+    FamilyCompaction {
+        family_name: String::from_utf8_lossy(&result.family).to_string(),
+        removed_segments: result.removed_segments.iter().map(|segment| String::from_utf8_lossy(segment).to_string()).collect(),
+        added_segment: result.added_segment.map(|segment| String::from_utf8_lossy(&segment).to_string()),
+        cells_read: result.cells_read,
+        cells_written: result.cells_written,
+    }
+}
\ No newline at end of file
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{ChangeStreamConfig, SetChangeStreamConfigRequest};
+use wdb_storage_engine::{ChangeFeedOptions, Permission, PersistanceLayer, TableOptions};
+
+use crate::server_ctx::ServerCtx;
+
+pub async fn set_change_stream_config<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<SetChangeStreamConfigRequest>) -> Result<Response<()>, Status> {
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Admin)?;
+    ctx.check_quota(&request.table_name, 0)?;
+    let table_name = Bytes::from(request.table_name);
+
+    let options = ctx.storage_engine.get_table(table_name.clone())
+        .ok_or(Status::not_found("Table with this name does not exist."))?
+        .get_options();
+
+    ctx.storage_engine.set_table_options(table_name, TableOptions {
+        change_feed: request.config.map(change_feed_options_from_proto),
+        ..(*options).clone()
+    }).map_err(Status::not_found)?;
+
+    Ok(Response::new(()))
+}
+
+pub fn change_feed_options_from_proto(config: ChangeStreamConfig) -> ChangeFeedOptions {
+    match config.retention_ms {
+        0 => ChangeFeedOptions::default(),
+        retention_ms => ChangeFeedOptions { retention_ms },
+    }
+}
\ No newline at end of file
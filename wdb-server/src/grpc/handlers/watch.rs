@@ -0,0 +1,60 @@
+use bytes::Bytes;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use wdb_grpc::wdb_grpc::{watch_response, WatchOverflow, WatchRequest, WatchResponse};
+use wdb_storage_engine::{Permission, PersistanceLayer, WatchEvent, WatchFilter};
+
+use crate::server_ctx::ServerCtx;
+
+use super::read_change_stream::data_change_to_proto;
+
+pub async fn watch<P: PersistanceLayer>(ctx: &ServerCtx<P>, request: Request<WatchRequest>) -> Result<Response<ReceiverStream<Result<WatchResponse, Status>>>, Status> {
+    const DEFAULT_BUFFER_SIZE: usize = 1024;
+    const MAX_BUFFER_SIZE: usize = 64 * 1024;
+
+    let request = request.into_inner();
+    ctx.check_table_permission(&request.table_name, Permission::Read)?;
+    ctx.check_quota(&request.table_name, 0)?;
+
+    let buffer_size = match request.buffer_size as usize {
+        0 => DEFAULT_BUFFER_SIZE,
+        size if size > MAX_BUFFER_SIZE => return Err(Status::invalid_argument("Invalid buffer size. Maximum allowed value is 65536.")),
+        size => size,
+    };
+
+    let as_filter = |val: Vec<u8>| -> Option<Bytes> {
+        match val.len() {
+            0 => None,
+            _ => Some(Bytes::from(val)),
+        }
+    };
+    let filter = WatchFilter {
+        start_row: as_filter(request.start_row),
+        end_row: as_filter(request.end_row),
+        prefix: as_filter(request.row_prefix),
+        family: as_filter(request.family_name.into_bytes()),
+        column: as_filter(request.column_name),
+    };
+
+    let mut events = ctx.storage_engine.watch(Bytes::from(request.table_name), filter, buffer_size)
+        .map_err(Status::not_found)?;
+
+    let authorizations = ctx.get_authorizations();
+    let (tx, rx) = mpsc::channel(buffer_size);
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            let event = match event {
+                WatchEvent::Change { row, write_num, commit_ts, ops } => 
+                    watch_response::Event::DataChange(data_change_to_proto(row, write_num, commit_ts, ops, &authorizations)),
+                WatchEvent::Overflow => watch_response::Event::Overflow(WatchOverflow {}),
+            };
+
+            if tx.send(Ok(WatchResponse { event: Some(event) })).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    Ok(Response::new(ReceiverStream::new(rx)))
+}
\ No newline at end of file
@@ -1,3 +1,4 @@
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 use wdb_grpc::wdb_grpc::{wide_db_server::WideDb, *};
 use wdb_storage_engine::PersistanceLayer;
@@ -18,19 +19,54 @@ impl<P: PersistanceLayer> HandlersService<P> {
 
 #[tonic::async_trait]
 impl<P: PersistanceLayer> WideDb for HandlersService<P> {
+    type ReadChangeStreamStream = ReceiverStream<Result<ReadChangeStreamResponse, Status>>;
+    type WatchStream = ReceiverStream<Result<WatchResponse, Status>>;
+
     async fn create_table(&self, request: Request<CreateTableRequest>) -> Result<Response<Table>, Status> {
-        handlers::create_table(&self.server_ctx, request).await
+        handlers::create_table(&self.server_ctx.with_request(&request), request).await
     }
 
     async fn list_tables(&self, request: Request<()>) -> Result<Response<ListTablesResponse>, Status> {
-        todo!()
+        handlers::list_tables(&self.server_ctx.with_request(&request), request).await
     } 
 
+    async fn get_table(&self, request: Request<GetTableRequest>) -> Result<Response<TableDetails>, Status> {
+        handlers::get_table(&self.server_ctx.with_request(&request), request).await
+    }
+
     async fn read_row(&self, request: Request<ReadRowRequest>) -> Result<Response<ReadRowResponse>, Status> {
-        handlers::read_row(&self.server_ctx, request).await
+        handlers::read_row(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn read_rows(&self, request: Request<ReadRowsRequest>) -> Result<Response<ReadRowsResponse>, Status> {
+        handlers::read_rows(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn sample_row_keys(&self, request: Request<SampleRowKeysRequest>) -> Result<Response<SampleRowKeysResponse>, Status> {
+        handlers::sample_row_keys(&self.server_ctx.with_request(&request), request).await
     }
 
     async fn mutate_row(&self, request: Request<MutateRowRequest>) -> Result<Response<()>, Status> {
-        handlers::row_mutate(&self.server_ctx, request).await
+        handlers::row_mutate(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn delete_table(&self, request: Request<DeleteTableRequest>) -> Result<Response<()>, Status> {
+        handlers::delete_table(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn modify_column_families(&self, request: Request<ModifyColumnFamiliesRequest>) -> Result<Response<Table>, Status> {
+        handlers::modify_column_families(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn set_change_stream_config(&self, request: Request<SetChangeStreamConfigRequest>) -> Result<Response<()>, Status> {
+        handlers::set_change_stream_config(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn read_change_stream(&self, request: Request<ReadChangeStreamRequest>) -> Result<Response<Self::ReadChangeStreamStream>, Status> {
+        handlers::read_change_stream(&self.server_ctx.with_request(&request), request).await
+    }
+
+    async fn watch(&self, request: Request<WatchRequest>) -> Result<Response<Self::WatchStream>, Status> {
+        handlers::watch(&self.server_ctx.with_request(&request), request).await
     }
 }
\ No newline at end of file
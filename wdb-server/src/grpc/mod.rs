@@ -1,6 +1,10 @@
 mod handlers;
 mod handlers_service;
+mod admin_handlers_service;
 mod grpc_api;
+mod grpc_options;
 
 pub use handlers_service::HandlersService;
-pub use grpc_api::GrpcApi;
\ No newline at end of file
+pub use admin_handlers_service::AdminHandlersService;
+pub use grpc_api::GrpcApi;
+pub use grpc_options::{GrpcOptions, TlsOptions};
\ No newline at end of file
@@ -0,0 +1,5 @@
+mod server_health;
+mod readiness_layer;
+
+pub use server_health::ServerHealth;
+pub use readiness_layer::{ReadinessLayer, ReadinessService};
\ No newline at end of file
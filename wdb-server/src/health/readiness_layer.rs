@@ -0,0 +1,54 @@
+use std::{future::Future, pin::Pin, sync::{atomic::{AtomicBool, Ordering}, Arc}, task::{Context, Poll}};
+
+use tonic::{body::BoxBody, Status};
+use tower::{Layer, Service};
+
+/// Rejects calls with UNAVAILABLE while the server is recovering or shutting down.
+#[derive(Debug, Clone)]
+pub struct ReadinessLayer {
+    accepting: Arc<AtomicBool>,
+}
+
+impl ReadinessLayer {
+    pub fn new(accepting: Arc<AtomicBool>) -> ReadinessLayer {
+        ReadinessLayer { accepting }
+    }
+}
+
+impl<S> Layer<S> for ReadinessLayer {
+    type Service = ReadinessService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ReadinessService { inner, accepting: self.accepting.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadinessService<S> {
+    inner: S,
+    accepting: Arc<AtomicBool>,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for ReadinessService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<BoxBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        // Health and reflection stay reachable, so probes can still tell why the server is not ready.
+        if !self.accepting.load(Ordering::Acquire) && !request.uri().path().starts_with("/grpc.") {
+            let response = Status::unavailable("Server is not accepting requests.").to_http();
+            return Box::pin(async move { Ok(response) });
+        }
+
+        Box::pin(self.inner.call(request))
+    }
+}
\ No newline at end of file
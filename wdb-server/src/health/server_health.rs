@@ -0,0 +1,97 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, time::Duration};
+
+use log::{info, warn};
+use tokio::time::sleep;
+use tonic::server::NamedService;
+use tonic_health::{server::HealthReporter, ServingStatus};
+use wdb_grpc::wdb_grpc::{wide_db_admin_server::WideDbAdminServer, wide_db_server::WideDbServer};
+use wdb_storage_engine::{PersistanceLayer, StorageEngine};
+
+use crate::grpc::{AdminHandlersService, HandlersService};
+
+/*
+Reported health statuses:
+- "" (the whole server) and the WideDB services serve once recovery finished, nothing failed and the server is not shutting down,
+- widedb.recovery serves once tables are loaded and change logs replayed,
+- widedb.storage serves while the data directory is writable,
+- widedb.flush_agent serves unless the flush agent stopped making progress.
+ */
+pub struct ServerHealth<P: PersistanceLayer> {
+    storage_engine: Arc<StorageEngine<P>>,
+    reporter: HealthReporter,
+    accepting: Arc<AtomicBool>,
+    serving: AtomicBool,
+    shutting_down: AtomicBool,
+}
+
+impl<P: PersistanceLayer> ServerHealth<P> {
+    pub const RECOVERY: &'static str = "widedb.recovery";
+    pub const STORAGE: &'static str = "widedb.storage";
+    pub const FLUSH_AGENT: &'static str = "widedb.flush_agent";
+    pub const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(storage_engine: Arc<StorageEngine<P>>, reporter: HealthReporter) -> ServerHealth<P> {
+        ServerHealth {
+            storage_engine,
+            reporter,
+            accepting: Arc::new(AtomicBool::new(false)),
+            serving: AtomicBool::new(false),
+            shutting_down: AtomicBool::new(false),
+        }
+    }
+
+    /// Shared with the readiness layer, which rejects calls while this is false.
+    pub fn get_accepting_flag(&self) -> Arc<AtomicBool> {
+        self.accepting.clone()
+    }
+
+    pub fn is_serving(&self) -> bool {
+        self.serving.load(Ordering::Acquire)
+    }
+
+    pub async fn set_shutting_down(&self) {
+        self.shutting_down.store(true, Ordering::Release);
+        self.update().await;
+    }
+
+    pub async fn update(&self) {
+        let recovered = self.storage_engine.is_recovered();
+        let shutting_down = self.shutting_down.load(Ordering::Acquire);
+
+        let storage_engine = self.storage_engine.clone();
+        let storage = tokio::task::spawn_blocking(move || storage_engine.check_storage()).await.unwrap();
+        let flush_agent_stalled = self.storage_engine.is_flush_agent_stalled();
+
+        let accepting = recovered && !shutting_down;
+        let serving = accepting && storage.is_ok() && !flush_agent_stalled;
+        self.accepting.store(accepting, Ordering::Release);
+
+        if self.serving.swap(serving, Ordering::AcqRel) != serving {
+            match serving {
+                true => info!("Server is serving."),
+                false => warn!("Server is not serving, recovered: {}, shutting down: {}, storage: {:?}, flush agent stalled: {}.", recovered, shutting_down, storage, flush_agent_stalled),
+            }
+        }
+
+        let mut reporter = self.reporter.clone();
+        reporter.set_service_status(ServerHealth::<P>::RECOVERY, ServerHealth::<P>::status(recovered)).await;
+        reporter.set_service_status(ServerHealth::<P>::STORAGE, ServerHealth::<P>::status(storage.is_ok())).await;
+        reporter.set_service_status(ServerHealth::<P>::FLUSH_AGENT, ServerHealth::<P>::status(!flush_agent_stalled)).await;
+        for service in ["", WideDbServer::<HandlersService<P>>::NAME, WideDbAdminServer::<AdminHandlersService<P>>::NAME] {
+            reporter.set_service_status(service, ServerHealth::<P>::status(serving)).await;
+        }
+    }
+
+    pub fn start_monitor(self: Arc<Self>) {
+        tokio::spawn(async move {
+            while !self.shutting_down.load(Ordering::Acquire) {
+                sleep(ServerHealth::<P>::CHECK_INTERVAL).await;
+                self.update().await;
+            }
+        });
+    }
+
+    fn status(serving: bool) -> ServingStatus {
+        if serving { ServingStatus::Serving } else { ServingStatus::NotServing }
+    }
+}
\ No newline at end of file
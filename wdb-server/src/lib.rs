@@ -0,0 +1,8 @@
+pub mod server;
+pub mod server_ctx;
+pub mod grpc;
+pub mod auth;
+pub mod config;
+pub mod metrics;
+pub mod health;
+pub mod quota;
\ No newline at end of file
@@ -1,35 +1,107 @@
-mod server;
-mod server_ctx;
-mod grpc;
+mod args;
+mod commands;
 
-use log::info;
+use std::{fs, process, time::Duration};
+
+use clap::Parser;
+use log::{info, warn};
 use tokio::signal;
 use wdb_storage_engine::{FSPersistance, StorageEngine};
 
-use self::server::Server;
-use grpc::GrpcApi;
+use wdb_server::{config::ServerConfig, grpc::GrpcApi, metrics::MetricsServer, server::Server};
+
+use args::Args;
 
 #[tokio::main]
 async fn main() {
-    env_logger::init();
+    let args = Args::parse();
+
+    let mut config = match &args.config {
+        Some(path) => ServerConfig::from_file(path).unwrap_or_else(|err| exit_with_error(&err)),
+        None => ServerConfig::default(),
+    };
+    args.apply(&mut config).unwrap_or_else(|err| exit_with_error(&err));
+
+    if args.print_config {
+        print!("{}", config.to_toml());
+    }
+    if let Err(err) = config.validate() {
+        exit_with_error(&format!("Invalid configuration: {}", err));
+    }
+    if args.print_config {
+        return;
+    }
+
+    env_logger::Builder::new().parse_filters(&config.log_level).init();
+
+    if let Err(err) = fs::create_dir_all(&config.data_dir) {
+        exit_with_error(&format!("Unable to create data directory {:?}: {}", config.data_dir, err));
+    }
+
+    if let Some(command) = args.command {
+        return commands::run(command, &config.data_dir);
+    }
 
     info!("WideDB server is starting...");
 
-    info!("Initializing storage engine...");
-    let storage_engine = StorageEngine::empty(FSPersistance::new(), true);
-    info!("Storage engine initialization success!");
+    let grpc_options = config.grpc_options().unwrap_or_else(|err| exit_with_error(&err));
+
+    info!("Opening storage engine in {:?}...", config.data_dir);
+    let storage_engine = StorageEngine::open(FSPersistance::with_base(&config.data_dir), config.engine.clone());
+
+    if config.metrics.enabled {
+        if let Err(err) = MetricsServer::init(storage_engine.clone(), config.metrics.listen_addr) {
+            exit_with_error(&format!("Unable to start metrics server: {}", err));
+        }
+        info!("Metrics available on http://{}/metrics.", config.metrics.listen_addr);
+    }
 
     info!("Initializing app server...");
-    let server = Server::init(storage_engine);
+    let server = Server::init_with_config(storage_engine.clone(), &config);
     info!("App server initialization success!");
 
+    // Health checks are answered with NOT_SERVING until recovery finishes.
     info!("Starting grpc server...");
-    GrpcApi::init(server.get_ctx().clone());
+    let grpc_api = GrpcApi::init(server.get_ctx().clone(), config.listen_addr, &grpc_options)
+        .unwrap_or_else(|err| exit_with_error(&format!("Unable to start grpc server: {}", err)));
+    info!("Grpc server listening on {}, tls: {}, authentication: {}.", config.listen_addr, grpc_options.tls.is_some(), grpc_options.authenticator.is_some());
 
-    match signal::ctrl_c().await {
-        Ok(()) => {},
-        Err(err) => {
-            eprintln!("Unable to listen for shutdown signal: {}", err);
-        }
-    };
+    info!("Recovering storage engine...");
+    let recovering_engine = storage_engine.clone();
+    tokio::task::spawn_blocking(move || recovering_engine.recover()).await.unwrap();
+    grpc_api.get_health().update().await;
+    info!("Storage engine recovery success!");
+
+    shutdown_signal().await;
+
+    info!("Shutting down, draining requests for up to {} ms...", config.shutdown_timeout_ms);
+    if !grpc_api.shutdown(Duration::from_millis(config.shutdown_timeout_ms)).await {
+        warn!("Requests still in flight after {} ms were cancelled.", config.shutdown_timeout_ms);
+    }
+
+    info!("Stopping flush agent...");
+    storage_engine.stop_flush_agent().await;
+
+    info!("Flushing memtables...");
+    tokio::task::spawn_blocking(move || storage_engine.flush_all()).await.unwrap();
+    info!("Shutdown complete.");
 }
+
+#[cfg(unix)]
+async fn shutdown_signal() {
+    let mut terminate = signal::unix::signal(signal::unix::SignalKind::terminate()).unwrap();
+    tokio::select! {
+        _ = signal::ctrl_c() => {},
+        _ = terminate.recv() => {},
+    }
+}
+
+#[cfg(not(unix))]
+async fn shutdown_signal() {
+    signal::ctrl_c().await.unwrap();
+}
+
+fn exit_with_error(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    process::exit(1);
+}
\ No newline at end of file
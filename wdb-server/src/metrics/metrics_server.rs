@@ -0,0 +1,54 @@
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use hyper::{header::CONTENT_TYPE, service::{make_service_fn, service_fn}, Body, Request, Response, StatusCode};
+use log::error;
+use prometheus::{Encoder, TextEncoder};
+use wdb_storage_engine::{PersistanceLayer, StorageEngine};
+
+/// Serves engine and RPC metrics in the Prometheus text format on `/metrics`.
+pub struct MetricsServer {
+
+}
+
+impl MetricsServer {
+    pub fn init<P: PersistanceLayer>(storage_engine: Arc<StorageEngine<P>>, addr: SocketAddr) -> Result<MetricsServer, String> {
+        let listener = std::net::TcpListener::bind(addr).map_err(|err| format!("Unable to bind {}: {}", addr, err))?;
+
+        MetricsServer::init_with_listener(storage_engine, listener)
+    }
+
+    pub fn init_with_listener<P: PersistanceLayer>(storage_engine: Arc<StorageEngine<P>>, listener: std::net::TcpListener) -> Result<MetricsServer, String> {
+        let make_service = make_service_fn(move |_| {
+            let storage_engine = storage_engine.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let storage_engine = storage_engine.clone();
+                    async move { Ok::<_, Infallible>(MetricsServer::handle(&storage_engine, request)) }
+                }))
+            }
+        });
+
+        let server = hyper::Server::from_tcp(listener).map_err(|err| format!("Unable to start metrics server: {}", err))?.serve(make_service);
+        tokio::spawn(async move {
+            if let Err(err) = server.await {
+                error!("Metrics server failed: {}", err);
+            }
+        });
+
+        Ok(MetricsServer {  })
+    }
+
+    fn handle<P: PersistanceLayer>(storage_engine: &StorageEngine<P>, request: Request<Body>) -> Response<Body> {
+        if request.uri().path() != "/metrics" {
+            return Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()).unwrap();
+        }
+
+        storage_engine.update_metrics();
+
+        let encoder = TextEncoder::new();
+        let mut buf = vec![];
+        encoder.encode(&prometheus::gather(), &mut buf).unwrap();
+
+        Response::builder().header(CONTENT_TYPE, encoder.format_type()).body(Body::from(buf)).unwrap()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,5 @@
+mod rpc_metrics_layer;
+mod metrics_server;
+
+pub use rpc_metrics_layer::{RpcMetricsLayer, RpcMetricsService};
+pub use metrics_server::MetricsServer;
\ No newline at end of file
@@ -0,0 +1,70 @@
+use std::{future::Future, pin::Pin, task::{Context, Poll}, time::Instant};
+
+use once_cell::sync::Lazy;
+use prometheus::{register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec};
+use tonic::Code;
+use tower::{Layer, Service};
+
+static RPC_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!("wdb_rpc_duration_seconds", "Time until the RPC response headers are sent.", &["method"]).unwrap()
+});
+
+static RPC_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("wdb_rpc_errors_total", "RPCs that failed, by status code.", &["method", "code"]).unwrap()
+});
+
+/// Records latency and errors of every gRPC call, including ones rejected by interceptors.
+#[derive(Debug, Clone, Default)]
+pub struct RpcMetricsLayer {}
+
+impl<S> Layer<S> for RpcMetricsLayer {
+    type Service = RpcMetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcMetricsService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcMetricsService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for RpcMetricsService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let start = Instant::now();
+        let method = request.uri().path().to_string();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = future.await;
+            if let Ok(response) = &response {
+                // Failed calls carry the status in the headers, successful ones only in the trailers.
+                let code = response.headers().get("grpc-status")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<i32>().ok())
+                    .map_or(Code::Ok, Code::from_i32);
+
+                // Paths of unknown methods come from the caller, so they share one label.
+                let method = if code == Code::Unimplemented { "unknown" } else { method.as_str() };
+                RPC_DURATION.with_label_values(&[method]).observe(start.elapsed().as_secs_f64());
+                if code != Code::Ok {
+                    RPC_ERRORS.with_label_values(&[method, &format!("{:?}", code)]).inc();
+                }
+            }
+            response
+        })
+    }
+}
\ No newline at end of file
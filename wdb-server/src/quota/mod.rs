@@ -0,0 +1,5 @@
+mod token_bucket;
+mod quota_limiter;
+
+pub use token_bucket::{TokenBucket, TokenBucketGuard};
+pub use quota_limiter::QuotaLimiter;
\ No newline at end of file
@@ -0,0 +1,130 @@
+use std::{sync::Arc, time::Duration};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use prometheus::{register_int_counter_vec, register_int_gauge_vec, IntCounterVec, IntGaugeVec};
+use tonic::{metadata::MetadataValue, Status};
+
+use crate::config::{QuotaConfig, QuotaLimits};
+
+use super::TokenBucket;
+
+static QUOTA_LIMIT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!("wdb_quota_limit", "Configured quota per second.", &["scope", "name", "resource"]).unwrap()
+});
+
+static QUOTA_CONSUMED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("wdb_quota_consumed_total", "Quota consumed by admitted calls.", &["scope", "name", "resource"]).unwrap()
+});
+
+static QUOTA_THROTTLED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("wdb_quota_throttled_total", "Calls rejected because the quota was exhausted.", &["scope", "name", "resource"]).unwrap()
+});
+
+#[derive(Debug)]
+struct QuotaBuckets {
+    scope: &'static str,
+    name: String,
+    requests: Option<TokenBucket>,
+    write_bytes: Option<TokenBucket>,
+}
+
+impl QuotaBuckets {
+    fn new(scope: &'static str, name: &str, limits: &QuotaLimits) -> QuotaBuckets {
+        let buckets = QuotaBuckets {
+            scope,
+            name: name.to_string(),
+            requests: limits.requests_per_sec.map(TokenBucket::new),
+            write_bytes: limits.write_bytes_per_sec.map(TokenBucket::new),
+        };
+        for (resource, bucket, _) in buckets.get_resources(0) {
+            QUOTA_LIMIT.with_label_values(&[scope, name, resource]).set(bucket.get_rate() as i64);
+        }
+        buckets
+    }
+
+    fn get_resources(&self, write_bytes: u64) -> impl Iterator<Item = (&'static str, &TokenBucket, u64)> {
+        let requests = self.requests.as_ref().map(|bucket| ("requests", bucket, 1));
+        let write_bytes = self.write_bytes.as_ref().map(|bucket| ("write_bytes", bucket, write_bytes));
+        requests.into_iter().chain(write_bytes)
+    }
+}
+
+/// Token bucket limits on request rate and write bytes per second, kept per table and per principal.
+#[derive(Debug, Default)]
+pub struct QuotaLimiter {
+    config: QuotaConfig,
+    tables: DashMap<String, Arc<QuotaBuckets>>,
+    principals: DashMap<String, Arc<QuotaBuckets>>,
+}
+
+impl QuotaLimiter {
+    /// Milliseconds after which a call rejected with RESOURCE_EXHAUSTED may pass.
+    pub const RETRY_AFTER_METADATA: &'static str = "retry-after-ms";
+
+    pub fn new(config: QuotaConfig) -> QuotaLimiter {
+        QuotaLimiter { config, tables: DashMap::new(), principals: DashMap::new() }
+    }
+
+    /*
+    Admission rules:
+    - a call takes one request and its write bytes from the table buckets and, when authenticated, the principal buckets,
+    - it is admitted only when every bucket can cover it, otherwise nothing is taken,
+    - every bucket stays locked from the check to the take, table buckets before principal buckets, so concurrent calls cannot overdraw them,
+    - the rejection carries the longest wait among the buckets that fell short.
+     */
+    pub fn acquire(&self, table: Option<&str>, principal: Option<&str>, write_bytes: u64) -> Result<(), Status> {
+        if self.config.is_unlimited() {
+            return Ok(());
+        }
+
+        let mut buckets = vec![];
+        if let Some(table) = table {
+            buckets.extend(QuotaLimiter::get_buckets(&self.tables, "table", table, |name| self.config.get_table_limits(name)));
+        }
+        if let Some(principal) = principal {
+            buckets.extend(QuotaLimiter::get_buckets(&self.principals, "principal", principal, |name| self.config.get_principal_limits(name)));
+        }
+
+        let mut locked = buckets.iter()
+            .flat_map(|quota| quota.get_resources(write_bytes).map(move |(resource, bucket, tokens)| (quota, resource, bucket.lock(), tokens)))
+            .collect::<Vec<_>>();
+
+        let mut exhausted: Option<(Duration, &QuotaBuckets, &'static str)> = None;
+        for (quota, resource, bucket, tokens) in locked.iter() {
+            let wait = bucket.wait_time(*tokens);
+            if wait > exhausted.map_or(Duration::ZERO, |(longest, _, _)| longest) {
+                exhausted = Some((wait, quota, resource));
+            }
+        }
+
+        if let Some((wait, quota, resource)) = exhausted {
+            QUOTA_THROTTLED.with_label_values(&[quota.scope, &quota.name, resource]).inc();
+
+            let mut status = Status::resource_exhausted(format!("Quota on {} for {} {} exceeded.", resource, quota.scope, quota.name));
+            let retry_after = wait.as_millis() as u64 + 1;
+            status.metadata_mut().insert(QuotaLimiter::RETRY_AFTER_METADATA, MetadataValue::from(retry_after));
+            return Err(status);
+        }
+
+        for (quota, resource, bucket, tokens) in locked.iter_mut() {
+            bucket.take(*tokens);
+            QUOTA_CONSUMED.with_label_values(&[quota.scope, &quota.name, resource]).inc_by(*tokens);
+        }
+
+        Ok(())
+    }
+
+    // Buckets are created on first use, names without any limit never get one.
+    fn get_buckets<'a, F: Fn(&str) -> &'a QuotaLimits>(map: &DashMap<String, Arc<QuotaBuckets>>, scope: &'static str, name: &str, get_limits: F) -> Option<Arc<QuotaBuckets>> {
+        if let Some(buckets) = map.get(name) {
+            return Some(buckets.clone());
+        }
+
+        let limits = get_limits(name);
+        if limits.is_unlimited() {
+            return None;
+        }
+        Some(map.entry(name.to_string()).or_insert_with(|| Arc::new(QuotaBuckets::new(scope, name, limits))).clone())
+    }
+}
\ No newline at end of file
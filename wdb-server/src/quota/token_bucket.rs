@@ -0,0 +1,62 @@
+use std::{sync::{Mutex, MutexGuard}, time::{Duration, Instant}};
+
+/// Refills `rate` tokens per second and holds at most one second worth of them.
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: u64) -> TokenBucket {
+        TokenBucket {
+            rate: rate as f64,
+            state: Mutex::new(BucketState { tokens: rate as f64, refilled_at: Instant::now() }),
+        }
+    }
+
+    pub fn get_rate(&self) -> u64 {
+        self.rate as u64
+    }
+
+    /// Holds the bucket until the guard is dropped, so the tokens checked are still there when taken.
+    pub fn lock(&self) -> TokenBucketGuard<'_> {
+        TokenBucketGuard { rate: self.rate, state: self.refill() }
+    }
+
+    fn refill(&self) -> MutexGuard<'_, BucketState> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let refill = now.duration_since(state.refilled_at).as_secs_f64() * self.rate;
+        state.tokens = (state.tokens + refill).min(self.rate);
+        state.refilled_at = now;
+        state
+    }
+}
+
+pub struct TokenBucketGuard<'a> {
+    rate: f64,
+    state: MutexGuard<'a, BucketState>,
+}
+
+impl TokenBucketGuard<'_> {
+    // Requests larger than the bucket only wait for it to fill up and then leave it in debt,
+    // otherwise they could never pass.
+    pub fn wait_time(&self, tokens: u64) -> Duration {
+        let missing = (tokens as f64).min(self.rate) - self.state.tokens;
+        match missing > 0.0 {
+            true => Duration::from_secs_f64(missing / self.rate),
+            false => Duration::ZERO,
+        }
+    }
+
+    pub fn take(&mut self, tokens: u64) {
+        self.state.tokens -= tokens as f64;
+    }
+}
\ No newline at end of file
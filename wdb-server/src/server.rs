@@ -2,7 +2,7 @@ use std::sync::{Arc, Mutex};
 
 use wdb_storage_engine::{PersistanceLayer, StorageEngine};
 
-use crate::server_ctx::ServerCtx;
+use crate::{config::{AdminConfig, QuotaConfig, ServerConfig}, quota::QuotaLimiter, server_ctx::ServerCtx};
 
 pub struct Server<P: PersistanceLayer> {
     storage_engine: Arc<StorageEngine<P>>,
@@ -11,8 +11,23 @@ pub struct Server<P: PersistanceLayer> {
 
 impl<P: PersistanceLayer> Server<P> {
     pub fn init(storage_engine: Arc<StorageEngine<P>>) -> Server<P> {
+        Server::init_with_quotas(storage_engine, QuotaConfig::default())
+    }
+
+    pub fn init_with_quotas(storage_engine: Arc<StorageEngine<P>>, quotas: QuotaConfig) -> Server<P> {
+        Server::init_with_parts(storage_engine, quotas, AdminConfig::default())
+    }
+
+    pub fn init_with_config(storage_engine: Arc<StorageEngine<P>>, config: &ServerConfig) -> Server<P> {
+        Server::init_with_parts(storage_engine, config.quotas.clone(), config.admin.clone())
+    }
+
+    fn init_with_parts(storage_engine: Arc<StorageEngine<P>>, quotas: QuotaConfig, admin: AdminConfig) -> Server<P> {
         let ctx = ServerCtx {
             storage_engine: storage_engine.clone(),
+            principal: None,
+            quota_limiter: Arc::new(QuotaLimiter::new(quotas)),
+            admin: Arc::new(admin),
         };
 
         Server {
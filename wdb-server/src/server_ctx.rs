@@ -1,8 +1,132 @@
 use std::sync::Arc;
 
-use wdb_storage_engine::{PersistanceLayer, StorageEngine};
+use bytes::Bytes;
+use tonic::{Request, Status};
+use wdb_storage_engine::{Acl, Authorizations, PersistanceLayer, Permission, StorageEngine};
+
+use crate::{auth::Principal, config::AdminConfig, quota::QuotaLimiter};
 
-#[derive(Clone)]
 pub struct ServerCtx<P: PersistanceLayer> {
     pub storage_engine: Arc<StorageEngine<P>>,
+    pub principal: Option<Principal>,
+    pub quota_limiter: Arc<QuotaLimiter>,
+    pub admin: Arc<AdminConfig>,
+}
+
+impl<P: PersistanceLayer> ServerCtx<P> {
+    /// Context of a single call, carrying the principal resolved by the auth interceptor.
+    pub fn with_request<T>(&self, request: &Request<T>) -> ServerCtx<P> {
+        ServerCtx {
+            storage_engine: self.storage_engine.clone(),
+            principal: request.extensions().get::<Principal>().cloned(),
+            quota_limiter: self.quota_limiter.clone(),
+            admin: self.admin.clone(),
+        }
+    }
+
+    pub fn get_principal_name(&self) -> &str {
+        self.principal.as_ref().map_or("anonymous", |principal| principal.name.as_str())
+    }
+
+    /// Principal roles double as cell visibility labels; without authentication only unlabelled cells are visible.
+    pub fn get_authorizations(&self) -> Authorizations {
+        match &self.principal {
+            Some(principal) => Authorizations::new(principal.roles.iter().map(|role| role.clone().into_bytes())),
+            None => Authorizations::default(),
+        }
+    }
+
+    /*
+    Authorization rules:
+    - without authentication there is no principal and every call is allowed,
+    - bootstrap admins from the server config are allowed everything,
+    - Admin on the catalog needs a grant in the catalog ACL, so without one only bootstrap admins have it,
+    - while neither the catalog nor the table has an ACL, every authenticated caller is allowed anything else,
+    - otherwise the caller needs a grant in the table ACL or in the catalog ACL, which covers all tables.
+     */
+    pub fn check_catalog_permission(&self, permission: Permission) -> Result<(), Status> {
+        let principal = match &self.principal {
+            Some(principal) => principal,
+            None => return Ok(()),
+        };
+
+        let members = principal.get_members();
+        if self.is_bootstrap_admin(&members) {
+            return Ok(());
+        }
+
+        let allowed = match self.storage_engine.get_catalog_acl() {
+            Some(acl) => acl.allows(&members, permission),
+            None => permission < Permission::Admin,
+        };
+
+        match allowed {
+            true => Ok(()),
+            false => Err(Status::permission_denied(format!("Principal {} lacks {:?} permission on the catalog.", principal, permission))),
+        }
+    }
+
+    pub fn check_table_permission(&self, table: &str, permission: Permission) -> Result<(), Status> {
+        if self.principal.is_none() {
+            return Ok(());
+        }
+
+        // Missing tables are left for the handler to report.
+        let acl = self.storage_engine.get_table_acl(Bytes::from(table.to_string())).unwrap_or(None);
+        self.check_table_acl(table, acl.as_ref(), permission)
+    }
+
+    /// Same as `check_table_permission` for callers that already hold the table.
+    pub fn check_table_acl(&self, table: &str, acl: Option<&Acl>, permission: Permission) -> Result<(), Status> {
+        let principal = match &self.principal {
+            Some(principal) => principal,
+            None => return Ok(()),
+        };
+
+        let members = principal.get_members();
+        let catalog_acl = self.storage_engine.get_catalog_acl();
+
+        let allowed = match (catalog_acl, acl) {
+            _ if self.is_bootstrap_admin(&members) => true,
+            (None, None) => true,
+            (catalog_acl, acl) => catalog_acl.is_some_and(|catalog_acl| catalog_acl.allows(&members, permission))
+                || acl.is_some_and(|acl| acl.allows(&members, permission)),
+        };
+
+        match allowed {
+            true => Ok(()),
+            false => Err(Status::permission_denied(format!("Principal {} lacks {:?} permission on table {}.", principal, permission, table))),
+        }
+    }
+
+    fn is_bootstrap_admin(&self, members: &[String]) -> bool {
+        members.iter().any(|member| self.admin.admins.contains(member))
+    }
+
+    /// Charges one request and `write_bytes` against the table and principal quotas.
+    /// Missing tables are charged to the principal only, so unknown names never get a bucket.
+    pub fn check_quota(&self, table: &str, write_bytes: u64) -> Result<(), Status> {
+        let table = Some(table).filter(|table| self.storage_engine.has_table(Bytes::from(table.to_string())));
+        self.quota_limiter.acquire(table, self.get_principal_id(), write_bytes)
+    }
+
+    /// Charges one request against the principal quota, for calls that are not about a single table.
+    pub fn check_principal_quota(&self) -> Result<(), Status> {
+        self.quota_limiter.acquire(None, self.get_principal_id(), 0)
+    }
+
+    fn get_principal_id(&self) -> Option<&str> {
+        self.principal.as_ref().map(|principal| principal.name.as_str())
+    }
+}
+
+impl<P: PersistanceLayer> Clone for ServerCtx<P> {
+    fn clone(&self) -> Self {
+        ServerCtx {
+            storage_engine: self.storage_engine.clone(),
+            principal: self.principal.clone(),
+            quota_limiter: self.quota_limiter.clone(),
+            admin: self.admin.clone(),
+        }
+    }
 }
\ No newline at end of file
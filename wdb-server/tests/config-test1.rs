@@ -0,0 +1,49 @@
+use std::{collections::BTreeMap, fs, net::SocketAddr, path::{Path, PathBuf}};
+
+use wdb_server::{config::{QuotaLimits, ServerConfig}, grpc::TlsOptions};
+
+#[test]
+fn config_validate_test() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("file");
+    fs::write(&file, "").unwrap();
+    let missing = dir.path().join("missing");
+
+    let valid = ServerConfig { data_dir: dir.path().join("data"), ..ServerConfig::default() };
+    assert_eq!(valid.validate(), Ok(()));
+    assert_eq!(ServerConfig::from_file(write_config(&dir.path().join("wdb.toml"), &valid.to_toml())).unwrap(), valid);
+
+    let invalid: Vec<(&str, Box<dyn Fn(&mut ServerConfig)>)> = vec![
+        ("empty data dir", Box::new(|config| config.data_dir = PathBuf::new())),
+        ("data dir is a file", Box::new(|config| config.data_dir = file.clone())),
+        ("log level", Box::new(|config| config.log_level = "warn,wdb_storage_engine=loud".to_string())),
+        ("flush interval", Box::new(|config| config.engine.flush_interval_ms = 0)),
+        ("optional client auth without CA", Box::new(|config| config.tls = Some(TlsOptions { cert_path: file.clone(), key_path: file.clone(), client_ca_path: None, client_auth_optional: true }))),
+        ("missing tokens file", Box::new(|config| config.auth.tokens_file = Some(missing.clone()))),
+        ("metrics port", Box::new(|config| config.metrics.listen_addr = SocketAddr::new(config.metrics.listen_addr.ip(), config.listen_addr.port()))),
+        ("zero quota", Box::new(|config| config.quotas.tables = BTreeMap::from([("users".to_string(), QuotaLimits { requests_per_sec: Some(0), write_bytes_per_sec: None })]))),
+        ("backup root is a file", Box::new(|config| config.admin.backup_root = Some(file.clone()))),
+        ("missing ingest dir", Box::new(|config| config.admin.ingest_dir = Some(missing.clone()))),
+        ("admin member", Box::new(|config| config.admin.admins = vec!["alice".to_string()])),
+    ];
+    for (name, update) in invalid {
+        let mut config = valid.clone();
+        update(&mut config);
+        assert!(config.validate().is_err(), "{}", name);
+    }
+
+    let mut config = valid.clone();
+    config.log_level = "warn,wdb_storage_engine=debug".to_string();
+    config.admin.backup_root = Some(missing.clone());
+    config.admin.ingest_dir = Some(dir.path().to_path_buf());
+    config.admin.admins = vec!["user:alice".to_string(), "role:admins".to_string()];
+    assert_eq!(config.validate(), Ok(()));
+
+    assert!(ServerConfig::from_file(write_config(&dir.path().join("unknown.toml"), "listen_port = 1\n")).is_err());
+    assert!(ServerConfig::from_file(&missing).is_err());
+}
+
+fn write_config(path: &Path, content: &str) -> PathBuf {
+    fs::write(path, content).unwrap();
+    path.to_path_buf()
+}
\ No newline at end of file
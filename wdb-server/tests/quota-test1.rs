@@ -0,0 +1,32 @@
+use std::{collections::BTreeMap, sync::{Arc, Barrier}, thread};
+
+use tonic::Code;
+use wdb_server::{config::{QuotaConfig, QuotaLimits}, quota::QuotaLimiter};
+
+#[test]
+fn quota_acquire_test() {
+    let limits = |requests_per_sec| QuotaLimits { requests_per_sec: Some(requests_per_sec), write_bytes_per_sec: None };
+    let limiter = QuotaLimiter::new(QuotaConfig {
+        tables: BTreeMap::from([("users".to_string(), limits(2))]),
+        principals: BTreeMap::from([("alice".to_string(), limits(1))]),
+        ..QuotaConfig::default()
+    });
+
+    // A call rejected by the principal quota takes nothing from the table quota.
+    limiter.acquire(Some("users"), Some("alice"), 0).unwrap();
+    assert_eq!(limiter.acquire(Some("users"), Some("alice"), 0).unwrap_err().code(), Code::ResourceExhausted);
+    limiter.acquire(Some("users"), Some("bob"), 0).unwrap();
+    assert_eq!(limiter.acquire(Some("users"), Some("bob"), 0).unwrap_err().code(), Code::ResourceExhausted);
+
+    let limiter = Arc::new(QuotaLimiter::new(QuotaConfig { default_table: limits(5), ..QuotaConfig::default() }));
+    let barrier = Arc::new(Barrier::new(32));
+    let handles = (0..32).map(|_| {
+        let (limiter, barrier) = (limiter.clone(), barrier.clone());
+        thread::spawn(move || {
+            barrier.wait();
+            limiter.acquire(Some("orders"), None, 0).is_ok()
+        })
+    }).collect::<Vec<_>>();
+    let admitted = handles.into_iter().map(|handle| handle.join().unwrap()).filter(|admitted| *admitted).count();
+    assert_eq!(admitted, 5);
+}
\ No newline at end of file
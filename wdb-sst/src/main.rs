@@ -0,0 +1,215 @@
+mod segment_files;
+
+use std::{fs::File, path::PathBuf, process};
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::json;
+use wdb_storage_engine::{InspectedCell, SSTableInspector, Statistics};
+
+use segment_files::find_segment_files;
+
+#[derive(Parser)]
+#[command(name = "wdb-sst", about = "Inspects and verifies WideDB segment files.")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Dumps the footer, index and decoded cells of segments.
+    Dump {
+        /// Segment file, family directory or data root.
+        path: PathBuf,
+        /// Prints only the footer and index, without reading data blocks.
+        #[arg(long)]
+        index_only: bool,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Verifies magic, index bounds, key ordering and properties of segments.
+    Verify {
+        /// Segment file, family directory or data root.
+        path: PathBuf,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Prints statistics stored in segment properties.
+    Stats {
+        /// Segment file, family directory or data root.
+        path: PathBuf,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct StatsOutput {
+    cell_count: u64,
+    row_count: u64,
+    put_count: u64,
+    delete_count: u64,
+    delete_column_count: u64,
+    delete_family_count: u64,
+    min_timestamp: Option<u64>,
+    max_timestamp: Option<u64>,
+    min_row: Option<String>,
+    max_row: Option<String>,
+    raw_bytes: u64,
+    disk_bytes: u64,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let ok = match args.command {
+        Command::Dump { path, index_only, json } => for_each_segment(&path, |path, inspector| dump(path, inspector, index_only, json)),
+        Command::Verify { path, json } => for_each_segment(&path, |path, inspector| verify(path, inspector, json)),
+        Command::Stats { path, json } => for_each_segment(&path, |path, inspector| stats(path, inspector, json)),
+    };
+
+    if !ok {
+        process::exit(1);
+    }
+}
+
+fn for_each_segment<F: FnMut(&PathBuf, &mut SSTableInspector<File>) -> bool>(path: &PathBuf, mut f: F) -> bool {
+    let files = match find_segment_files(path) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("{}: {}", path.display(), err);
+            return false;
+        }
+    };
+
+    let mut ok = true;
+    for file in files {
+        let mut inspector = match File::open(&file).map_err(|err| err.to_string()).and_then(|r| SSTableInspector::open(r).map_err(|err| err.to_string())) {
+            Ok(inspector) => inspector,
+            Err(err) => {
+                eprintln!("{}: {}", file.display(), err);
+                ok = false;
+                continue;
+            }
+        };
+        ok &= f(&file, &mut inspector);
+    }
+    ok
+}
+
+fn dump(path: &PathBuf, inspector: &mut SSTableInspector<File>, index_only: bool, json: bool) -> bool {
+    let footer = inspector.get_footer();
+    let blocks = inspector.get_blocks();
+
+    let mut ok = true;
+    let mut cells: Vec<Result<Vec<InspectedCell>, &'static str>> = vec![];
+    if !index_only {
+        for i in 0..blocks.len() {
+            let res = inspector.read_block(i);
+            ok &= res.is_ok();
+            cells.push(res);
+        }
+    }
+
+    if json {
+        let blocks = blocks.iter().enumerate().map(|(i, block)| {
+            match cells.get(i) {
+                None => json!({ "block": block }),
+                Some(Ok(cells)) => json!({ "block": block, "cells": cells }),
+                Some(Err(err)) => json!({ "block": block, "error": err }),
+            }
+        }).collect::<Vec<_>>();
+        println!("{}", json!({ "path": path, "footer": footer, "blocks": blocks }));
+        return ok;
+    }
+
+    println!("== {}", path.display());
+    println!("footer: version={} size={} index_pos={} index_len={} props_pos={:?} props_len={:?} bloom_pos={:?} bloom_len={:?} compression={:?} max_mvcc={}",
+        footer.version, footer.size, footer.index_pos, footer.index_len, footer.props_pos, footer.props_len, footer.bloom_pos, footer.bloom_len, footer.compression, footer.max_mvcc);
+    for (i, block) in blocks.iter().enumerate() {
+        let crc32 = block.crc32.map_or("none".to_string(), |crc32| format!("{:08x}", crc32));
+        println!("block {}: offset={} size={} crc32={} first_key={}", i, block.offset, block.data_size, crc32, format_key(&block.first_key));
+        match cells.get(i) {
+            None => {},
+            Some(Ok(cells)) => for cell in cells {
+                println!("  {} type={} mvcc={} value={} visibility={}", format_key(cell), cell.cell_type, cell.mvcc_id, cell.value, cell.visibility);
+            },
+            Some(Err(err)) => println!("  error: {}", err),
+        }
+    }
+    ok
+}
+
+fn verify(path: &PathBuf, inspector: &mut SSTableInspector<File>, json: bool) -> bool {
+    let report = inspector.verify();
+    let ok = report.errors.is_empty();
+
+    if json {
+        println!("{}", json!({ "path": path, "ok": ok, "report": report }));
+        return ok;
+    }
+
+    match ok {
+        true => println!("{}: OK ({} blocks, {} cells)", path.display(), report.blocks, report.cells),
+        false => {
+            println!("{}: FAILED ({} blocks, {} cells)", path.display(), report.blocks, report.cells);
+            for err in report.errors.iter() {
+                println!("  {}", err);
+            }
+        }
+    }
+    ok
+}
+
+fn stats(path: &PathBuf, inspector: &mut SSTableInspector<File>, json: bool) -> bool {
+    let stats = match inspector.read_stats() {
+        Ok(Some(stats)) => stats_output(stats),
+        Ok(None) => {
+            eprintln!("{}: segment has no properties block (format version 1).", path.display());
+            return true;
+        },
+        Err(err) => {
+            eprintln!("{}: {}", path.display(), err);
+            return false;
+        }
+    };
+
+    if json {
+        println!("{}", json!({ "path": path, "stats": stats }));
+        return true;
+    }
+
+    println!("== {}", path.display());
+    println!("cells: {} (put {}, delete {}, delete column {}, delete family {})", 
+        stats.cell_count, stats.put_count, stats.delete_count, stats.delete_column_count, stats.delete_family_count);
+    println!("rows: {} [{} .. {}]", stats.row_count, stats.min_row.unwrap_or_default(), stats.max_row.unwrap_or_default());
+    println!("timestamps: [{} .. {}]", stats.min_timestamp.map_or("-".to_string(), |ts| ts.to_string()), stats.max_timestamp.map_or("-".to_string(), |ts| ts.to_string()));
+    println!("bytes: raw {}, disk {}", stats.raw_bytes, stats.disk_bytes);
+    true
+}
+
+fn stats_output(stats: Statistics) -> StatsOutput {
+    let escape = |bytes: &[u8]| -> String {
+        bytes.iter().flat_map(|b| std::ascii::escape_default(*b)).map(char::from).collect()
+    };
+
+    StatsOutput {
+        cell_count: stats.cell_count,
+        row_count: stats.row_count,
+        put_count: stats.put_count,
+        delete_count: stats.delete_count,
+        delete_column_count: stats.delete_column_count,
+        delete_family_count: stats.delete_family_count,
+        min_timestamp: stats.min_timestamp,
+        max_timestamp: stats.max_timestamp,
+        min_row: stats.min_row.map(|row| escape(&row)),
+        max_row: stats.max_row.map(|row| escape(&row)),
+        raw_bytes: stats.raw_bytes,
+        disk_bytes: stats.disk_bytes,
+    }
+}
+
+fn format_key(cell: &InspectedCell) -> String {
+    format!("{}/{}:{}@{}", cell.row, cell.family, cell.column, cell.timestamp)
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+use std::{fs::read_dir, path::PathBuf};
+
+const SKIPPED_FILES: [&'static str; 2] = ["family.options", "table.options"];
+
+// Segments live in `<table>.table/<family>.family/`, next to the family options file.
+pub fn find_segment_files(path: &PathBuf) -> Result<Vec<PathBuf>, String> {
+    if path.is_file() {
+        return Ok(vec![path.clone()]);
+    }
+
+    let mut results = vec![];
+    let entries = read_dir(path).map_err(|err| err.to_string())?;
+    for entry in entries {
+        let entry = entry.map_err(|err| err.to_string())?.path();
+        let name = entry.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+        if entry.is_dir() {
+            if name.ends_with(".table") || name.ends_with(".family") {
+                results.extend(find_segment_files(&entry)?);
+            }
+            continue;
+        }
+
+        let in_family_dir = path.file_name().is_some_and(|dir| dir.to_string_lossy().ends_with(".family"));
+        if in_family_dir && !SKIPPED_FILES.contains(&name.as_str()) && !name.ends_with(".tmp") {
+            results.push(entry);
+        }
+    }
+    results.sort();
+
+    Ok(results)
+}
\ No newline at end of file
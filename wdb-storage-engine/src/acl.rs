@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Permissions are ordered, each one implies the ones below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Permission {
+    Read,
+    Write,
+    Admin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AclEntry {
+    /// `user:<name>`, `role:<name>` or `*` for every authenticated caller.
+    pub member: String,
+    pub permission: Permission,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Acl {
+    pub entries: Vec<AclEntry>,
+}
+
+impl Acl {
+    pub const ALL_MEMBERS: &'static str = "*";
+
+    pub fn new(entries: Vec<AclEntry>) -> Acl {
+        Acl { entries }
+    }
+
+    pub fn allows<T: AsRef<str>>(&self, members: &[T], permission: Permission) -> bool {
+        self.entries.iter().any(|entry| {
+            entry.permission >= permission
+                && (entry.member == Acl::ALL_MEMBERS || members.iter().any(|member| member.as_ref() == entry.member))
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,71 @@
+use std::{fs, io::{self, ErrorKind}, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub id: String,
+    pub parent: Option<String>,
+    pub created_at: u64,
+    pub tables: Vec<BackupTable>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupTable {
+    pub name: String,
+    pub mvcc_read_point: u64,
+    pub families: Vec<BackupFamily>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupFamily {
+    pub name: String,
+    pub segments: Vec<BackupSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupSegment {
+    pub name: String,
+    pub size: u64,
+    pub crc32: u32,
+}
+
+impl BackupManifest {
+    pub const FILE_NAME: &'static str = "manifest.json";
+
+    pub fn read(backup_dir: &Path) -> io::Result<BackupManifest> {
+        let data = match fs::read(backup_dir.join(BackupManifest::FILE_NAME)) {
+            Err(err) if err.kind() == ErrorKind::NotFound => return Err(io::Error::new(ErrorKind::NotFound, "Backup manifest not found.")),
+            Err(err) => return Err(err),
+            Ok(data) => data,
+        };
+
+        serde_json::from_slice(&data).map_err(|_| io::Error::new(ErrorKind::InvalidData, "Backup manifest is corrupted."))
+    }
+
+    // Manifest is written last and atomically, so a backup without one is incomplete.
+    pub fn write(&self, backup_dir: &Path) -> io::Result<()> {
+        let path = backup_dir.join(BackupManifest::FILE_NAME);
+        let tmp_path = path.with_extension("tmp");
+
+        fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(tmp_path, path)
+    }
+
+    pub fn find_segment(&self, table: &str, family: &str, segment: &str) -> Option<&BackupSegment> {
+        self.tables.iter()
+            .filter(|t| t.name == table)
+            .flat_map(|t| t.families.iter())
+            .filter(|f| f.name == family)
+            .flat_map(|f| f.segments.iter())
+            .find(|s| s.name == segment)
+    }
+
+    pub fn get_segments_count(&self) -> usize {
+        self.tables.iter().flat_map(|t| t.families.iter()).map(|f| f.segments.len()).sum()
+    }
+
+    pub fn get_size(&self) -> u64 {
+        self.tables.iter().flat_map(|t| t.families.iter()).flat_map(|f| f.segments.iter()).map(|s| s.size).sum()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,176 @@
+mod backup_manifest;
+
+pub use backup_manifest::{BackupManifest, BackupTable, BackupFamily, BackupSegment};
+
+use std::{ffi::OsStr, fs::{self, read_dir}, io::{self, ErrorKind}, path::Path};
+
+use bytes::Bytes;
+use log::{debug, info};
+use uuid::Uuid;
+
+use crate::{fs_persistance::{link_or_copy, FSPersistance, StoragePaths}, PersistanceLayer, StorageEngine, Timestamp};
+
+/*
+Backup directory mirrors the data root layout, so restoring is a matter of linking files back:
+- <backup_root>/<backup_id>/manifest.json
+- <backup_root>/<backup_id>/catalog.acl
+- <backup_root>/<backup_id>/<table>.table/table.options
+- <backup_root>/<backup_id>/<table>.table/<family>.family/family.options
+- <backup_root>/<backup_id>/<table>.table/<family>.family/<segment>
+ */
+pub fn create_backup<P: PersistanceLayer>(storage_engine: &StorageEngine<P>, backup_root: &Path, parent: Option<&str>) -> io::Result<BackupManifest> {
+    let parent = match parent {
+        // Parent id names a directory right under the backup root.
+        Some(parent) if Path::new(parent).file_name() != Some(OsStr::new(parent)) => {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "Invalid parent backup id."));
+        },
+        Some(parent) => Some(BackupManifest::read(&backup_root.join(parent))?),
+        None => None,
+    };
+
+    let backup_id = Uuid::now_v7().to_string();
+    let backup_dir = backup_root.join(&backup_id);
+    let paths = StoragePaths::new(&backup_dir);
+    let persistance = storage_engine.get_persitance_layer();
+    info!("Creating backup {:?}", backup_dir);
+
+    let table_names = storage_engine.get_tables_iter().map(|table| table.get_name()).collect::<Vec<Bytes>>();
+
+    let mut tables = vec![];
+    for table_name in table_names {
+        // Table is held exclusively, so no mutation is in flight and the read point is consistent.
+        let table = match storage_engine.get_table(table_name.clone()) {
+            Some(table) => table,
+            None => continue,
+        };
+        let name = to_utf8(&table_name)?;
+
+        let mut families = vec![];
+        for family in table.get_families_iter() {
+            if family.get_memtable_size() > 0 {
+                family.flush_memtable(&table_name, persistance);
+            }
+
+            let family_name = family.get_name();
+            let family_str = to_utf8(&family_name)?;
+
+            let mut segments = vec![];
+            for sstable in family.get_segments().iter() {
+                let segment_str = to_utf8(sstable.get_segment())?;
+                let dest = paths.get_segment_file(&table_name, &family_name, sstable.get_segment());
+
+                let reused = parent.as_ref().and_then(|parent| parent.find_segment(&name, &family_str, &segment_str));
+                match reused {
+                    Some(segment) => {
+                        let parent_paths = StoragePaths::new(backup_root.join(&parent.as_ref().unwrap().id));
+                        let src = parent_paths.get_segment_file(&table_name, &family_name, sstable.get_segment());
+                        link_or_copy(&src, &dest)?;
+                        segments.push(segment.clone());
+                    },
+                    None => {
+                        persistance.link_segment(&table_name, &family_name, sstable.get_segment(), &dest)?;
+                        segments.push(BackupSegment { name: segment_str, size: sstable.get_size(), crc32: 0 });
+                    },
+                }
+            }
+
+            FSPersistance::write_options_file(paths.get_family_options_file(&table_name, &family_name), &*family.get_options());
+            families.push(BackupFamily { name: family_str, segments });
+        }
+
+        FSPersistance::write_options_file(paths.get_table_options_file(&table_name), &*table.get_options());
+        tables.push(BackupTable { name, mvcc_read_point: table.mvcc_get_read_point(), families });
+    }
+
+    // Checksums of new segments are computed after tables are released; linked segments never change.
+    for table in tables.iter_mut() {
+        let table_name = Bytes::from(table.name.clone());
+        for family in table.families.iter_mut() {
+            let family_name = Bytes::from(family.name.clone());
+            for segment in family.segments.iter_mut() {
+                let reused = parent.as_ref().is_some_and(|parent| parent.find_segment(&table.name, &family.name, &segment.name).is_some());
+                if !reused {
+                    let path = paths.get_segment_file(&table_name, &family_name, &Bytes::from(segment.name.clone()));
+                    segment.crc32 = crc32fast::hash(&fs::read(path)?);
+                }
+            }
+        }
+    }
+
+    if let Some(acl) = storage_engine.get_catalog_acl() {
+        FSPersistance::write_options_file(paths.get_catalog_acl_file(), &*acl);
+    }
+
+    let manifest = BackupManifest {
+        id: backup_id,
+        parent: parent.map(|parent| parent.id),
+        created_at: Timestamp::ensure_timestamp(None).into(),
+        tables,
+    };
+    fs::create_dir_all(&backup_dir)?;
+    manifest.write(&backup_dir)?;
+    info!("Backup {} created. Segments: {}", manifest.id, manifest.get_segments_count());
+
+    Ok(manifest)
+}
+
+pub fn restore_backup(backup_dir: &Path, data_root: &Path) -> io::Result<BackupManifest> {
+    let manifest = BackupManifest::read(backup_dir)?;
+    let backup_paths = StoragePaths::new(backup_dir);
+    let paths = StoragePaths::new(data_root);
+
+    match read_dir(data_root) {
+        Err(err) if err.kind() == ErrorKind::NotFound => {},
+        Err(err) => return Err(err),
+        Ok(entries) => {
+            for entry in entries {
+                if entry?.file_name().to_string_lossy().ends_with(".table") {
+                    return Err(io::Error::new(ErrorKind::AlreadyExists, "Data root is not empty."));
+                }
+            }
+        }
+    }
+
+    for table in manifest.tables.iter() {
+        let table_name = Bytes::from(table.name.clone());
+        for family in table.families.iter() {
+            let family_name = Bytes::from(family.name.clone());
+            for segment in family.segments.iter() {
+                let path = backup_paths.get_segment_file(&table_name, &family_name, &Bytes::from(segment.name.clone()));
+                let data = fs::read(path).map_err(|err| io::Error::new(err.kind(), "Backup segment is missing."))?;
+                if crc32fast::hash(&data) != segment.crc32 {
+                    return Err(io::Error::new(ErrorKind::InvalidData, "Backup segment checksum mismatch."));
+                }
+            }
+        }
+    }
+
+    info!("Restoring backup {} into {:?}", manifest.id, data_root);
+    if backup_paths.get_catalog_acl_file().exists() {
+        link_or_copy(&backup_paths.get_catalog_acl_file(), &paths.get_catalog_acl_file())?;
+    }
+
+    for table in manifest.tables.iter() {
+        let table_name = Bytes::from(table.name.clone());
+        fs::create_dir_all(paths.table_dir(&table_name))?;
+        link_or_copy(&backup_paths.get_table_options_file(&table_name), &paths.get_table_options_file(&table_name))?;
+
+        for family in table.families.iter() {
+            let family_name = Bytes::from(family.name.clone());
+            fs::create_dir_all(paths.get_family_dir(&table_name, &family_name))?;
+            link_or_copy(&backup_paths.get_family_options_file(&table_name, &family_name), &paths.get_family_options_file(&table_name, &family_name))?;
+
+            for segment in family.segments.iter() {
+                let segment = Bytes::from(segment.name.clone());
+                debug!("Restoring segment {:?}", segment);
+                link_or_copy(&backup_paths.get_segment_file(&table_name, &family_name, &segment), &paths.get_segment_file(&table_name, &family_name, &segment))?;
+            }
+        }
+    }
+
+    Ok(manifest)
+}
+
+fn to_utf8(name: &Bytes) -> io::Result<String> {
+    String::from_utf8(name.to_vec()).map_err(|_| io::Error::new(ErrorKind::InvalidData, "Backed up names must be valid UTF-8."))
+}
\ No newline at end of file
@@ -16,6 +16,7 @@ pub trait Cell {
     fn get_key_without_cell_type(&self) -> &[u8];
     fn get_key_row_cf_col(&self) -> &[u8];
     fn get_value(&self) -> &[u8];
+    fn get_visibility(&self) -> &[u8];
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
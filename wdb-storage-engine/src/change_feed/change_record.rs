@@ -0,0 +1,54 @@
+use std::io::Read;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+
+use crate::{RowMutationOp, Timestamp};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeRecord {
+    pub write_num: u64,
+    pub commit_ts: Timestamp,
+    pub row: Bytes,
+    pub ops: Vec<RowMutationOp>,
+}
+
+impl ChangeRecord {
+    /*
+    Change log record structure:
+    - record_length: u32
+    - record (bincode)
+     */
+    pub fn as_bytes(&self) -> Bytes {
+        let record = bincode::serialize(self).unwrap();
+
+        let mut buf = BytesMut::with_capacity(4 + record.len());
+        buf.put_u32(record.len() as u32);
+        buf.put(&record[..]);
+
+        buf.freeze()
+    }
+
+    // Torn record at the end of a log is left by a crash during append and is skipped.
+    pub fn read_all<R: Read>(mut r: R) -> Vec<ChangeRecord> {
+        let mut buf = vec![];
+        r.read_to_end(&mut buf).unwrap();
+        let mut buf = Bytes::from(buf);
+
+        let mut results = vec![];
+        while buf.remaining() >= 4 {
+            let len = buf.get_u32() as usize;
+            if buf.remaining() < len {
+                break;
+            }
+
+            match bincode::deserialize::<ChangeRecord>(&buf[..len]) {
+                Ok(record) => results.push(record),
+                Err(_) => break,
+            }
+            buf.advance(len);
+        }
+
+        results
+    }
+}
\ No newline at end of file
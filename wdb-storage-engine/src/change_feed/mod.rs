@@ -0,0 +1,183 @@
+mod change_record;
+
+pub use change_record::ChangeRecord;
+
+use std::sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex};
+
+use bytes::Bytes;
+use log::debug;
+use tokio::sync::watch;
+
+use crate::{table::{ChangeFeedOptions, MVCCWriteEntry}, ChangeLogWrite, PersistanceLayer, RowMutationOp, Timestamp};
+
+const MAX_LOG_SIZE: usize = 4 * 1024 * 1024;
+
+pub struct ChangeFeed {
+    table: Bytes,
+    retention_ms: AtomicU64,
+    writer: Mutex<Option<ChangeLogWriter>>,
+    logs: Mutex<Vec<ChangeLog>>,
+    visible_write_num: watch::Sender<u64>,
+}
+
+struct ChangeLogWriter {
+    w: Box<dyn ChangeLogWrite + Send>,
+    size: usize,
+}
+
+#[derive(Debug, Clone)]
+struct ChangeLog {
+    name: Bytes,
+    first_write_num: u64,
+    last_write_num: u64,
+    last_commit_ts: Timestamp,
+}
+
+impl ChangeFeed {
+    pub fn open<P: PersistanceLayer>(persistance: &P, table: &Bytes, options: &ChangeFeedOptions) -> ChangeFeed {
+        let mut logs = vec![];
+        for name in persistance.get_change_logs_list(table) {
+            let records = match persistance.get_change_log_read(table, &name) {
+                Some(r) => ChangeRecord::read_all(r),
+                None => continue,
+            };
+
+            match (records.first(), records.last()) {
+                (Some(first), Some(last)) => logs.push(ChangeLog { 
+                    name, 
+                    first_write_num: first.write_num, 
+                    last_write_num: last.write_num, 
+                    last_commit_ts: last.commit_ts,
+                }),
+                _ => persistance.delete_change_log(table, &name),
+            }
+        }
+        logs.sort_by_key(|log| log.first_write_num);
+
+        let last_write_num = logs.last().map_or(0, |log| log.last_write_num);
+        let feed = ChangeFeed {
+            table: table.clone(),
+            retention_ms: AtomicU64::new(options.retention_ms),
+            writer: Mutex::new(None),
+            logs: Mutex::new(logs),
+            visible_write_num: watch::channel(last_write_num).0,
+        };
+        feed.prune(persistance);
+
+        feed
+    }
+
+    pub fn set_options(&self, options: &ChangeFeedOptions) {
+        self.retention_ms.store(options.retention_ms, Ordering::Relaxed);
+    }
+
+    pub fn get_last_write_num(&self) -> u64 {
+        self.logs.lock().unwrap().last().map_or(0, |log| log.last_write_num)
+    }
+
+    // MVCC write number is taken under the writer lock, so records are appended in commit order.
+    pub fn append<P: PersistanceLayer, F: FnOnce() -> Arc<MVCCWriteEntry>>(&self, persistance: &P, new_write: F, row: &Bytes, ops: &[RowMutationOp]) -> Arc<MVCCWriteEntry> {
+        let mut writer = self.writer.lock().unwrap();
+
+        let write_entry = new_write();
+        let record = ChangeRecord {
+            write_num: write_entry.get_write_num(),
+            commit_ts: Timestamp::ensure_timestamp(None),
+            row: row.clone(),
+            ops: ops.to_vec(),
+        };
+        let buf = record.as_bytes();
+
+        let roll = match &*writer {
+            None => true,
+            Some(writer) => writer.size >= MAX_LOG_SIZE,
+        };
+        if roll {
+            let name = Bytes::from(format!("{:020}.log", record.write_num));
+            debug!("Starting new change log {:?}", name);
+
+            *writer = Some(ChangeLogWriter { w: Box::new(persistance.get_change_log_write(&self.table, &name)), size: 0 });
+            self.logs.lock().unwrap().push(ChangeLog { 
+                name, 
+                first_write_num: record.write_num, 
+                last_write_num: record.write_num, 
+                last_commit_ts: record.commit_ts,
+            });
+            self.prune(persistance);
+        }
+
+        let writer = writer.as_mut().unwrap();
+        writer.w.write_all(&buf).unwrap();
+        writer.w.flush().unwrap();
+        writer.w.sync().unwrap();
+        writer.size += buf.len();
+
+        let mut logs = self.logs.lock().unwrap();
+        let log = logs.last_mut().unwrap();
+        log.last_write_num = record.write_num;
+        log.last_commit_ts = record.commit_ts;
+
+        write_entry
+    }
+
+    pub fn publish(&self, read_point: u64) {
+        self.visible_write_num.send_replace(read_point);
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.visible_write_num.subscribe()
+    }
+
+    pub fn read<P: PersistanceLayer>(&self, persistance: &P, cursor: u64, limit: usize) -> Result<Vec<ChangeRecord>, &'static str> {
+        let visible = *self.visible_write_num.borrow();
+        let logs = self.logs.lock().unwrap().clone();
+
+        if let Some(oldest) = logs.first() {
+            if cursor != 0 && cursor + 1 < oldest.first_write_num {
+                return Err("Continuation token has expired.");
+            }
+        }
+
+        let mut results = vec![];
+        for log in logs.iter().filter(|log| log.last_write_num > cursor) {
+            if log.first_write_num > visible {
+                break;
+            }
+
+            let records = match persistance.get_change_log_read(&self.table, &log.name) {
+                Some(r) => ChangeRecord::read_all(r),
+                None => continue,
+            };
+            for record in records {
+                if record.write_num <= cursor {
+                    continue;
+                }
+                if record.write_num > visible || results.len() >= limit {
+                    return Ok(results);
+                }
+                results.push(record);
+            }
+        }
+
+        Ok(results)
+    }
+
+    pub fn destroy<P: PersistanceLayer>(&self, persistance: &P) {
+        let _writer = self.writer.lock().unwrap();
+        for log in self.logs.lock().unwrap().drain(..) {
+            persistance.delete_change_log(&self.table, &log.name);
+        }
+    }
+
+    pub fn prune<P: PersistanceLayer>(&self, persistance: &P) {
+        let now: u64 = Timestamp::ensure_timestamp(None).into();
+        let cutoff = Timestamp::from(now.saturating_sub(self.retention_ms.load(Ordering::Relaxed)));
+
+        let mut logs = self.logs.lock().unwrap();
+        while logs.len() > 1 && logs[0].last_commit_ts < cutoff {
+            let log = logs.remove(0);
+            debug!("Removing expired change log {:?}", log.name);
+            persistance.delete_change_log(&self.table, &log.name);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,177 @@
+use std::{collections::HashMap, sync::Arc};
+
+use bytes::Bytes;
+use itertools::{kmerge, Itertools};
+use log::info;
+use uuid::Uuid;
+
+use crate::{cell::CellType, delete_tracker::DeleteTracker, gc_tracker::GcTracker, key_value::KeyValue, metrics, table::FamilyOptions, utils::sstable::{SSTable, SSTableReader, SSTableWriter}, Cell, PersistanceLayer, StorageEngine};
+
+#[derive(Debug, Clone)]
+pub struct CompactionResult {
+    pub family: Bytes,
+    pub removed_segments: Vec<Bytes>,
+    pub added_segment: Option<Bytes>,
+    pub cells_read: u64,
+    pub cells_written: u64,
+}
+
+struct CompactionInput {
+    family: Bytes,
+    segments: Vec<SSTable>,
+    options: Arc<FamilyOptions>,
+    // Every segment of the family takes part and the memtable is empty, so tombstones have nothing left to delete.
+    major: bool,
+}
+
+/*
+Segments of the family that may hold rows in [start, end] are rewritten into a single segment:
+- versions of a key shadowed by a newer visible version are dropped,
+- cells deleted by a visible tombstone are dropped,
+- tombstones themselves are dropped only by a major compaction,
+- versions older than the family max age are dropped,
+- versions past the family max versions are dropped only by a major compaction, a tombstone outside of it could uncover them,
+- cells above the read point are kept untouched, their writes are still in flight.
+ */
+pub fn compact_range<P: PersistanceLayer>(storage_engine: &StorageEngine<P>, table: Bytes, family: Option<Bytes>, start: Option<Bytes>, end: Option<Bytes>) -> Result<Vec<CompactionResult>, &'static str> {
+    let start = start.map(|row| KeyValue::new_first_on_row(&row));
+    let end = end.map(|row| KeyValue::new_last_on_row(&row));
+
+    // Segments are selected while holding the table, the merge itself runs without it, like ingestion does.
+    let (read_point, inputs) = {
+        let table = storage_engine.get_table(table.clone()).ok_or("Table with this name does not exist.")?;
+        let families = match &family {
+            Some(family) => {
+                table.get_family(family).ok_or("Family with this name does not exist.")?;
+                vec![family.clone()]
+            },
+            None => table.get_families_iter().map(|family| family.get_name()).collect_vec(),
+        };
+
+        let inputs = families.into_iter().filter_map(|name| {
+            let family = table.get_family(&name)?;
+            let all_segments = family.get_segments();
+            let segments = all_segments.iter()
+                .filter(|sstable| sstable.may_contain_rows(&start, &end))
+                .cloned()
+                .collect_vec();
+            let major = segments.len() == all_segments.len() && family.get_memtable_size() == 0;
+            Some(CompactionInput { family: name, segments, options: family.get_options(), major })
+        }).collect_vec();
+
+        (table.mvcc_get_read_point(), inputs)
+    };
+
+    let persistance = storage_engine.get_persitance_layer();
+    let mut results = vec![];
+    for input in inputs {
+        if input.segments.is_empty() {
+            results.push(CompactionResult { family: input.family, removed_segments: vec![], added_segment: None, cells_read: 0, cells_written: 0 });
+            continue;
+        }
+
+        let (added, cells_read, cells_written) = merge_segments(persistance, &table, &input, read_point)?;
+        let removed_segments = input.segments.iter().map(|sstable| sstable.get_segment().clone()).collect_vec();
+        let added_segment = added.as_ref().map(|sstable| sstable.get_segment().clone());
+
+        let replaced = match storage_engine.get_table(table.clone()) {
+            None => false,
+            Some(table) => match table.get_family(&input.family) {
+                None => false,
+                Some(family) => {
+                    family.replace_segments(&removed_segments, added);
+                    true
+                },
+            },
+        };
+        if !replaced {
+            if let Some(segment) = &added_segment {
+                persistance.delete_segment(&table, &input.family, segment);
+            }
+            return Err("Table or family was dropped during compaction.");
+        }
+
+        // Scans and backups may still read the removed segments, so they are deleted once the last reader is gone.
+        storage_engine.retire_segments(&input.segments);
+
+        metrics::COMPACTIONS.inc();
+        metrics::COMPACTION_CELLS_DROPPED.inc_by(cells_read - cells_written);
+        info!("Compacted {} segments of table {} family {}, {} of {} cells kept", removed_segments.len(), String::from_utf8_lossy(&table), String::from_utf8_lossy(&input.family), cells_written, cells_read);
+
+        results.push(CompactionResult { family: input.family, removed_segments, added_segment, cells_read, cells_written });
+    }
+
+    Ok(results)
+}
+
+fn merge_segments<P: PersistanceLayer>(persistance: &P, table: &Bytes, input: &CompactionInput, read_point: u64) -> Result<(Option<SSTable>, u64, u64), &'static str> {
+    // A corrupt block fails the compaction and leaves the input segments in place.
+    let iters = input.segments.iter().map(|sstable| {
+        let mut reader = SSTableReader::new(persistance.get_segment_read(sstable.get_table(), sstable.get_family(), sstable.get_segment()));
+        reader.read_blocks(sstable.get_blocks(None, None)).map(|kvs| kvs.into_iter())
+    }).collect::<Result<Vec<_>, &'static str>>()?;
+
+    let mut delete_tracker = DeleteTracker::new();
+    let mut gc_tracker = GcTracker::new(HashMap::from([(input.family.to_vec(), input.options.gc_policy.clone())]), input.major);
+    let mut current_row: Vec<u8> = vec![];
+    let mut last_key: Option<Vec<u8>> = None;
+    let mut cells_read = 0;
+    let kvs = kmerge(iters).filter(|kv| {
+        cells_read += 1;
+        if kv.get_mvcc_id() > read_point {
+            return true;
+        }
+
+        if kv.get_row() != current_row {
+            delete_tracker.reset();
+            gc_tracker.reset();
+            current_row = kv.get_row().to_vec();
+        }
+
+        if last_key.as_deref() == Some(kv.get_key()) {
+            return false;
+        }
+        last_key = Some(kv.get_key().to_vec());
+
+        match kv.get_cell_type() {
+            CellType::Put => !delete_tracker.is_deleted(kv) && !gc_tracker.is_collected(kv),
+            _ => {
+                delete_tracker.add(kv);
+                !input.major
+            },
+        }
+    }).collect_vec();
+
+    let cells_written = kvs.len() as u64;
+    if kvs.is_empty() {
+        return Ok((None, cells_read, cells_written));
+    }
+
+    // The output is written under a temporary name, recovery skips it until it is complete.
+    let segment = Bytes::from(Uuid::now_v7().to_string());
+    let tmp_segment = Bytes::from(format!("{}.tmp", String::from_utf8_lossy(&segment)));
+    let mut write = persistance.get_segment_write(table, input.family.clone(), &tmp_segment);
+    let mut writer = SSTableWriter::with_options(&mut write, &input.options);
+    // Recovery derives the table write point from segment footers, so dropped cells must not lower it.
+    writer.raise_max_mvcc_id(input.segments.iter().map(|sstable| sstable.get_max_mvcc_id()).max().unwrap());
+    for kv in kvs.iter() {
+        writer.write_kv(kv);
+    }
+
+    let index = writer.end();
+    let max_mvcc = writer.get_max_mvcc_id();
+    let size = writer.get_size();
+    let stats = writer.get_stats().clone();
+    let bloom_filter = writer.get_bloom_filter();
+    drop(writer);
+    drop(write);
+
+    if persistance.commit_segment(table, &input.family, &tmp_segment, &segment).is_err() {
+        persistance.delete_segment(table, &input.family, &tmp_segment);
+        return Err("Unable to write compacted segment.");
+    }
+
+    let sstable = SSTable::new(table, &input.family, &segment, index, max_mvcc, size, Some(stats))
+        .with_bloom_filter(bloom_filter);
+    Ok((Some(sstable), cells_read, cells_written))
+}
\ No newline at end of file
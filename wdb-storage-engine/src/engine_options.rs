@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineOptions {
+    /// Runs the background agent flushing memtables that grew past `memtable_flush_bytes`.
+    pub flush_agent: bool,
+    pub flush_interval_ms: u64,
+    pub memtable_flush_bytes: u64,
+}
+
+impl EngineOptions {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.flush_interval_ms == 0 {
+            return Err("Flush interval must be greater than zero.");
+        }
+        if self.memtable_flush_bytes == 0 {
+            return Err("Memtable flush threshold must be greater than zero.");
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        EngineOptions { flush_agent: true, flush_interval_ms: 5000, memtable_flush_bytes: 8 * 1024 * 1024 }
+    }
+}
\ No newline at end of file
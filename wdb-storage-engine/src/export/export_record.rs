@@ -0,0 +1,82 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::{cell::Cell, key_value::KeyValue, visibility::ColumnVisibility, FamilyOptions};
+
+/*
+Export file structure (JSON Lines):
+- first line: ExportHeader
+- every next line: ExportRecord, one per cell version, in key order
+Keys, values and labels are arbitrary bytes, so they are written as base64, family names in the header as well.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportHeader {
+    pub format: String,
+    pub version: u32,
+    pub table: String,
+    pub families: Vec<(String, FamilyOptions)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportRecord {
+    pub row: String,
+    pub family: String,
+    pub column: String,
+    pub timestamp: u64,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub visibility: String,
+}
+
+impl ExportHeader {
+    pub const FORMAT: &'static str = "wdb-export";
+    pub const VERSION: u32 = 2;
+}
+
+impl ExportRecord {
+    pub fn from_kv(kv: &KeyValue) -> ExportRecord {
+        ExportRecord {
+            row: BASE64_STANDARD.encode(kv.get_row()),
+            family: BASE64_STANDARD.encode(kv.get_cf()),
+            column: BASE64_STANDARD.encode(kv.get_col()),
+            timestamp: kv.get_timestamp().into(),
+            value: BASE64_STANDARD.encode(kv.get_value()),
+            visibility: BASE64_STANDARD.encode(kv.get_visibility()),
+        }
+    }
+
+    pub fn get_row(&self) -> Result<Bytes, &'static str> {
+        decode(&self.row)
+    }
+
+    pub fn get_family(&self) -> Result<Bytes, &'static str> {
+        decode(&self.family)
+    }
+
+    pub fn get_column(&self) -> Result<Bytes, &'static str> {
+        decode(&self.column)
+    }
+
+    pub fn get_value(&self) -> Result<Bytes, &'static str> {
+        decode(&self.value)
+    }
+
+    pub fn get_visibility(&self) -> Result<Option<Bytes>, &'static str> {
+        let visibility = decode(&self.visibility)?;
+        if visibility.is_empty() {
+            return Ok(None);
+        }
+
+        ColumnVisibility::validate(&visibility)?;
+        Ok(Some(visibility))
+    }
+}
+
+pub fn encode(data: &[u8]) -> String {
+    BASE64_STANDARD.encode(data)
+}
+
+pub fn decode(data: &str) -> Result<Bytes, &'static str> {
+    BASE64_STANDARD.decode(data).map(Bytes::from).map_err(|_| "Invalid base64 field in export file.")
+}
\ No newline at end of file
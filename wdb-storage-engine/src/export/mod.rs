@@ -0,0 +1,109 @@
+mod export_record;
+
+pub use export_record::{ExportHeader, ExportRecord};
+
+use std::io::{BufRead, Write};
+
+use bytes::Bytes;
+use log::info;
+use serde::Serialize;
+
+use crate::{key_value::KeyValue, PersistanceLayer, RowMutation, RowMutationOp, StorageEngine, Timestamp};
+
+use export_record::{decode, encode};
+
+pub fn export_table<P: PersistanceLayer, W: Write>(storage_engine: &StorageEngine<P>, table: Bytes, start: Option<Bytes>, end: Option<Bytes>, mut w: W) -> Result<u64, &'static str> {
+    let table = storage_engine.get_table(table).ok_or("Table with this name does not exist.")?;
+
+    let header = ExportHeader {
+        format: ExportHeader::FORMAT.to_string(),
+        version: ExportHeader::VERSION,
+        table: String::from_utf8_lossy(&table.get_name()).to_string(),
+        families: table.get_families_iter().map(|family| {
+            (encode(&family.get_name()), (*family.get_options()).clone())
+        }).collect(),
+    };
+    write_line(&mut w, &header)?;
+
+    // End row is exclusive, so the scan stops before the first key on it.
+    let start = start.map(|row| KeyValue::new_first_on_row(&row));
+    let end = end.map(|row| KeyValue::new_first_on_row(&row));
+
+    let mut count = 0;
+    for kv in table.scan_unfiltered(storage_engine.get_persitance_layer(), start, end, None)? {
+        write_line(&mut w, &ExportRecord::from_kv(&kv))?;
+        count += 1;
+    }
+    w.flush().map_err(|_| "Unable to write export.")?;
+
+    info!("Exported {} cells of table {}", count, header.table);
+    Ok(count)
+}
+
+// Consecutive cells of a row are applied as one mutation; families missing in the table are created from the header.
+pub fn import_table<P: PersistanceLayer, R: BufRead>(storage_engine: &StorageEngine<P>, table: Bytes, r: R) -> Result<u64, &'static str> {
+    let mut lines = r.lines();
+
+    let header = lines.next().ok_or("Export header is missing.")?.map_err(|_| "Unable to read export header.")?;
+    let header: ExportHeader = serde_json::from_str(&header).map_err(|_| "Invalid export header.")?;
+    if header.format != ExportHeader::FORMAT || header.version != ExportHeader::VERSION {
+        return Err("Unsupported export format.");
+    }
+
+    if storage_engine.get_table(table.clone()).is_none() {
+        storage_engine.create_table(table.clone())?;
+    }
+    {
+        let table = storage_engine.get_table(table.clone()).ok_or("Table with this name does not exist.")?;
+        for (family, options) in header.families {
+            let family = decode(&family)?;
+            if table.get_family(&family).is_none() {
+                table.add_family(storage_engine.get_persitance_layer(), family, options)?;
+            }
+        }
+    }
+
+    let mut count = 0;
+    let mut mutation: Option<RowMutation> = None;
+    for line in lines {
+        let line = line.map_err(|_| "Unable to read export record.")?;
+        if line.is_empty() {
+            continue;
+        }
+        let record: ExportRecord = serde_json::from_str(&line).map_err(|_| "Invalid export record.")?;
+
+        let row = record.get_row()?;
+        if mutation.as_ref().is_some_and(|mutation| mutation.row != row) {
+            storage_engine.try_execute_row_mutation(mutation.take().unwrap())?;
+        }
+
+        let mutation = mutation.get_or_insert_with(|| RowMutation { table: table.clone(), row, ops: vec![] });
+        mutation.ops.push(RowMutationOp::Put { 
+            family: record.get_family()?, 
+            column: record.get_column()?, 
+            timestamp: Some(Timestamp::from(record.timestamp)), 
+            value: record.get_value()?,
+            visibility: record.get_visibility()?,
+        });
+        count += 1;
+    }
+    if let Some(mutation) = mutation {
+        storage_engine.try_execute_row_mutation(mutation)?;
+    }
+
+    // Imported data is flushed right away, so it does not depend on memtable flush thresholds.
+    let table = storage_engine.get_table(table).ok_or("Table with this name does not exist.")?;
+    for family in table.get_families_iter() {
+        if family.get_memtable_size() > 0 {
+            family.flush_memtable(&table.get_name(), storage_engine.get_persitance_layer());
+        }
+    }
+
+    info!("Imported {} cells into table {}", count, String::from_utf8_lossy(&table.get_name()));
+    Ok(count)
+}
+
+fn write_line<W: Write, T: Serialize>(w: &mut W, line: &T) -> Result<(), &'static str> {
+    serde_json::to_writer(&mut *w, line).map_err(|_| "Unable to write export.")?;
+    w.write_all(b"\n").map_err(|_| "Unable to write export.")
+}
\ No newline at end of file
@@ -1,18 +1,23 @@
 use std::sync::Arc;
 
 use log::{debug, info};
-use tokio::time::{sleep, Duration};
+use tokio::{sync::oneshot, task::JoinHandle, time::{sleep, Duration}};
 
-use crate::{PersistanceLayer, StorageEngine};
+use crate::{EngineOptions, PersistanceLayer, StorageEngine};
 
 pub struct FlushAgent {
-
+    stop: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
 }
 
 impl FlushAgent {
-    pub fn new<T: PersistanceLayer + Send + Sync + 'static>(storage_engine: Arc<StorageEngine<T>>) {
-        tokio::spawn(async move {
+    pub fn new<T: PersistanceLayer + Send + Sync + 'static>(storage_engine: Arc<StorageEngine<T>>, options: &EngineOptions) -> FlushAgent {
+        let interval = Duration::from_millis(options.flush_interval_ms);
+        let flush_bytes = options.memtable_flush_bytes;
+        let (stop, mut stopped) = oneshot::channel();
+        let handle = tokio::spawn(async move {
             loop {  
+                storage_engine.flush_agent_heartbeat();
                 debug!("Scanning start...");
                 for table in storage_engine.get_tables_iter() {
                     let table_name = std::str::from_utf8(&table.get_name()).unwrap().to_string();
@@ -20,15 +25,29 @@ impl FlushAgent {
                         let family_name = std::str::from_utf8(&family.get_name()).unwrap().to_string();
                         let memtable_size = family.get_memtable_size();
                         debug!("Checking table {} family {}. Memtable size: {} bytes.", table_name, family_name, memtable_size);
-                        if memtable_size >= 80 /*8 * 1024 * 1024*/ {
+                        if memtable_size >= flush_bytes {
                             info!("Flushing memtable of table {} family {}. Memtable size: {} bytes.", table_name, family_name, memtable_size);
                             family.flush_memtable(&table.get_name(), storage_engine.get_persitance_layer());
                         }
                     }
                 }
                 debug!("Scanning end.");
-                sleep(Duration::from_secs(5)).await;
+                storage_engine.prune_change_feeds();
+                storage_engine.delete_retired_segments();
+
+                tokio::select! {
+                    _ = sleep(interval) => {},
+                    _ = &mut stopped => break,
+                }
             }
         });
+
+        FlushAgent { stop, handle }
+    }
+
+    // Waits for a flush in progress, so no segment is left half written.
+    pub async fn stop(self) {
+        let _ = self.stop.send(());
+        self.handle.await.unwrap();
     }
 }
\ No newline at end of file
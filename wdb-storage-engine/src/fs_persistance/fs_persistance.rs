@@ -1,24 +1,74 @@
-use std::{cmp::max, collections::HashMap, fs::{self, read_dir}, io::{Cursor, Read, Seek, Write}};
+use std::{cmp::max, collections::HashMap, fs::{self, read_dir, OpenOptions}, io::{Cursor, ErrorKind, Read, Seek, Write}, path::{Path, PathBuf}};
 use bytes::Bytes;
 use log::debug;
+use serde::Serialize;
 
-use crate::{utils::sstable::SSTable, PersistanceLayer};
+use crate::{utils::sstable::SSTable, Acl, ChangeLogWrite, FamilyOptions, PersistanceLayer, TableOptions};
 
 use super::storage_paths::StoragePaths;
 
 #[derive(Debug, Clone)]
-pub struct FSPersistance {}  
+pub struct FSPersistance {
+    paths: StoragePaths,
+}  
 
 impl FSPersistance {
     pub fn new() -> FSPersistance {
-        FSPersistance { }
+        FSPersistance::with_base(StoragePaths::DEFAULT_BASE)
+    }
+
+    pub fn with_base<T: AsRef<Path>>(base: T) -> FSPersistance {
+        FSPersistance { paths: StoragePaths::new(base) }
+    }
+
+    pub fn get_base(&self) -> PathBuf {
+        self.paths.base()
+    }
+
+    pub(crate) fn write_options_file<T: Serialize>(path: PathBuf, options: &T) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        // Write to a temporary file first so a crash never leaves torn options behind.
+        let tmp_path = path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp_path).unwrap();
+        bincode::serialize_into(&mut file, options).unwrap();
+        file.sync_all().unwrap();
+        fs::rename(tmp_path, path).unwrap();
+    }
+}
+
+impl ChangeLogWrite for fs::File {
+    fn sync(&mut self) -> std::io::Result<()> {
+        self.sync_data()
+    }
+}
+
+// Directories are synced as well, so newly created files survive a crash.
+fn sync_dir(path: &Path) -> std::io::Result<()> {
+    for entry in read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            sync_dir(&path)?;
+        } else {
+            fs::File::open(&path)?.sync_all()?;
+        }
+    }
+    fs::File::open(path)?.sync_all()
+}
+
+// Segments are immutable once written, so a hard link is as good as a copy and costs nothing.
+pub(crate) fn link_or_copy(src: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dest.parent().unwrap())?;
+
+    match fs::hard_link(src, dest) {
+        Ok(()) => Ok(()),
+        Err(_) => fs::copy(src, dest).map(|_| ()),
     }
 }
 
 impl PersistanceLayer for FSPersistance {
     fn get_segment_write(&self, table: &Bytes, family: Bytes, segment: &Bytes) -> impl Write {
-        let segment = std::str::from_utf8(&segment.clone()).unwrap().to_string();
-        let path = StoragePaths::get_family_dir(table, &family).join(segment);
+        let path = self.paths.get_segment_file(table, &family, segment);
         debug!("Path {:?}", path);
         let parent = path.parent().unwrap();
         fs::create_dir_all(parent).unwrap();
@@ -30,8 +80,7 @@ impl PersistanceLayer for FSPersistance {
     }
 
     fn get_segment_read(&self, table: &Bytes, family: &Bytes, segment: &Bytes) -> impl Read + Seek {
-        let segment = std::str::from_utf8(&segment.clone()).unwrap().to_string();
-        let path = StoragePaths::get_family_dir(table, &family).join(segment);
+        let path = self.paths.get_segment_file(table, &family, segment);
 
         let res = fs::File::open(path);
         match res {
@@ -40,8 +89,25 @@ impl PersistanceLayer for FSPersistance {
         }
     }
 
-    fn get_tables_list(&self) -> Vec<(Bytes, u64, Vec<(Bytes, Vec<SSTable>)>)> {
-        let paths = read_dir(StoragePaths::base()).unwrap();
+    fn commit_segment(&self, table: &Bytes, family: &Bytes, tmp_segment: &Bytes, segment: &Bytes) -> std::io::Result<()> {
+        let tmp_path = self.paths.get_segment_file(table, family, tmp_segment);
+        let path = self.paths.get_segment_file(table, family, segment);
+        debug!("Committing segment {:?} to {:?}", tmp_path, path);
+
+        fs::File::open(&tmp_path)?.sync_all()?;
+        fs::rename(&tmp_path, &path)?;
+        fs::File::open(path.parent().unwrap())?.sync_all()
+    }
+
+    fn link_segment(&self, table: &Bytes, family: &Bytes, segment: &Bytes, dest: &Path) -> std::io::Result<()> {
+        let path = self.paths.get_segment_file(table, family, segment);
+        debug!("Linking segment {:?} to {:?}", path, dest);
+
+        link_or_copy(&path, dest)
+    }
+
+    fn get_tables_list(&self) -> Vec<(Bytes, u64, TableOptions, Vec<(Bytes, FamilyOptions, Vec<SSTable>)>)> {
+        let paths = read_dir(self.paths.base()).unwrap();
 
         let mut results = vec![];
 
@@ -52,20 +118,39 @@ impl PersistanceLayer for FSPersistance {
                 let table_name = Bytes::from(name.strip_suffix(".table").unwrap().to_string());
                 let mut families = vec![];
                 let mut max_mvcc: u64 = 0;
+                let mut table_options = TableOptions::default();
 
                 let paths = read_dir(path).unwrap();
                 for path in paths {
                     let path = path.unwrap().path();
                     let name = path.file_name().unwrap().to_str().unwrap();
 
+                    if name == StoragePaths::TABLE_OPTIONS_FILE {
+                        let r = fs::File::open(&path).unwrap();
+                        table_options = bincode::deserialize_from(r).unwrap();
+                    }
+
                     if name.ends_with(".family") {
                         let family_name = Bytes::from(name.strip_suffix(".family").unwrap().to_string());
+                        let mut options = FamilyOptions::default();
                         let mut segments = vec![];
                         
                         let paths = read_dir(path).unwrap();
                         for path in paths {
                             let path = path.unwrap().path();
-                            let name = Bytes::from(path.file_name().unwrap().to_str().unwrap().to_string());
+                            let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+
+                            if file_name == StoragePaths::FAMILY_OPTIONS_FILE {
+                                let r = fs::File::open(path).unwrap();
+                                options = bincode::deserialize_from(r).unwrap();
+                                continue;
+                            }
+
+                            if file_name.ends_with(".tmp") {
+                                continue;
+                            }
+
+                            let name = Bytes::from(file_name);
                             
                             let r = fs::File::open(path).unwrap();
                             let segment = SSTable::read(&table_name, &family_name, &name, r);
@@ -73,14 +158,165 @@ impl PersistanceLayer for FSPersistance {
                             segments.push(segment);
                         }
 
-                        families.push((family_name, segments));
+                        families.push((family_name, options, segments));
                     }
                 }
 
-                results.push((table_name, max_mvcc, families));
+                results.push((table_name, max_mvcc, table_options, families));
             }
         }
 
         results
     }
+
+    fn write_table_options(&self, table: &Bytes, options: &TableOptions) {
+        let path = self.paths.get_table_options_file(table);
+        debug!("Writing table options {:?}", path);
+        FSPersistance::write_options_file(path, options);
+    }
+
+    fn write_family_options(&self, table: &Bytes, family: &Bytes, options: &FamilyOptions) {
+        let path = self.paths.get_family_options_file(table, family);
+        debug!("Writing family options {:?}", path);
+        FSPersistance::write_options_file(path, options);
+    }
+
+    fn get_catalog_acl(&self) -> Option<Acl> {
+        match fs::File::open(self.paths.get_catalog_acl_file()) {
+            Err(err) if err.kind() == ErrorKind::NotFound => None,
+            Err(err) => panic!("{:?}", err),
+            Ok(file) => Some(bincode::deserialize_from(file).unwrap()),
+        }
+    }
+
+    fn write_catalog_acl(&self, acl: Option<&Acl>) {
+        let path = self.paths.get_catalog_acl_file();
+        debug!("Writing catalog acl {:?}", path);
+
+        match acl {
+            Some(acl) => FSPersistance::write_options_file(path, acl),
+            None => match fs::remove_file(path) {
+                Err(err) if err.kind() != ErrorKind::NotFound => panic!("{:?}", err),
+                _ => {},
+            },
+        }
+    }
+
+    fn delete_segment(&self, table: &Bytes, family: &Bytes, segment: &Bytes) {
+        let path = self.paths.get_segment_file(table, &family, segment);
+        debug!("Removing segment {:?}", path);
+
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => panic!("{:?}", err),
+            _ => {},
+        }
+    }
+
+    fn delete_family(&self, table: &Bytes, family: &Bytes) {
+        let path = self.paths.get_family_dir(table, family);
+        debug!("Removing family directory {:?}", path);
+
+        match fs::remove_dir_all(path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => panic!("{:?}", err),
+            _ => {},
+        }
+    }
+
+    fn delete_table(&self, table: &Bytes) {
+        let path = self.paths.table_dir(table);
+        debug!("Removing table directory {:?}", path);
+
+        match fs::remove_dir_all(path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => panic!("{:?}", err),
+            _ => {},
+        }
+    }
+
+    fn get_change_log_write(&self, table: &Bytes, log: &Bytes) -> impl ChangeLogWrite + Send + 'static {
+        let log = std::str::from_utf8(&log.clone()).unwrap().to_string();
+        let path = self.paths.get_change_logs_dir(table).join(log);
+        debug!("Opening change log {:?}", path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        let res = OpenOptions::new().create(true).append(true).open(&path);
+        let file = match res {
+            Err(err) => panic!("{:?}", err),
+            Ok(file) => file,
+        };
+        // The new log has to stay listed after a crash, not only its records.
+        fs::File::open(path.parent().unwrap()).and_then(|dir| dir.sync_all()).unwrap();
+
+        file
+    }
+
+    fn get_change_log_read(&self, table: &Bytes, log: &Bytes) -> Option<impl Read> {
+        let log = std::str::from_utf8(&log.clone()).unwrap().to_string();
+        let path = self.paths.get_change_logs_dir(table).join(log);
+
+        match fs::File::open(path) {
+            Err(err) if err.kind() == ErrorKind::NotFound => None,
+            Err(err) => panic!("{:?}", err),
+            Ok(file) => Some(file),
+        }
+    }
+
+    fn get_change_logs_list(&self, table: &Bytes) -> Vec<Bytes> {
+        let paths = match read_dir(self.paths.get_change_logs_dir(table)) {
+            Err(err) if err.kind() == ErrorKind::NotFound => return vec![],
+            Err(err) => panic!("{:?}", err),
+            Ok(paths) => paths,
+        };
+
+        paths.map(|path| {
+            let path = path.unwrap().path();
+            Bytes::from(path.file_name().unwrap().to_str().unwrap().to_string())
+        }).filter(|name| name.ends_with(b".log")).collect()
+    }
+
+    fn delete_change_log(&self, table: &Bytes, log: &Bytes) {
+        let log = std::str::from_utf8(&log.clone()).unwrap().to_string();
+        let path = self.paths.get_change_logs_dir(table).join(log);
+        debug!("Removing change log {:?}", path);
+
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => panic!("{:?}", err),
+            _ => {},
+        }
+    }
+
+    // The base directory holds the entry of the table directory, so it is synced too.
+    fn sync_table(&self, table: &Bytes) {
+        let path = self.paths.table_dir(table);
+        debug!("Syncing table directory {:?}", path);
+
+        let res = sync_dir(&path).and_then(|_| fs::File::open(self.paths.base())).and_then(|dir| dir.sync_all());
+        match res {
+            Err(err) if err.kind() != ErrorKind::NotFound => panic!("{:?}", err),
+            _ => {},
+        }
+    }
+
+    fn sync_catalog(&self) {
+        let path = self.paths.get_catalog_acl_file();
+        debug!("Syncing catalog {:?}", path);
+
+        match fs::File::open(path).and_then(|file| file.sync_all()) {
+            Err(err) if err.kind() != ErrorKind::NotFound => panic!("{:?}", err),
+            _ => {},
+        }
+        match fs::File::open(self.paths.base()).and_then(|dir| dir.sync_all()) {
+            Err(err) if err.kind() != ErrorKind::NotFound => panic!("{:?}", err),
+            _ => {},
+        }
+    }
+
+    // Read-only or full disks only show up on write, so a probe file is written and synced.
+    fn check_writable(&self) -> std::io::Result<()> {
+        let path = self.paths.get_health_check_file();
+
+        let mut file = fs::File::create(&path)?;
+        file.write_all(b"ok")?;
+        file.sync_all()?;
+        fs::remove_file(path)
+    }
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
 mod fs_persistance;
 mod storage_paths;
 
-pub use fs_persistance::FSPersistance;
\ No newline at end of file
+pub use fs_persistance::FSPersistance;
+pub(crate) use fs_persistance::link_or_copy;
+pub(crate) use storage_paths::StoragePaths;
\ No newline at end of file
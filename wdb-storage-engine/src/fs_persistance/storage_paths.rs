@@ -2,20 +2,58 @@ use std::path::{Path, PathBuf};
 
 use bytes::Bytes;
 
-pub struct StoragePaths { }
+#[derive(Debug, Clone)]
+pub struct StoragePaths {
+    base: PathBuf,
+}
 
 impl StoragePaths {
-    pub fn base() -> PathBuf {
-        Path::new("/usr/local/wdb/").to_path_buf()
+    pub const DEFAULT_BASE: &'static str = "/usr/local/wdb/";
+    pub const FAMILY_OPTIONS_FILE: &'static str = "family.options";
+    pub const TABLE_OPTIONS_FILE: &'static str = "table.options";
+    pub const CATALOG_ACL_FILE: &'static str = "catalog.acl";
+    pub const HEALTH_CHECK_FILE: &'static str = "health.check";
+
+    pub fn new<T: AsRef<Path>>(base: T) -> StoragePaths {
+        StoragePaths { base: base.as_ref().to_path_buf() }
+    }
+
+    pub fn base(&self) -> PathBuf {
+        self.base.clone()
+    }
+
+    pub fn get_catalog_acl_file(&self) -> PathBuf {
+        self.base().join(StoragePaths::CATALOG_ACL_FILE)
+    }
+
+    pub fn get_health_check_file(&self) -> PathBuf {
+        self.base().join(StoragePaths::HEALTH_CHECK_FILE)
     }
 
-    pub fn table_dir(table_name: &Bytes) -> PathBuf {
+    pub fn table_dir(&self, table_name: &Bytes) -> PathBuf {
         let table_name = std::str::from_utf8(&table_name.clone()).unwrap().to_string();
-        StoragePaths::base().join(table_name + ".table/")
+        self.base().join(table_name + ".table/")
     }
 
-    pub fn get_family_dir(table_name: &Bytes, family_name: &Bytes) -> PathBuf {
+    pub fn get_table_options_file(&self, table_name: &Bytes) -> PathBuf {
+        self.table_dir(table_name).join(StoragePaths::TABLE_OPTIONS_FILE)
+    }
+
+    pub fn get_change_logs_dir(&self, table_name: &Bytes) -> PathBuf {
+        self.table_dir(table_name).join("changes/")
+    }
+
+    pub fn get_family_dir(&self, table_name: &Bytes, family_name: &Bytes) -> PathBuf {
         let family_name = std::str::from_utf8(&family_name.clone()).unwrap().to_string();
-        StoragePaths::table_dir(table_name).join(family_name + ".family/")
+        self.table_dir(table_name).join(family_name + ".family/")
+    }
+
+    pub fn get_family_options_file(&self, table_name: &Bytes, family_name: &Bytes) -> PathBuf {
+        self.get_family_dir(table_name, family_name).join(StoragePaths::FAMILY_OPTIONS_FILE)
+    }
+
+    pub fn get_segment_file(&self, table_name: &Bytes, family_name: &Bytes, segment: &Bytes) -> PathBuf {
+        let segment = std::str::from_utf8(&segment.clone()).unwrap().to_string();
+        self.get_family_dir(table_name, family_name).join(segment)
     }
 }
\ No newline at end of file
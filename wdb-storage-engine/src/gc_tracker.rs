@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use crate::{cell::Cell, key_value::KeyValue, table::GcPolicy, utils::Timestamp};
+
+/*
+Tracks visible versions of every column of a row, cells have to come in key order:
+- a version older than max_age_ms is collected,
+- versions past the newest max_versions of a column are collected.
+Only live puts must be passed, tombstones and deleted cells don't count as versions.
+ */
+pub struct GcTracker {
+    policies: HashMap<Vec<u8>, GcPolicy>,
+    now: u64,
+    count_versions: bool,
+    versions: HashMap<Vec<u8>, u32>,
+}
+
+impl GcTracker {
+    pub fn new(policies: HashMap<Vec<u8>, GcPolicy>, count_versions: bool) -> GcTracker {
+        GcTracker {
+            policies,
+            now: Timestamp::ensure_timestamp(None).into(),
+            count_versions,
+            versions: HashMap::new(),
+        }
+    }
+
+    pub fn is_collected(&mut self, cell: &KeyValue) -> bool {
+        let policy = match self.policies.get(cell.get_cf()) {
+            Some(policy) => policy,
+            None => return false,
+        };
+
+        if let Some(max_age_ms) = policy.max_age_ms {
+            let ts: u64 = cell.get_timestamp().into();
+            if ts < self.now.saturating_sub(max_age_ms) {
+                return true;
+            }
+        }
+
+        if let (true, Some(max_versions)) = (self.count_versions, policy.max_versions) {
+            let versions = self.versions.entry(cell.get_key_row_cf_col().to_vec()).or_insert(0);
+            *versions += 1;
+            if *versions > max_versions {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    pub fn reset(&mut self) {
+        self.versions.clear();
+    }
+}
\ No newline at end of file
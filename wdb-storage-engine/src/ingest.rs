@@ -0,0 +1,130 @@
+use std::io::{Read, Seek};
+
+use bytes::Bytes;
+use log::info;
+use uuid::Uuid;
+
+use crate::{cell::Cell, key_value::KeyValue, utils::sstable::{SSTable, SSTableReader, SSTableWriter}, visibility::ColumnVisibility, FamilyOptions, PersistanceLayer, StorageEngine};
+
+pub fn ingest_sstables<P: PersistanceLayer, R: Read + Seek>(storage_engine: &StorageEngine<P>, table: Bytes, family: Bytes, files: Vec<R>) -> Result<Vec<SSTable>, &'static str> {
+    // Write numbers are reserved and completed up front. Ingested segments become visible only
+    // once added to the family, and any later write to the same keys still wins.
+    let (options, write_nums) = {
+        let table = storage_engine.get_table(table.clone()).ok_or("Table with this name does not exist.")?;
+        let options = table.get_family(&family).ok_or("Family with this name does not exist.")?.get_options();
+
+        let write_nums = files.iter().map(|_| {
+            let write_entry = table.mvcc_new_write();
+            let write_num = write_entry.get_write_num();
+            table.mvcc_complete(write_entry);
+            write_num
+        }).collect::<Vec<u64>>();
+        (options, write_nums)
+    };
+
+    let persistance = storage_engine.get_persitance_layer();
+    let mut sstables = vec![];
+    for (file, write_num) in files.into_iter().zip(write_nums) {
+        match rewrite_sstable(persistance, &table, &family, &options, file, write_num) {
+            Ok(sstable) => sstables.push(sstable),
+            Err(err) => {
+                delete_sstables(persistance, &sstables);
+                return Err(err);
+            }
+        }
+    }
+
+    let res = match storage_engine.get_table(table.clone()) {
+        None => Err("Table with this name does not exist."),
+        Some(table) => match table.get_family(&family) {
+            None => Err("Family with this name does not exist."),
+            Some(family) => {
+                family.add_segments(sstables.clone());
+                Ok(())
+            },
+        },
+    };
+    if let Err(err) = res {
+        delete_sstables(persistance, &sstables);
+        return Err(err);
+    }
+
+    info!("Ingested {} segments into table {} family {}", sstables.len(), String::from_utf8_lossy(&table), String::from_utf8_lossy(&family));
+    Ok(sstables)
+}
+
+// Every cell is validated and stamped with the reserved write number while being copied into a new segment.
+fn rewrite_sstable<P: PersistanceLayer, R: Read + Seek>(persistance: &P, table: &Bytes, family: &Bytes, options: &FamilyOptions, file: R, write_num: u64) -> Result<SSTable, &'static str> {
+    let mut reader = SSTableReader::try_new(file)?;
+    let index = reader.try_read_index()?;
+    if index.is_empty() {
+        return Err("SSTable cannot be empty.");
+    }
+
+    // The segment is written under a temporary name, recovery skips it until it is complete.
+    let segment = Bytes::from(Uuid::now_v7().to_string());
+    let tmp_segment = Bytes::from(format!("{}.tmp", String::from_utf8_lossy(&segment)));
+    let mut write = persistance.get_segment_write(table, family.clone(), &tmp_segment);
+    let mut writer = SSTableWriter::with_options(&mut write, options);
+
+    let mut last_kv: Option<KeyValue> = None;
+    let mut res = Ok(());
+    for entry in index.iter() {
+        let kvs = match reader.read_block(entry.value()) {
+            Ok(kvs) => kvs,
+            Err(err) => {
+                res = Err(err);
+                break;
+            }
+        };
+
+        for mut kv in kvs {
+            if let Err(err) = kv.validate_key() {
+                res = Err(err);
+                break;
+            }
+            if kv.get_cf() != &family[..] {
+                res = Err("SSTable contains cells of a different family.");
+                break;
+            }
+            if ColumnVisibility::validate(kv.get_visibility()).is_err() {
+                res = Err("SSTable contains an invalid visibility expression.");
+                break;
+            }
+            if last_kv.as_ref().is_some_and(|last_kv| &kv <= last_kv) {
+                res = Err("SSTable keys are not in strictly increasing order.");
+                break;
+            }
+
+            kv.set_mvcc_id(write_num);
+            writer.write_kv(&kv);
+            last_kv = Some(kv);
+        }
+        if res.is_err() {
+            break;
+        }
+    }
+
+    let index = writer.end();
+    let size = writer.get_size();
+    let stats = writer.get_stats().clone();
+    let bloom_filter = writer.get_bloom_filter();
+    drop(writer);
+    drop(write);
+
+    let res = res.and_then(|_| {
+        persistance.commit_segment(table, family, &tmp_segment, &segment).map_err(|_| "Unable to write ingested segment.")
+    });
+    if let Err(err) = res {
+        persistance.delete_segment(table, family, &tmp_segment);
+        return Err(err);
+    }
+
+    Ok(SSTable::new(table, family, &segment, index, write_num, size, Some(stats)).with_bloom_filter(bloom_filter))
+}
+
+fn delete_sstables<P: PersistanceLayer>(persistance: &P, sstables: &[SSTable]) {
+    for sstable in sstables {
+        persistance.delete_segment(sstable.get_table(), sstable.get_family(), sstable.get_segment());
+    }
+}
\ No newline at end of file
@@ -10,6 +10,7 @@ Keyvalue buffer structure:
 - value_length: u64
 - key
 - value
+- visibility (rest of the buffer, empty when the cell has no label)
 
 Key structure:
 - row_length: u16
@@ -56,11 +57,15 @@ impl KeyValue {
     }
 
     pub fn new(row: &Bytes, cf: &Bytes, col: &Bytes, timestamp: Timestamp, key_type: &CellType, value: &Bytes) -> KeyValue {
+        KeyValue::new_with_visibility(row, cf, col, timestamp, key_type, value, &Bytes::new())
+    }
+
+    pub fn new_with_visibility(row: &Bytes, cf: &Bytes, col: &Bytes, timestamp: Timestamp, key_type: &CellType, value: &Bytes, visibility: &Bytes) -> KeyValue {
         let row_len = row.len();
         let cf_len = cf.len();
         let key_len = 2 + row_len + 2 + cf_len + col.len() + 8 + 1;
         let value_len = value.len();
-        let capacity = key_len + value_len;
+        let capacity = 2 + 8 + key_len + value_len + visibility.len();
 
         let mut buffer = BytesMut::with_capacity(capacity);
         
@@ -76,6 +81,7 @@ impl KeyValue {
         buffer.put_u8(*key_type as u8);
 
         buffer.put(value.clone());
+        buffer.put(visibility.clone());
         
         KeyValue { buffer: buffer.freeze(), mvcc_id: 0 }
     }
@@ -90,20 +96,49 @@ impl KeyValue {
         KeyValue { buffer: buffer.freeze(), mvcc_id: 0 }
     }
 
-    pub fn new_from_kv_bytes(key_len: u16, key: Vec<u8>, val_len: u64, val: Vec<u8>) -> KeyValue {
+    pub fn new_from_kv_bytes(key_len: u16, key: Vec<u8>, val_len: u64, val: Vec<u8>, visibility: Vec<u8>) -> KeyValue {
         let mut buffer = BytesMut::new();
 
         buffer.put_u16(key_len as u16);
         buffer.put_u64(val_len);
         buffer.put(key.as_slice());
         buffer.put(val.as_slice());
+        buffer.put(visibility.as_slice());
 
         KeyValue { buffer: buffer.freeze(), mvcc_id: 0 }
     }
 
+    // Keys read from untrusted files must be checked before any Cell accessor slices into them.
+    pub fn validate_key(&self) -> Result<(), &'static str> {
+        let key_len = self.get_key_len() as usize;
+        if key_len < 2 + 2 + 8 + 1 || self.buffer.len() < 10 + key_len {
+            return Err("Invalid key length.");
+        }
+
+        let row_len = self.get_row_len() as usize;
+        if 2 + row_len + 2 + 8 + 1 > key_len {
+            return Err("Invalid row length.");
+        }
+
+        let cf_len = self.get_cf_len() as usize;
+        if 2 + row_len + 2 + cf_len + 8 + 1 > key_len {
+            return Err("Invalid family length.");
+        }
+
+        match CellType::try_from(self.buffer[10 + key_len - 1]) {
+            Ok(CellType::Minimum) | Ok(CellType::Maximum) | Err(_) => Err("Invalid cell type."),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    // Segment entry layout: key_length, value_length, key, value, visibility_length: u16, visibility, mvcc_id: u64.
     pub fn as_bytes(&self) -> Bytes {
+        let visibility = self.get_visibility();
+
         let mut buf = BytesMut::new();
-        buf.put(&self.buffer[..]);
+        buf.put(&self.buffer[..self.buffer.len() - visibility.len()]);
+        buf.put_u16(visibility.len() as u16);
+        buf.put(visibility);
         buf.put_u64(self.mvcc_id);
 
         buf.freeze()
@@ -120,7 +155,7 @@ impl KeyValue {
 
 impl Cell for KeyValue {
     fn get_size(&self) -> u64 {
-        let size: u64 = 2 + 8 + self.get_key_len() as u64 + self.get_value_len();
+        let size: u64 = self.buffer.len() as u64;
         size
     }
 
@@ -200,6 +235,11 @@ impl Cell for KeyValue {
         let len = self.get_value_len() as usize;
         &self.buffer[pos..(pos+len)]
     }
+
+    fn get_visibility(&self) -> &[u8] {
+        let pos = 10 + self.get_key_len() as usize + self.get_value_len() as usize;
+        &self.buffer[pos..]
+    }
 }
 
 impl Ord for KeyValue {    
@@ -253,12 +293,13 @@ impl PartialOrd for KeyValue {
 impl Debug for KeyValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("KeyValue")
-            .field("row", &String::from_utf8(self.get_row().to_vec()).unwrap())
-            .field("cf", &String::from_utf8(self.get_cf().to_vec()).unwrap())
-            .field("col", &String::from_utf8(self.get_col().to_vec()).unwrap())
+            .field("row", &String::from_utf8_lossy(self.get_row()))
+            .field("cf", &String::from_utf8_lossy(self.get_cf()))
+            .field("col", &String::from_utf8_lossy(self.get_col()))
             .field("ts", &self.get_timestamp())
             .field("type", &self.get_cell_type())
-            .field("value", &String::from_utf8(self.get_value().to_vec()).unwrap())
+            .field("value", &String::from_utf8_lossy(self.get_value()))
+            .field("visibility", &String::from_utf8_lossy(self.get_visibility()))
             .finish()
     }
 }
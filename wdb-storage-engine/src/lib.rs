@@ -1,4 +1,5 @@
 mod storage_engine;
+mod engine_options;
 mod row_lock;
 mod table;
 mod row_mutation;
@@ -14,19 +15,50 @@ mod persistance_layer;
 mod kv_scanner;
 mod row_result;
 mod delete_tracker;
+mod gc_tracker;
+mod change_feed;
+mod watch;
+mod backup;
+mod export;
+mod ingest;
+mod compaction;
+mod acl;
+mod visibility;
+mod metrics;
 
 pub use row_mutation::RowMutation;
 pub use row_mutation::RowMutationOp;
 
 pub use storage_engine::StorageEngine;
+pub use engine_options::EngineOptions;
 
 pub use table::Table;
 pub use table::TableFamily;
+pub use table::{FamilyOptions, FamilyModification, GcPolicy, Compression, BloomFilterOptions};
+pub use table::{TableOptions, ChangeFeedOptions};
 
-pub use persistance_layer::PersistanceLayer;
+pub use persistance_layer::{PersistanceLayer, ChangeLogWrite};
 
 pub use utils::Timestamp;
+pub use utils::TimeRange;
+pub use utils::sstable::SSTable;
+pub use utils::sstable::Statistics;
+pub use utils::sstable::SSTableBuilder;
+pub use utils::sstable::{SSTableInspector, InspectedFooter, InspectedBlock, InspectedCell, VerifyReport};
 
 pub use cell::Cell;
 
+pub use change_feed::ChangeRecord;
+pub use watch::{WatchFilter, WatchEvent};
+
+pub use backup::{BackupManifest, BackupTable, BackupFamily, BackupSegment, restore_backup};
+
+pub use export::{ExportHeader, ExportRecord};
+
+pub use compaction::CompactionResult;
+
+pub use acl::{Acl, AclEntry, Permission};
+
+pub use visibility::{Authorizations, ColumnVisibility};
+
 pub use fs_persistance::FSPersistance;
\ No newline at end of file
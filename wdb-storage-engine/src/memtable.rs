@@ -1,4 +1,4 @@
-use std::{ops::{Deref, RangeBounds}, sync::{atomic::{AtomicU64, AtomicUsize, Ordering}, Arc}, vec::IntoIter};
+use std::{ops::{Deref, RangeBounds}, sync::{atomic::{AtomicU64, AtomicUsize, Ordering}, Arc, RwLock}, vec::IntoIter};
 
 use arc_swap::ArcSwap;
 use bytes::Bytes;
@@ -12,7 +12,9 @@ use crate::{key_value::KeyValue, kv_scanner::KVScanner, Cell};
 pub struct Memtable {
     active: ArcSwap<Segment>,
     activeSize: AtomicU64,
-    snapshot: ArcSwap<Segment>
+    snapshot: ArcSwap<Segment>,
+    // Inserts share it and snapshots take it exclusively, so no insert lands in a segment that is being flushed.
+    writers: RwLock<()>,
 }
 
 impl Memtable {
@@ -21,17 +23,20 @@ impl Memtable {
             active: ArcSwap::from(Arc::new(Segment::new())),
             activeSize: AtomicU64::new(0),
             snapshot: ArcSwap::default(),
+            writers: RwLock::new(()),
         }
     }
 
     pub fn insert(&self, cell: KeyValue) {
         let size = cell.get_size();        
+        let _writers = self.writers.read().unwrap();
         self.active.load().insert(cell);
         self.activeSize.fetch_add(size, Ordering::Relaxed);
     } 
 
     // TODO: This operation may override old snapshot. Add correct handling.
     pub fn snapshot(&self) -> Arc<Segment> {
+        let _writers = self.writers.write().unwrap();
         let to_flush = self.active.load_full();
         let new_active = Arc::new(Segment::new());
         self.snapshot.store(to_flush.clone());
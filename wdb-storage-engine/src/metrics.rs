@@ -0,0 +1,77 @@
+use once_cell::sync::Lazy;
+use prometheus::{exponential_buckets, register_histogram, register_int_counter, register_int_gauge_vec, Histogram, IntCounter, IntGaugeVec};
+
+// Registered in the default registry, so the server exports engine and RPC metrics together.
+pub(crate) static MUTATIONS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("wdb_mutations_total", "Row mutations applied.").unwrap()
+});
+
+pub(crate) static CELLS_WRITTEN: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("wdb_cells_written_total", "Cells written by row mutations.").unwrap()
+});
+
+pub(crate) static FLUSHES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("wdb_flushes_total", "Memtable flushes.").unwrap()
+});
+
+pub(crate) static FLUSH_DURATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!("wdb_flush_duration_seconds", "Memtable flush duration.").unwrap()
+});
+
+pub(crate) static FLUSH_BYTES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("wdb_flush_bytes_total", "Bytes of segments written by memtable flushes.").unwrap()
+});
+
+pub(crate) static COMPACTIONS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("wdb_compactions_total", "Segment compactions.").unwrap()
+});
+
+pub(crate) static COMPACTION_CELLS_DROPPED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("wdb_compaction_cells_dropped_total", "Shadowed, deleted and tombstone cells dropped by compactions.").unwrap()
+});
+
+pub(crate) static SCAN_CELLS_READ: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("wdb_scan_cells_read_total", "Cells read by scans, before deletes and filters are applied.").unwrap()
+});
+
+pub(crate) static SCAN_CELLS_RETURNED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("wdb_scan_cells_returned_total", "Cells returned by scans.").unwrap()
+});
+
+pub(crate) static MEMTABLE_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!("wdb_memtable_bytes", "Active memtable size.", &["table", "family"]).unwrap()
+});
+
+pub(crate) static SEGMENTS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!("wdb_segments", "Segments on disk.", &["table", "family"]).unwrap()
+});
+
+pub(crate) static MVCC_LAG: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!("wdb_mvcc_lag", "Writes started but not yet visible to readers.", &["table"]).unwrap()
+});
+
+pub(crate) static ROW_LOCK_WAIT: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!("wdb_row_lock_wait_seconds", "Time spent waiting for row locks.", exponential_buckets(0.00001, 4.0, 10).unwrap()).unwrap()
+});
+
+pub(crate) static ROW_LOCKS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!("wdb_row_locks", "Row locks currently held or waited for.", &["table"]).unwrap()
+});
+
+// Counters are exported from startup, even before anything touched them.
+pub(crate) fn register() {
+    Lazy::force(&MUTATIONS);
+    Lazy::force(&CELLS_WRITTEN);
+    Lazy::force(&FLUSHES);
+    Lazy::force(&FLUSH_DURATION);
+    Lazy::force(&FLUSH_BYTES);
+    Lazy::force(&COMPACTIONS);
+    Lazy::force(&COMPACTION_CELLS_DROPPED);
+    Lazy::force(&SCAN_CELLS_READ);
+    Lazy::force(&SCAN_CELLS_RETURNED);
+    Lazy::force(&MEMTABLE_BYTES);
+    Lazy::force(&SEGMENTS);
+    Lazy::force(&MVCC_LAG);
+    Lazy::force(&ROW_LOCK_WAIT);
+    Lazy::force(&ROW_LOCKS);
+}
\ No newline at end of file
@@ -1,11 +1,33 @@
-use std::io::{Read, Seek, Write};
+use std::{io::{Read, Seek, Write}, path::Path};
 
 use bytes::Bytes;
 
-use crate::utils::sstable::SSTable;
+use crate::{utils::sstable::SSTable, Acl, FamilyOptions, TableOptions};
 
 pub trait PersistanceLayer: Send + Sync + 'static {
     fn get_segment_write(&self, table: &Bytes, family: Bytes, segment: &Bytes) -> impl Write;
     fn get_segment_read(&self, table: &Bytes, family: &Bytes, segment: &Bytes) -> impl Read + Seek;
-    fn get_tables_list(&self) -> Vec<(Bytes, u64, Vec<(Bytes, Vec<SSTable>)>)>;
+    // Syncs a segment written under a temporary name and moves it to its final name.
+    fn commit_segment(&self, table: &Bytes, family: &Bytes, tmp_segment: &Bytes, segment: &Bytes) -> std::io::Result<()>;
+    fn link_segment(&self, table: &Bytes, family: &Bytes, segment: &Bytes, dest: &Path) -> std::io::Result<()>;
+    fn get_tables_list(&self) -> Vec<(Bytes, u64, TableOptions, Vec<(Bytes, FamilyOptions, Vec<SSTable>)>)>;
+    fn write_table_options(&self, table: &Bytes, options: &TableOptions);
+    fn write_family_options(&self, table: &Bytes, family: &Bytes, options: &FamilyOptions);
+    fn get_catalog_acl(&self) -> Option<Acl>;
+    fn write_catalog_acl(&self, acl: Option<&Acl>);
+    fn delete_segment(&self, table: &Bytes, family: &Bytes, segment: &Bytes);
+    fn delete_family(&self, table: &Bytes, family: &Bytes);
+    fn delete_table(&self, table: &Bytes);
+    fn get_change_log_write(&self, table: &Bytes, log: &Bytes) -> impl ChangeLogWrite + Send + 'static;
+    fn get_change_log_read(&self, table: &Bytes, log: &Bytes) -> Option<impl Read>;
+    fn get_change_logs_list(&self, table: &Bytes) -> Vec<Bytes>;
+    fn delete_change_log(&self, table: &Bytes, log: &Bytes);
+    fn sync_table(&self, table: &Bytes);
+    fn sync_catalog(&self);
+    fn check_writable(&self) -> std::io::Result<()>;
+}
+
+pub trait ChangeLogWrite: Write {
+    // Records are acknowledged only after they reach the disk.
+    fn sync(&mut self) -> std::io::Result<()>;
 }
\ No newline at end of file
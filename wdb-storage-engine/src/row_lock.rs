@@ -1,15 +1,83 @@
-use std::sync::{RwLock, RwLockWriteGuard};
+use std::sync::{Arc, Condvar, Mutex};
 
-use crate::utils::hashed_bytes::HashedBytes;
+use bytes::Bytes;
+use dashmap::DashMap;
 
-#[derive(Debug)]
+use crate::metrics;
+
+#[derive(Debug, Default)]
 pub struct RowLockContext {
-    pub row: HashedBytes,
-    pub lock: RwLock<bool>,
+    locked: Mutex<bool>,
+    released: Condvar,
+}
+
+/// Exclusive row locks. An entry lives only while someone holds or waits for its row,
+/// so the table does not grow with the key space.
+#[derive(Debug, Default)]
+pub struct RowLocks {
+    locks: DashMap<Bytes, Arc<RowLockContext>>,
+}
+
+impl RowLocks {
+    pub fn new() -> RowLocks {
+        RowLocks::default()
+    }
+
+    // The map shard is released before waiting, so only callers of the same row block each other.
+    pub fn lock(&self, row: &Bytes) -> RowLockGuard<'_> {
+        let context = self.locks.entry(row.clone()).or_default().clone();
+
+        let timer = metrics::ROW_LOCK_WAIT.start_timer();
+        let mut locked = context.locked.lock().unwrap();
+        while *locked {
+            locked = context.released.wait(locked).unwrap();
+        }
+        *locked = true;
+        drop(locked);
+        timer.observe_duration();
+
+        RowLockGuard { locks: self, row: row.clone(), context: Some(context) }
+    }
+
+    // Rows are locked in key order, so callers locking overlapping rows cannot deadlock.
+    pub fn lock_rows(&self, rows: &[Bytes]) -> MultiRowLockGuard<'_> {
+        let mut rows = rows.to_vec();
+        rows.sort();
+        rows.dedup();
+
+        MultiRowLockGuard { guards: rows.iter().map(|row| self.lock(row)).collect() }
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.locks.len()
+    }
+}
+
+pub struct RowLockGuard<'a> {
+    locks: &'a RowLocks,
+    row: Bytes,
+    context: Option<Arc<RowLockContext>>,
+}
+
+impl Drop for RowLockGuard<'_> {
+    fn drop(&mut self) {
+        let context = self.context.take().unwrap();
+        *context.locked.lock().unwrap() = false;
+        context.released.notify_one();
+        drop(context);
+
+        // References are only taken under the shard lock, so a count of one means nobody holds or waits for the row.
+        self.locks.locks.remove_if(&self.row, |_, context| Arc::strong_count(context) == 1);
+    }
+}
+
+/// Holds the locks of several rows and releases all of them when dropped.
+pub struct MultiRowLockGuard<'a> {
+    guards: Vec<RowLockGuard<'a>>,
 }
 
-impl RowLockContext {
-    pub fn write_lock(&self) -> RwLockWriteGuard<'_, bool> {
-        self.lock.write().unwrap()
+impl MultiRowLockGuard<'_> {
+    pub fn get_rows_count(&self) -> usize {
+        self.guards.len()
     }
 }
\ No newline at end of file
@@ -1,46 +1,43 @@
 use bytes::Bytes;
-use dashmap::mapref::one::{Ref, RefMut};
+use dashmap::mapref::one::Ref;
 use log::debug;
 
-use crate::{cell::CellType, key_value::KeyValue, utils::hashed_bytes::HashedBytes, PersistanceLayer, RowMutationOp, Table, TableFamily, Timestamp};
+use crate::{cell::CellType, key_value::KeyValue, metrics, utils::hashed_bytes::HashedBytes, visibility::ColumnVisibility, PersistanceLayer, RowMutationOp, Table, TableFamily, Timestamp};
 
 pub struct RowMutationExecutor {}
 
 impl RowMutationExecutor {
-    pub fn unsafe_execute_row_mutation(table: RefMut<u64, Table>, row: HashedBytes, ops: Vec<RowMutationOp>) {
+    pub fn unsafe_execute_row_mutation<P: PersistanceLayer>(persistance: &P, table: Ref<u64, Table>, row: HashedBytes, ops: Vec<RowMutationOp>) -> Result<(), &'static str> {
         // Stage I - mutation preprocessing
         debug!("RowMutationExecutor - Stage I begin");
         let mut parsed: Vec<RowMutationOpParsed> = Vec::new();
 
-        for op in ops {
-            let parsed_op = match &op {
-                RowMutationOp::DeleteCell { family, column, timestamp } => {
-                    let family = table.get_family(&family).unwrap();
-                    RowMutationOpParsed(family, op)
-                },
-                RowMutationOp::DeleteColumn { family, column, timestamp } => {
-                    let family = table.get_family(&family).unwrap();
-                    RowMutationOpParsed(family, op)
-                },
-                RowMutationOp::DeleteFamily { family, timestamp } => {
-                    let family = table.get_family(&family).unwrap();
-                    RowMutationOpParsed(family, op)
+        // Timestamps are resolved up front, so the change feed records exactly what was written.
+        let now = Timestamp::ensure_timestamp(None);
+        let ops = ops.into_iter().map(|op| RowMutationExecutor::resolve_timestamp(op, now)).collect::<Vec<RowMutationOp>>();
+
+        for op in ops.iter().cloned() {
+            let family = match &op {
+                RowMutationOp::Put { family, visibility, .. } => {
+                    if let Some(visibility) = visibility {
+                        ColumnVisibility::validate(visibility)?;
+                    }
+                    family
                 },
-                RowMutationOp::Put { family, column, timestamp, value } => {
-                    let family = table.get_family(&family).unwrap();
-                    RowMutationOpParsed(family, op)
-                }
+                RowMutationOp::DeleteCell { family, .. } => family,
+                RowMutationOp::DeleteColumn { family, .. } => family,
+                RowMutationOp::DeleteFamily { family, .. } => family,
             };
-            parsed.push(parsed_op);
+            let family = table.get_family(family).ok_or("Family with this name does not exist.")?;
+            parsed.push(RowMutationOpParsed(family, op));
         }
         debug!("RowMutationExecutor - Stage I end");
 
         
         // Stage II - ensure row write lock and get MVCC write number
         debug!("RowMutationExecutor - Stage II begin");
-        let row_lock = table.get_row_lock(&row);
-        let w = row_lock.write_lock();
-        let write_entry = table.mvcc_new_write();
+        let _row_lock = table.lock_row(&row);
+        let write_entry = table.mvcc_new_write_logged(persistance, row.bytes_as_ref(), &ops);
         let mvcc_id = write_entry.get_write_num();
         debug!("Got MVCC write number {}", mvcc_id);
         debug!("RowMutationExecutor - Stage II end");
@@ -53,46 +50,66 @@ impl RowMutationExecutor {
             let op = op.1;
 
             match op {
-                RowMutationOp::Put { column, timestamp, value, ..} => {
-                    RowMutationExecutor::unsafe_put(&table, &family, row.clone(), column, timestamp, value, mvcc_id);
+                RowMutationOp::Put { column, timestamp, value, visibility, ..} => {
+                    RowMutationExecutor::unsafe_put(&family, row.clone(), column, timestamp, value, visibility, mvcc_id);
                 },
                 RowMutationOp::DeleteCell { column, timestamp, .. } => {
-                    RowMutationExecutor::unsafe_delete_cell(&table, &family, row.clone(), column, timestamp, mvcc_id);
+                    RowMutationExecutor::unsafe_delete_cell(&family, row.clone(), column, timestamp, mvcc_id);
                 },
                 RowMutationOp::DeleteColumn { column, timestamp, .. } => {
-                    RowMutationExecutor::unsafe_delete_column(&table, &family, row.clone(), column, timestamp, mvcc_id);
+                    RowMutationExecutor::unsafe_delete_column(&family, row.clone(), column, timestamp, mvcc_id);
                 },
                 RowMutationOp::DeleteFamily { timestamp, .. } => {
-                    RowMutationExecutor::unsafe_delete_family(&table, &family, row.clone(), timestamp, mvcc_id);
+                    RowMutationExecutor::unsafe_delete_family(&family, row.clone(), timestamp, mvcc_id);
                 },
             }
         }
         table.mvcc_complete(write_entry);
         debug!("RowMutationExecutor - Stage III end");
+
+        metrics::MUTATIONS.inc();
+        metrics::CELLS_WRITTEN.inc_by(ops.len() as u64);
+
+        table.get_watch_registry().publish(row.bytes_as_ref(), mvcc_id, &ops);
+        Ok(())
+    }
+
+    fn resolve_timestamp(op: RowMutationOp, now: Timestamp) -> RowMutationOp {
+        match op {
+            RowMutationOp::Put { family, column, timestamp, value, visibility } => 
+                RowMutationOp::Put { family, column, timestamp: Some(timestamp.unwrap_or(now)), value, visibility },
+            RowMutationOp::DeleteCell { family, column, timestamp } => 
+                RowMutationOp::DeleteCell { family, column, timestamp: Some(timestamp.unwrap_or(now)) },
+            RowMutationOp::DeleteColumn { family, column, timestamp } => 
+                RowMutationOp::DeleteColumn { family, column, timestamp: Some(timestamp.unwrap_or(now)) },
+            RowMutationOp::DeleteFamily { family, timestamp } => 
+                RowMutationOp::DeleteFamily { family, timestamp: Some(timestamp.unwrap_or(now)) },
+        }
     }
 
-    fn unsafe_put(table: &Table, family: &TableFamily, row: HashedBytes, column: Bytes, ts: Option<Timestamp>, value: Bytes, mvcc_id: u64) {
+    fn unsafe_put(family: &TableFamily, row: HashedBytes, column: Bytes, ts: Option<Timestamp>, value: Bytes, visibility: Option<Bytes>, mvcc_id: u64) {
         let ts = Timestamp::ensure_timestamp(ts);
-        let mut cell = KeyValue::new(row.bytes_as_ref(), &family.get_name(), &column, ts, &CellType::Put, &value);
+        let visibility = visibility.unwrap_or_default();
+        let mut cell = KeyValue::new_with_visibility(row.bytes_as_ref(), &family.get_name(), &column, ts, &CellType::Put, &value, &visibility);
         cell.set_mvcc_id(mvcc_id);
         family.insert_kv(cell);
     }
 
-    fn unsafe_delete_cell(table: &Table, family: &TableFamily, row: HashedBytes, column: Bytes, ts: Option<Timestamp>, mvcc_id: u64) {
+    fn unsafe_delete_cell(family: &TableFamily, row: HashedBytes, column: Bytes, ts: Option<Timestamp>, mvcc_id: u64) {
         let ts = Timestamp::ensure_timestamp(ts);
         let mut cell = KeyValue::new(row.bytes_as_ref(), &family.get_name(), &column, ts, &CellType::Delete, &Bytes::from(""));
         cell.set_mvcc_id(mvcc_id);
         family.insert_kv(cell);
     }
 
-    fn unsafe_delete_column(table: &Table, family: &TableFamily, row: HashedBytes, column: Bytes, ts: Option<Timestamp>, mvcc_id: u64) {
+    fn unsafe_delete_column(family: &TableFamily, row: HashedBytes, column: Bytes, ts: Option<Timestamp>, mvcc_id: u64) {
         let ts = Timestamp::ensure_timestamp(ts);
         let mut cell = KeyValue::new(row.bytes_as_ref(), &family.get_name(), &column, ts, &CellType::DeleteColumn, &Bytes::from(""));
         cell.set_mvcc_id(mvcc_id);
         family.insert_kv(cell);
     } 
 
-    fn unsafe_delete_family(table: &Table, family: &TableFamily, row: HashedBytes, ts: Option<Timestamp>, mvcc_id: u64) {
+    fn unsafe_delete_family(family: &TableFamily, row: HashedBytes, ts: Option<Timestamp>, mvcc_id: u64) {
         let ts = Timestamp::ensure_timestamp(ts);   
         let mut cell = KeyValue::new(row.bytes_as_ref(), &family.get_name(), &Bytes::from_static(b""), ts, &CellType::DeleteFamily, &Bytes::from_static(b""));
         cell.set_mvcc_id(mvcc_id);
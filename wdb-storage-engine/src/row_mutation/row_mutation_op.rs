@@ -1,14 +1,16 @@
 use bytes::Bytes;
+use serde::{Deserialize, Serialize};
 
 use crate::utils::Timestamp;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RowMutationOp {
     Put {
         family: Bytes,
         column: Bytes,
         timestamp: Option<Timestamp>,
         value: Bytes,
+        visibility: Option<Bytes>,
     },
 
     DeleteCell {
@@ -1,48 +1,159 @@
-use std::sync::{Arc, Mutex};
+use std::{cmp::max, io::{BufRead, Read, Seek, Write}, path::Path, sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex, Weak}, time::{Duration, Instant}};
 
+use arc_swap::ArcSwapOption;
 use bytes::Bytes;
-use dashmap::{mapref::one::RefMut, DashMap};
+use dashmap::{mapref::one::{Ref, RefMut}, DashMap};
+use tokio::sync::{mpsc, watch};
 
-use crate::{ flush_agent::FlushAgent, key_value::KeyValue, row_filter::RowFilter, row_mutation::RowMutationExecutor, row_result::RowResult, table::Table, utils::{hashed_bytes::HashedBytes, Timestamp}, PersistanceLayer, RowMutation, RowMutationOp, TableFamily};
+use crate::{ acl::Acl, engine_options::EngineOptions, backup::{self, BackupManifest}, export, ingest, compaction::{self, CompactionResult}, change_feed::{ChangeFeed, ChangeRecord}, flush_agent::FlushAgent, key_value::KeyValue, metrics, row_filter::RowFilter, row_mutation::RowMutationExecutor, row_result::RowResult, table::Table, utils::{hashed_bytes::HashedBytes, sstable::{SSTable, Statistics}, TimeRange, Timestamp}, visibility::Authorizations, FamilyModification, PersistanceLayer, RowMutation, RowMutationOp, TableFamily, TableOptions, WatchEvent, WatchFilter};
+
+// Family name, memtable size and segments.
+type FamilySegments = (Bytes, u64, Arc<Vec<SSTable>>);
+// Table statistics and statistics of every family.
+type TableStats = (Statistics, Vec<(Bytes, Statistics)>);
+// Table, family and segment removed by a compaction, with the readers of its sstable.
+type RetiredSegment = (Bytes, Bytes, Bytes, Weak<()>);
 
 pub struct StorageEngine<P: PersistanceLayer> {
     tables: DashMap<u64, Table>,
     tables_lock: Mutex<()>,
+    compaction_lock: Mutex<()>,
+    retired_segments: Mutex<Vec<RetiredSegment>>,
+    catalog_acl: ArcSwapOption<Acl>,
     persistance_layer: P,
+    options: EngineOptions,
+    recovered: AtomicBool,
+    started_at: Instant,
+    flush_agent_heartbeat_ms: AtomicU64,
+    flush_agent: Mutex<Option<FlushAgent>>,
 }
 
 impl<P: PersistanceLayer> StorageEngine<P> {
     pub fn empty(persistance_layer: P, flush_agent: bool) -> Arc<StorageEngine<P>> {
-        let tables_data = persistance_layer.get_tables_list();
-        let tables = DashMap::new();
+        StorageEngine::with_options(persistance_layer, EngineOptions { flush_agent, ..EngineOptions::default() })
+    }
+
+    pub fn with_options(persistance_layer: P, options: EngineOptions) -> Arc<StorageEngine<P>> {
+        let engine = StorageEngine::open(persistance_layer, options);
+        engine.recover();
+        engine
+    }
+
+    /// Engine without any tables loaded yet, `recover` has to be called before it serves requests.
+    pub fn open(persistance_layer: P, options: EngineOptions) -> Arc<StorageEngine<P>> {
+        metrics::register();
+
+        Arc::new(StorageEngine {
+            tables: DashMap::new(),
+            tables_lock: Mutex::new(()),
+            compaction_lock: Mutex::new(()),
+            retired_segments: Mutex::new(vec![]),
+            catalog_acl: ArcSwapOption::empty(),
+            persistance_layer,
+            options,
+            recovered: AtomicBool::new(false),
+            started_at: Instant::now(),
+            flush_agent_heartbeat_ms: AtomicU64::new(0),
+            flush_agent: Mutex::new(None),
+        })
+    }
+
+    // Loads segments and replays change logs of every table, then starts the flush agent.
+    pub fn recover(self: &Arc<Self>) {
+        let _lock = self.tables_lock.lock().unwrap();
+        if self.is_recovered() {
+            return;
+        }
+
+        let tables_data = self.persistance_layer.get_tables_list();
         for table_data in tables_data {
             let name = HashedBytes::from_bytes(table_data.0);
             let id = *name.hash_as_ref();
-            tables.insert(
+
+            let change_feed = table_data.2.change_feed.as_ref().map(|options| {
+                ChangeFeed::open(&self.persistance_layer, name.bytes_as_ref(), options)
+            });
+            let mvcc_id = max(table_data.1, change_feed.as_ref().map_or(0, |feed| feed.get_last_write_num()));
+
+            self.tables.insert(
                 id, 
                 Table::new_from_families_vec(
                     id, 
                     name.bytes_as_ref().clone(), 
-                    table_data.1,
-                    table_data.2
+                    mvcc_id,
+                    table_data.2,
+                    change_feed,
+                    table_data.3
                 )
             );
         }
 
-        let engine = Arc::new(StorageEngine {
-            tables,
-            tables_lock: Mutex::new(()),
-            persistance_layer,
-        });
+        self.catalog_acl.store(self.persistance_layer.get_catalog_acl().map(Arc::new));
 
-        if flush_agent {
-            FlushAgent::new(engine.clone());
+        if self.options.flush_agent {
+            self.flush_agent_heartbeat();
+            *self.flush_agent.lock().unwrap() = Some(FlushAgent::new(self.clone(), &self.options));
         }
 
-        engine
-    }    
+        self.recovered.store(true, Ordering::Release);
+    }
+
+    pub fn is_recovered(&self) -> bool {
+        self.recovered.load(Ordering::Acquire)
+    }
+
+    pub fn get_options(&self) -> &EngineOptions {
+        &self.options
+    }
+
+    pub(crate) fn flush_agent_heartbeat(&self) {
+        self.flush_agent_heartbeat_ms.store(self.started_at.elapsed().as_millis() as u64, Ordering::Relaxed);
+    }
+
+    // A flush agent that missed several rounds is stuck, most likely on a flush that does not finish.
+    pub fn is_flush_agent_stalled(&self) -> bool {
+        if self.flush_agent.lock().unwrap().is_none() {
+            return false;
+        }
+
+        let heartbeat = Duration::from_millis(self.flush_agent_heartbeat_ms.load(Ordering::Relaxed));
+        let stall_after = max(Duration::from_millis(self.options.flush_interval_ms * 3), Duration::from_secs(60));
+        self.started_at.elapsed().saturating_sub(heartbeat) > stall_after
+    }
+
+    pub async fn stop_flush_agent(&self) {
+        let flush_agent = self.flush_agent.lock().unwrap().take();
+        if let Some(flush_agent) = flush_agent {
+            flush_agent.stop().await;
+        }
+    }
+
+    /// Flushes every memtable and syncs tables and the catalog to disk, so nothing depends on replaying logs after a restart.
+    pub fn flush_all(&self) {
+        for table in self.tables.iter() {
+            table.flush_memtable(&self.persistance_layer);
+            self.persistance_layer.sync_table(&table.get_name());
+        }
+        self.delete_retired_segments();
+        self.persistance_layer.sync_catalog();
+    }
+
+    // Logs expire with time as well, a table that stopped writing never rolls its log again.
+    pub fn prune_change_feeds(&self) {
+        for table in self.tables.iter() {
+            table.prune_change_feed(&self.persistance_layer);
+        }
+    }
+
+    pub fn check_storage(&self) -> std::io::Result<()> {
+        self.persistance_layer.check_writable()
+    }
 
     pub fn create_table(&self, name: Bytes) -> Result<(), &'static str> {
+        self.create_table_with_options(name, TableOptions::default())
+    }
+
+    pub fn create_table_with_options(&self, name: Bytes, options: TableOptions) -> Result<(), &'static str> {
         let name = HashedBytes::from_bytes(name);
 
         let _lock = self.tables_lock.lock().unwrap();
@@ -53,12 +164,103 @@ impl<P: PersistanceLayer> StorageEngine<P> {
         }
 
         let table = Table::new(id, name.bytes_as_ref().clone());
+        if options != TableOptions::default() {
+            table.set_options(&self.persistance_layer, options);
+        }
 
         self.tables.insert(id, table);
 
         Ok(())
     }
 
+    pub fn drop_table(&self, name: Bytes) -> Result<(), &'static str> {
+        let name = HashedBytes::from_bytes(name);
+
+        let _lock = self.tables_lock.lock().unwrap();
+
+        // Removal waits for the shard lock, so in-flight scans and mutations on the table finish first.
+        let id = *name.hash_as_ref();
+        let table = match self.tables.remove(&id) {
+            Some((_, table)) => table,
+            None => return Err("Table with this name does not exist."),
+        };
+
+        table.drop_families(&self.persistance_layer);
+        if let Some(change_feed) = table.get_change_feed() {
+            change_feed.destroy(&self.persistance_layer);
+        }
+        self.persistance_layer.delete_table(name.bytes_as_ref());
+
+        Ok(())
+    }
+
+    // Families are modified under the families lock, the table stays shared so reads and writes go on meanwhile.
+    pub fn modify_column_families(&self, table: Bytes, modifications: Vec<FamilyModification>) -> Result<(), &'static str> {
+        let table = self.get_table_ref(table).ok_or("Table with this name does not exist.")?;
+
+        table.modify_families(&self.persistance_layer, modifications)
+    }
+
+    pub fn set_table_options(&self, table: Bytes, options: TableOptions) -> Result<(), &'static str> {
+        let table = self.get_table(table).ok_or("Table with this name does not exist.")?;
+
+        table.set_options(&self.persistance_layer, options);
+
+        Ok(())
+    }
+
+    pub fn get_catalog_acl(&self) -> Option<Arc<Acl>> {
+        self.catalog_acl.load_full()
+    }
+
+    pub fn set_catalog_acl(&self, acl: Option<Acl>) {
+        let _lock = self.tables_lock.lock().unwrap();
+
+        self.persistance_layer.write_catalog_acl(acl.as_ref());
+        self.catalog_acl.store(acl.map(Arc::new));
+    }
+
+    pub fn get_table_acl(&self, table: Bytes) -> Result<Option<Acl>, &'static str> {
+        let table = self.get_table_ref(table).ok_or("Table with this name does not exist.")?;
+
+        Ok(table.get_options().acl.clone())
+    }
+
+    pub fn set_table_acl(&self, table: Bytes, acl: Option<Acl>) -> Result<(), &'static str> {
+        // The table reference is exclusive, so concurrent options updates cannot interleave.
+        let table = self.get_table(table).ok_or("Table with this name does not exist.")?;
+
+        let options = TableOptions { acl, ..(*table.get_options()).clone() };
+        table.set_options(&self.persistance_layer, options);
+
+        Ok(())
+    }
+
+    pub fn read_change_stream(&self, table: Bytes, cursor: u64, limit: usize) -> Result<Vec<ChangeRecord>, &'static str> {
+        let change_feed = self.get_change_feed(table)?;
+
+        change_feed.read(&self.persistance_layer, cursor, limit)
+    }
+
+    pub fn watch_change_stream(&self, table: Bytes) -> Result<watch::Receiver<u64>, &'static str> {
+        let change_feed = self.get_change_feed(table)?;
+
+        Ok(change_feed.subscribe())
+    }
+
+    pub fn watch(&self, table: Bytes, filter: WatchFilter, buffer_size: usize) -> Result<mpsc::Receiver<WatchEvent>, &'static str> {
+        let table = self.get_table(table).ok_or("Table with this name does not exist.")?;
+
+        Ok(table.get_watch_registry().subscribe(filter, buffer_size))
+    }
+
+    // Feed is cloned out of the table, so reading logs does not block writers on the table.
+    fn get_change_feed(&self, table: Bytes) -> Result<Arc<ChangeFeed>, &'static str> {
+        let table = self.get_table(table).ok_or("Table with this name does not exist.")?;
+
+        table.get_change_feed().ok_or("Change stream is not enabled for this table.")
+    }
+
     pub fn get_tables_iter(&self) -> dashmap::iter::Iter<u64, Table, std::hash::RandomState, DashMap<u64, Table>> {
         self.tables.iter()
     }
@@ -70,36 +272,203 @@ impl<P: PersistanceLayer> StorageEngine<P> {
         self.tables.get_mut(&id)
     }
 
+    pub fn has_table(&self, name: Bytes) -> bool {
+        self.get_table_ref(name).is_some()
+    }
+
+    // Shared access to the table, for callers that only need what the table guards itself.
+    fn get_table_ref(&self, name: Bytes) -> Option<Ref<u64, Table>> {
+        let id = *HashedBytes::from_bytes(name).hash_as_ref();
+
+        self.tables.get(&id)
+    }
+
+    // Mutations share the table, the row lock orders writers of the same row and the memtable orders them against flushes.
     pub fn execute_row_mutation(&self, mutation: RowMutation) {
-        let table = self.get_table(mutation.table.clone()).unwrap();
+        self.try_execute_row_mutation(mutation).unwrap()
+    }
+
+    // Nothing is written when the table or a family is missing, or a visibility expression is invalid.
+    pub fn try_execute_row_mutation(&self, mutation: RowMutation) -> Result<(), &'static str> {
+        let id = *HashedBytes::from_bytes(mutation.table.clone()).hash_as_ref();
+        let table = self.tables.get(&id).ok_or("Table with this name does not exist.")?;
         let row = HashedBytes::from_bytes(mutation.row.clone());
         
-        RowMutationExecutor::unsafe_execute_row_mutation(table, row, mutation.ops);
-    }  
+        RowMutationExecutor::unsafe_execute_row_mutation(&self.persistance_layer, table, row, mutation.ops)
+    }
 
+    // Labelled cells are not filtered, callers outside of the engine read with authorizations.
     pub fn read_row(&self, table: Bytes, row: Bytes, filter: Option<&dyn RowFilter>) -> RowResult {
-        let table: RefMut<u64, Table, std::hash::RandomState> = self.get_table(table.clone()).unwrap();
+        self.read_row_cells(table, row, None).unwrap()
+    }
+
+    pub fn read_row_with_authorizations(&self, table: Bytes, row: Bytes, filter: Option<&dyn RowFilter>, authorizations: Authorizations) -> Result<RowResult, &'static str> {
+        self.read_row_cells(table, row, Some(authorizations))
+    }
+
+    fn read_row_cells(&self, table: Bytes, row: Bytes, authorizations: Option<Authorizations>) -> Result<RowResult, &'static str> {
+        let table: RefMut<u64, Table, std::hash::RandomState> = self.get_table(table.clone()).ok_or("Table with this name does not exist.")?;
 
         let row = HashedBytes::from_bytes(row.clone());
 
         let start = KeyValue::new_first_on_row(row.bytes_as_ref());
         let end = KeyValue::new_last_on_row(row.bytes_as_ref());
 
-        let iter = table.scan(self.get_persitance_layer(), Some(start), Some(end));
-        
-        return RowResult { 
+        let cells = match authorizations {
+            Some(authorizations) => table.scan_time_range(self.get_persitance_layer(), Some(start), Some(end), None, authorizations)?.collect(),
+            None => table.scan_unfiltered(self.get_persitance_layer(), Some(start), Some(end), None)?.collect(),
+        };
+
+        Ok(RowResult { 
             row: row.bytes_as_ref().clone(), 
-            cells: iter.collect(), 
-        }
+            cells, 
+        })
     }
 
     pub fn scan(&self, table: Bytes, start: Option<KeyValue>, end: Option<KeyValue>, filter: Option<&dyn RowFilter>) -> Vec<KeyValue> {
         let table: RefMut<u64, Table, std::hash::RandomState> = self.get_table(table.clone()).unwrap();
 
-        let iter = table.scan(self.get_persitance_layer(), start, end);
+        let iter = table.scan_unfiltered(self.get_persitance_layer(), start, end, None).unwrap();
         iter.collect::<Vec<KeyValue>>()
     }
 
+    pub fn scan_time_range(&self, table: Bytes, start: Option<KeyValue>, end: Option<KeyValue>, time_range: TimeRange) -> Vec<KeyValue> {
+        let table: RefMut<u64, Table, std::hash::RandomState> = self.get_table(table.clone()).unwrap();
+
+        let iter = table.scan_unfiltered(self.get_persitance_layer(), start, end, Some(time_range)).unwrap();
+        iter.collect::<Vec<KeyValue>>()
+    }
+
+    pub fn read_rows(&self, table: Bytes, start: Option<Bytes>, end: Option<Bytes>, time_range: Option<TimeRange>, authorizations: Authorizations) -> Result<Vec<KeyValue>, &'static str> {
+        let mut cells = vec![];
+        self.read_rows_while(table, start, end, time_range, authorizations, |cell| {
+            cells.push(cell);
+            true
+        })?;
+        Ok(cells)
+    }
+
+    // Passes cells to f in key order and stops the scan as soon as f returns false.
+    pub fn read_rows_while<F: FnMut(KeyValue) -> bool>(&self, table: Bytes, start: Option<Bytes>, end: Option<Bytes>, time_range: Option<TimeRange>, authorizations: Authorizations, mut f: F) -> Result<(), &'static str> {
+        let table = self.get_table(table).ok_or("Table with this name does not exist.")?;
+
+        // End row is exclusive, so the scan stops before the first key on it.
+        let start = start.map(|row| KeyValue::new_first_on_row(&row));
+        let end = end.map(|row| KeyValue::new_first_on_row(&row));
+
+        for cell in table.scan_time_range(self.get_persitance_layer(), start, end, time_range, authorizations)? {
+            if !f(cell) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn sample_row_keys(&self, table: Bytes, interval: u64) -> Result<Vec<(Bytes, u64)>, &'static str> {
+        let table = self.get_table(table).ok_or("Table with this name does not exist.")?;
+
+        Ok(table.sample_row_keys(interval))
+    }
+
+    pub fn get_table_stats(&self, table: Bytes) -> Result<TableStats, &'static str> {
+        let table = self.get_table(table).ok_or("Table with this name does not exist.")?;
+
+        Ok(table.get_stats())
+    }
+
+    pub fn list_segments(&self, table: Bytes, family: Option<Bytes>) -> Result<Vec<FamilySegments>, &'static str> {
+        let table = self.get_table(table).ok_or("Table with this name does not exist.")?;
+
+        match family {
+            Some(family) => {
+                let family = table.get_family(&family).ok_or("Family with this name does not exist.")?;
+                Ok(vec![(family.get_name(), family.get_memtable_size(), family.get_segments())])
+            },
+            None => Ok(table.get_families_iter().map(|family| {
+                (family.get_name(), family.get_memtable_size(), family.get_segments())
+            }).collect()),
+        }
+    }
+
+    pub fn flush_table(&self, table: Bytes, family: Option<Bytes>) -> Result<(), &'static str> {
+        let table = self.get_table(table).ok_or("Table with this name does not exist.")?;
+
+        match family {
+            Some(family) => {
+                let family = table.get_family(&family).ok_or("Family with this name does not exist.")?;
+                if family.get_memtable_size() > 0 {
+                    family.flush_memtable(&table.get_name(), &self.persistance_layer);
+                }
+            },
+            None => table.flush_memtable(&self.persistance_layer),
+        }
+        self.persistance_layer.sync_table(&table.get_name());
+
+        Ok(())
+    }
+
+    // Compactions run one at a time, so two of them never rewrite the same segments.
+    pub fn compact_range(&self, table: Bytes, family: Option<Bytes>, start: Option<Bytes>, end: Option<Bytes>) -> Result<Vec<CompactionResult>, &'static str> {
+        let _lock = self.compaction_lock.lock().unwrap();
+        let results = compaction::compact_range(self, table, family, start, end);
+        self.delete_retired_segments();
+        results
+    }
+
+    pub(crate) fn retire_segments(&self, sstables: &[SSTable]) {
+        let mut retired = self.retired_segments.lock().unwrap();
+        for sstable in sstables {
+            retired.push((sstable.get_table().clone(), sstable.get_family().clone(), sstable.get_segment().clone(), sstable.get_readers()));
+        }
+    }
+
+    // Segments removed by a compaction stay on disk until no scan, backup or export reads them anymore.
+    pub fn delete_retired_segments(&self) {
+        self.retired_segments.lock().unwrap().retain(|(table, family, segment, readers)| {
+            if readers.strong_count() > 0 {
+                return true;
+            }
+            self.persistance_layer.delete_segment(table, family, segment);
+            false
+        });
+    }
+
+    // Gauges are refreshed on demand, so dropped tables and families disappear from the next scrape.
+    pub fn update_metrics(&self) {
+        metrics::MEMTABLE_BYTES.reset();
+        metrics::SEGMENTS.reset();
+        metrics::MVCC_LAG.reset();
+        metrics::ROW_LOCKS.reset();
+
+        for table in self.tables.iter() {
+            let table_name = String::from_utf8_lossy(&table.get_name()).to_string();
+            metrics::MVCC_LAG.with_label_values(&[&table_name]).set(table.mvcc_get_write_point().saturating_sub(table.mvcc_get_read_point()) as i64);
+            metrics::ROW_LOCKS.with_label_values(&[&table_name]).set(table.get_row_locks_count() as i64);
+
+            for family in table.get_families_iter() {
+                let family_name = String::from_utf8_lossy(&family.get_name()).to_string();
+                metrics::MEMTABLE_BYTES.with_label_values(&[&table_name, &family_name]).set(family.get_memtable_size() as i64);
+                metrics::SEGMENTS.with_label_values(&[&table_name, &family_name]).set(family.get_segments_count() as i64);
+            }
+        }
+    }
+
+    pub fn create_backup(&self, backup_root: &Path, parent: Option<&str>) -> std::io::Result<BackupManifest> {
+        backup::create_backup(self, backup_root, parent)
+    }
+
+    pub fn export_table<W: Write>(&self, table: Bytes, start: Option<Bytes>, end: Option<Bytes>, w: W) -> Result<u64, &'static str> {
+        export::export_table(self, table, start, end, w)
+    }
+
+    pub fn import_table<R: BufRead>(&self, table: Bytes, r: R) -> Result<u64, &'static str> {
+        export::import_table(self, table, r)
+    }
+
+    pub fn ingest_sstables<R: Read + Seek>(&self, table: Bytes, family: Bytes, files: Vec<R>) -> Result<Vec<SSTable>, &'static str> {
+        ingest::ingest_sstables(self, table, family, files)
+    }
+
     pub fn get_persitance_layer(&self) -> &P {
         &self.persistance_layer
     }
@@ -0,0 +1,53 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FamilyOptions {
+    pub gc_policy: GcPolicy,
+    // Apply to segments written from now on, existing segments keep how they were written.
+    #[serde(default)]
+    pub compression: Compression,
+    #[serde(default)]
+    pub bloom_filter: BloomFilterOptions,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GcPolicy {
+    pub max_versions: Option<u32>,
+    pub max_age_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compression {
+    #[default]
+    None,
+    Lz4,
+    Zstd,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BloomFilterOptions {
+    pub enabled: bool,
+    pub bits_per_key: u32,
+}
+
+impl Default for BloomFilterOptions {
+    fn default() -> Self {
+        BloomFilterOptions { enabled: false, bits_per_key: 10 }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FamilyModification {
+    Create {
+        family: Bytes,
+        options: FamilyOptions,
+    },
+    Update {
+        family: Bytes,
+        options: FamilyOptions,
+    },
+    Drop {
+        family: Bytes,
+    },
+}
\ No newline at end of file
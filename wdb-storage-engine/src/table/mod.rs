@@ -1,5 +1,9 @@
 mod table;
 mod table_family;
+mod family_options;
+mod table_options;
 
-pub use table::Table;
-pub use table_family::TableFamily;
\ No newline at end of file
+pub use table::{Table, MVCCWriteEntry};
+pub use table_family::TableFamily;
+pub use family_options::*;
+pub use table_options::*;
\ No newline at end of file
@@ -1,19 +1,23 @@
-use std::{collections::LinkedList, ops::{Bound, Range, RangeBounds}, sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex, RwLock}};
+use std::{collections::{HashMap, HashSet, LinkedList}, ops::{Bound, Range, RangeBounds}, sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex}};
 
+use arc_swap::{ArcSwap, ArcSwapOption};
 use bytes::Bytes;
 use dashmap::{iter::Iter, mapref::one::Ref, DashMap};
 use itertools::kmerge;
 use log::debug;
 
-use crate::{cell::{Cell, CellType}, delete_tracker::DeleteTracker, key_value::KeyValue, kv_scanner::KVScanner, memtable::Memtable, row_lock::RowLockContext, storage_engine, utils::{hashed_bytes::HashedBytes, sstable::SSTable}, PersistanceLayer, StorageEngine};
+use crate::{change_feed::ChangeFeed, cell::{Cell, CellType}, delete_tracker::DeleteTracker, gc_tracker::GcTracker, key_value::KeyValue, kv_scanner::KVScanner, memtable::Memtable, metrics, row_lock::{MultiRowLockGuard, RowLockGuard, RowLocks}, storage_engine, utils::{hashed_bytes::HashedBytes, sstable::{SSTable, Statistics}, TimeRange}, visibility::{Authorizations, ColumnVisibility}, watch::WatchRegistry, PersistanceLayer, RowMutationOp, StorageEngine};
 
-use super::{table_family::TableFamily};
+use super::{table_family::TableFamily, FamilyModification, FamilyOptions, GcPolicy, TableOptions};
 
 pub struct Table {
     id: u64,
     name: Bytes,
+    options: ArcSwap<TableOptions>,
+    change_feed: ArcSwapOption<ChangeFeed>,
+    watch_registry: WatchRegistry,
     families: DashMap<u64, TableFamily>,
-    row_locks: DashMap<u64, RowLockContext>,
+    row_locks: RowLocks,
     families_lock: Mutex<()>,
     mvcc_read_point: AtomicU64,
     mvcc_write_point: AtomicU64,
@@ -25,8 +29,11 @@ impl Table {
         Table {
             id,
             name,
+            options: ArcSwap::from_pointee(TableOptions::default()),
+            change_feed: ArcSwapOption::empty(),
+            watch_registry: WatchRegistry::new(),
             families: DashMap::new(),
-            row_locks: DashMap::new(),
+            row_locks: RowLocks::new(),
             families_lock: Mutex::new(()),
             mvcc_read_point: AtomicU64::new(0),
             mvcc_write_point: AtomicU64::new(0),
@@ -34,7 +41,7 @@ impl Table {
         }
     }
 
-    pub fn new_from_families_vec(id: u64, name: Bytes, mvcc_id: u64, families_data: Vec<(Bytes, Vec<SSTable>)>) -> Table {
+    pub fn new_from_families_vec(id: u64, name: Bytes, mvcc_id: u64, options: TableOptions, change_feed: Option<ChangeFeed>, families_data: Vec<(Bytes, FamilyOptions, Vec<SSTable>)>) -> Table {
         let families = DashMap::new();
         for family_data in families_data {
             let name = HashedBytes::from_bytes(family_data.0);
@@ -42,15 +49,22 @@ impl Table {
             
             families.insert(
                 id, 
-                TableFamily::new_from_segments_vec(id, name.bytes_as_ref().clone(), family_data.1)
+                TableFamily::new_from_segments_vec(id, name.bytes_as_ref().clone(), family_data.1, family_data.2)
             );
         }
 
+        if let Some(change_feed) = &change_feed {
+            change_feed.publish(mvcc_id);
+        }
+
         Table {
             id,
             name,
+            options: ArcSwap::from_pointee(options),
+            change_feed: ArcSwapOption::from_pointee(change_feed),
+            watch_registry: WatchRegistry::new(),
             families,
-            row_locks: DashMap::new(),
+            row_locks: RowLocks::new(),
             families_lock: Mutex::new(()),
             mvcc_read_point: AtomicU64::new(mvcc_id),
             mvcc_write_point: AtomicU64::new(mvcc_id),
@@ -62,6 +76,44 @@ impl Table {
         self.name.clone()
     }
 
+    pub fn get_options(&self) -> Arc<TableOptions> {
+        self.options.load_full()
+    }
+
+    pub fn set_options<P: PersistanceLayer>(&self, persistance: &P, options: TableOptions) {
+        persistance.write_table_options(&self.name, &options);
+
+        match (&options.change_feed, self.change_feed.load_full()) {
+            (Some(feed_options), Some(change_feed)) => change_feed.set_options(feed_options),
+            (Some(feed_options), None) => {
+                let change_feed = ChangeFeed::open(persistance, &self.name, feed_options);
+                change_feed.publish(self.mvcc_get_read_point());
+                self.change_feed.store(Some(Arc::new(change_feed)));
+            },
+            (None, Some(change_feed)) => {
+                self.change_feed.store(None);
+                change_feed.destroy(persistance);
+            },
+            (None, None) => {},
+        }
+
+        self.options.store(Arc::new(options));
+    }
+
+    pub fn prune_change_feed<P: PersistanceLayer>(&self, persistance: &P) {
+        if let Some(change_feed) = self.change_feed.load_full() {
+            change_feed.prune(persistance);
+        }
+    }
+
+    pub fn get_change_feed(&self) -> Option<Arc<ChangeFeed>> {
+        self.change_feed.load_full()
+    }
+
+    pub fn get_watch_registry(&self) -> &WatchRegistry {
+        &self.watch_registry
+    }
+
     pub fn get_family(&self, name: &Bytes) -> Option<Ref<u64, TableFamily>> {
         let name = HashedBytes::from_bytes(name.clone());
 
@@ -82,37 +134,160 @@ impl Table {
             return Err("Family with this name already exists.")
         }
 
-        let family = TableFamily::new(id, name.bytes_as_ref().clone());
+        let family = TableFamily::new(id, name.bytes_as_ref().clone(), FamilyOptions::default());
         self.families.insert(id, family);
 
         Ok(())
     }
+
+    pub fn add_family<P: PersistanceLayer>(&self, persistance: &P, name: Bytes, options: FamilyOptions) -> Result<(), &'static str> {
+        let _lock = self.families_lock.lock().unwrap();
+
+        self.apply_family_modification(persistance, FamilyModification::Create { family: name, options })
+    }
+
+    pub fn update_family_options<P: PersistanceLayer>(&self, persistance: &P, name: Bytes, options: FamilyOptions) -> Result<(), &'static str> {
+        let _lock = self.families_lock.lock().unwrap();
+
+        self.apply_family_modification(persistance, FamilyModification::Update { family: name, options })
+    }
+
+    // Every modification is checked against the families it would see before any of them is applied, so a failing request changes nothing.
+    pub fn modify_families<P: PersistanceLayer>(&self, persistance: &P, modifications: Vec<FamilyModification>) -> Result<(), &'static str> {
+        let _lock = self.families_lock.lock().unwrap();
+
+        let mut families = self.families.iter().map(|family| family.get_name()).collect::<HashSet<Bytes>>();
+        for modification in modifications.iter() {
+            match modification {
+                FamilyModification::Create { family, .. } => {
+                    if !families.insert(family.clone()) {
+                        return Err("Family with this name already exists.");
+                    }
+                },
+                FamilyModification::Update { family, .. } => {
+                    if !families.contains(family) {
+                        return Err("Family with this name does not exist.");
+                    }
+                },
+                FamilyModification::Drop { family } => {
+                    if !families.remove(family) {
+                        return Err("Family with this name does not exist.");
+                    }
+                },
+            }
+        }
+
+        for modification in modifications {
+            self.apply_family_modification(persistance, modification)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn drop_family<P: PersistanceLayer>(&self, persistance: &P, name: Bytes) -> Result<(), &'static str> {
+        let _lock = self.families_lock.lock().unwrap();
+
+        self.apply_family_modification(persistance, FamilyModification::Drop { family: name })
+    }
+
+    // Callers hold the families lock.
+    fn apply_family_modification<P: PersistanceLayer>(&self, persistance: &P, modification: FamilyModification) -> Result<(), &'static str> {
+        match modification {
+            FamilyModification::Create { family: name, options } => {
+                let name = HashedBytes::from_bytes(name);
+                let id = *name.hash_as_ref();
+
+                if self.families.contains_key(&id) {
+                    return Err("Family with this name already exists.")
+                }
+
+                persistance.write_family_options(&self.name, name.bytes_as_ref(), &options);
+
+                let family = TableFamily::new(id, name.bytes_as_ref().clone(), options);
+                self.families.insert(id, family);
+            },
+            FamilyModification::Update { family: name, options } => {
+                let family = self.get_family(&name).ok_or("Family with this name does not exist.")?;
+
+                persistance.write_family_options(&self.name, &name, &options);
+                family.set_options(options);
+            },
+            FamilyModification::Drop { family: name } => {
+                let name = HashedBytes::from_bytes(name);
+                let id = *name.hash_as_ref();
+
+                // Removal waits for the shard lock, so any scan still holding the family finishes first.
+                let family = match self.families.remove(&id) {
+                    Some((_, family)) => family,
+                    None => return Err("Family with this name does not exist."),
+                };
+
+                family.delete_segments(&self.name, persistance);
+            },
+        }
+
+        Ok(())
+    }
+
+    pub fn drop_families<P: PersistanceLayer>(&self, persistance: &P) {
+        let _lock = self.families_lock.lock().unwrap();
+
+        let ids = self.families.iter().map(|family| *family.key()).collect::<Vec<u64>>();
+        for id in ids {
+            if let Some((_, family)) = self.families.remove(&id) {
+                family.delete_segments(&self.name, persistance);
+            }
+        }
+    }
  
-    pub fn get_row_lock(&self, row: &HashedBytes) -> dashmap::mapref::one::RefMut<u64, RowLockContext, std::hash::RandomState> {
-        let hash = *row.hash_as_ref();
-        
-        let lock = self.row_locks.entry(hash).or_insert_with(|| RowLockContext {
-            row: row.clone(),
-            lock: RwLock::new(true),
-        });
-        lock
+    pub fn flush_memtable<P: PersistanceLayer>(&self, persistance: &P) {
+        for family in self.families.iter() {
+            if family.get_memtable_size() > 0 {
+                family.flush_memtable(&self.name, persistance);
+            }
+        }
+    }
+ 
+    pub fn lock_row(&self, row: &HashedBytes) -> RowLockGuard<'_> {
+        self.row_locks.lock(row.bytes_as_ref())
+    }
+
+    pub fn lock_rows(&self, rows: &[Bytes]) -> MultiRowLockGuard<'_> {
+        self.row_locks.lock_rows(rows)
     }
 
+    pub fn get_row_locks_count(&self) -> usize {
+        self.row_locks.get_count()
+    }
+
+    // Numbers are taken under the queue lock, so concurrent writers enqueue them in order.
     pub fn mvcc_new_write(&self) -> Arc<MVCCWriteEntry> {
+        let mut queue = self.mvcc_write_queue.lock().unwrap();
         let prev = self.mvcc_write_point.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         let write_num = prev + 1;
         let write_entry = Arc::new(MVCCWriteEntry {
             write_num,
             completed: AtomicBool::new(false),
         });
-        self.mvcc_write_queue.lock().unwrap().push_back(write_entry.clone());
+        queue.push_back(write_entry.clone());
         write_entry
     }
 
+    pub fn mvcc_new_write_logged<P: PersistanceLayer>(&self, persistance: &P, row: &Bytes, ops: &[RowMutationOp]) -> Arc<MVCCWriteEntry> {
+        match self.change_feed.load_full() {
+            Some(change_feed) => change_feed.append(persistance, || self.mvcc_new_write(), row, ops),
+            None => self.mvcc_new_write(),
+        }
+    }
+
     pub fn mvcc_get_read_point(&self) -> u64 {
         self.mvcc_read_point.load(Ordering::Relaxed)
     }
 
+    pub fn mvcc_get_write_point(&self) -> u64 {
+        self.mvcc_write_point.load(Ordering::Relaxed)
+    }
+
     pub fn mvcc_complete(&self, write_entry: Arc<MVCCWriteEntry>) {
         write_entry.mark_as_completed();
         let mut queue = self.mvcc_write_queue.lock().unwrap();
@@ -135,25 +310,91 @@ impl Table {
 
         self.mvcc_read_point.store(read_point, Ordering::Relaxed);
         debug!("MVCC new read point: {}", read_point);
+
+        if let Some(change_feed) = &*self.change_feed.load() {
+            change_feed.publish(read_point);
+        }
+    }
+
+    pub fn get_stats(&self) -> (Statistics, Vec<(Bytes, Statistics)>) {
+        let mut stats = Statistics::default();
+        let mut families_stats = vec![];
+        for family in self.families.iter() {
+            let family_stats = family.get_stats();
+            stats.merge(&family_stats);
+            families_stats.push((family.get_name(), family_stats));
+        }
+        (stats, families_stats)
+    }
+
+    pub fn sample_row_keys(&self, interval: u64) -> Vec<(Bytes, u64)> {
+        let mut blocks = vec![];
+        for family in self.families.iter() {
+            for sstable in family.get_segments().iter() {
+                blocks.extend(sstable.get_index_iter().map(|(key, block)| (key, block.get_data_size() as u64)));
+            }
+        }
+        blocks.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut samples: Vec<(Bytes, u64)> = vec![];
+        let mut offset = 0;
+        let mut last_sample_offset = 0;
+        for (key, data_size) in blocks {
+            if offset - last_sample_offset >= interval {
+                let row = Bytes::copy_from_slice(key.get_row());
+                if samples.last().is_none_or(|last| last.0 != row) {
+                    samples.push((row, offset));
+                    last_sample_offset = offset;
+                }
+            }
+            offset += data_size;
+        }
+
+        samples
+    }
+
+    pub fn get_gc_policies(&self) -> HashMap<Vec<u8>, GcPolicy> {
+        self.families.iter()
+            .map(|family| (family.get_name().to_vec(), family.get_options().gc_policy.clone()))
+            .filter(|(_, gc_policy)| *gc_policy != GcPolicy::default())
+            .collect()
     }
 
-    pub fn scan<P: PersistanceLayer>(&self, persitance: &P, start: Option<KeyValue>, end: Option<KeyValue>) -> impl Iterator<Item = KeyValue> + '_ {
+    // Every cell is returned, labelled or not, for callers inside the engine such as export.
+    pub fn scan_unfiltered<P: PersistanceLayer>(&self, persitance: &P, start: Option<KeyValue>, end: Option<KeyValue>, time_range: Option<TimeRange>) -> Result<impl Iterator<Item = KeyValue> + '_, &'static str> {
+        self.scan_cells(persitance, start, end, time_range, None)
+    }
+
+    // Labelled cells are returned only when the authorizations satisfy their visibility expression.
+    pub fn scan_time_range<P: PersistanceLayer>(&self, persitance: &P, start: Option<KeyValue>, end: Option<KeyValue>, time_range: Option<TimeRange>, authorizations: Authorizations) -> Result<impl Iterator<Item = KeyValue> + '_, &'static str> {
+        self.scan_cells(persitance, start, end, time_range, Some(authorizations))
+    }
+
+    fn scan_cells<P: PersistanceLayer>(&self, persitance: &P, start: Option<KeyValue>, end: Option<KeyValue>, time_range: Option<TimeRange>, authorizations: Option<Authorizations>) -> Result<impl Iterator<Item = KeyValue> + '_, &'static str> {
         let read_point = self.mvcc_get_read_point();
 
+        // Segments are pruned with the same policies the tracker collects with.
+        let gc_policies = self.get_gc_policies();
         let mut iters = vec![];
         for family in self.families.iter() {
-            iters.push(family.scan(persitance, start.clone(), end.clone(), Some(read_point)).collect::<Vec<KeyValue>>());
+            let gc_policy = gc_policies.get(&family.get_name()[..]);
+            iters.push(family.scan(persitance, start.clone(), end.clone(), time_range, Some(read_point), gc_policy)?.collect::<Vec<KeyValue>>());
         }
         
         let merge_iter = kmerge(iters);
 
         let mut delete_tracker = DeleteTracker::new();
+        let mut gc_tracker = GcTracker::new(gc_policies, true);
+        let mut visible: HashMap<Vec<u8>, bool> = HashMap::new();
 
         let mut current_row: Vec<u8> = vec![];
-        merge_iter.map(move |cell: KeyValue| {
+        Ok(merge_iter.map(move |cell: KeyValue| {
+            metrics::SCAN_CELLS_READ.inc();
+
             let row = cell.get_row();
             if row != current_row {
                 delete_tracker.reset();
+                gc_tracker.reset();
                 current_row = row.to_vec();
             }
 
@@ -164,12 +405,32 @@ impl Table {
                     if delete_tracker.is_deleted(&cell) {
                         return None;
                     }
+                    // Versions are counted before the time range, a collected version stays hidden in every range.
+                    if gc_tracker.is_collected(&cell) {
+                        return None;
+                    }
+                    if let Some(time_range) = &time_range {
+                        if !time_range.contains(cell.get_timestamp()) {
+                            return None;
+                        }
+                    }
+                    if let Some(authorizations) = &authorizations {
+                        // Expressions that fail to parse hide the cell.
+                        let visibility = cell.get_visibility();
+                        let is_visible = *visible.entry(visibility.to_vec()).or_insert_with(|| {
+                            ColumnVisibility::parse(visibility).is_ok_and(|visibility| visibility.evaluate(authorizations))
+                        });
+                        if !is_visible {
+                            return None;
+                        }
+                    }
+                    metrics::SCAN_CELLS_RETURNED.inc();
                     return Some(cell);
                 },
                 _ => {},
             }
             return None;
-        }).flatten()
+        }).flatten())
     }
 }
 
@@ -4,28 +4,39 @@ use arc_swap::{access::Access, ArcSwap};
 use bytes::Bytes;
 use itertools::{kmerge, Itertools};
 
-use crate::{key_value::KeyValue, memtable::Memtable, utils::sstable::{SSTable, SSTableReader, SSTableWriter}, Cell, PersistanceLayer};
+use crate::{key_value::KeyValue, memtable::Memtable, metrics, utils::{sstable::{SSTable, SSTableReader, SSTableWriter, Statistics}, TimeRange, Timestamp}, Cell, PersistanceLayer};
+
+use super::{FamilyOptions, GcPolicy};
 
 pub struct TableFamily {
     id: u64,
     name: Bytes,
+    options: ArcSwap<FamilyOptions>,
     sstables: ArcSwap<Vec<SSTable>>,
     memtable: Memtable,
 }
 
 impl TableFamily {
-    pub fn new(id: u64, name: Bytes) -> TableFamily {
-        TableFamily { id, name, sstables: ArcSwap::default(), memtable: Memtable::new(), }
+    pub fn new(id: u64, name: Bytes, options: FamilyOptions) -> TableFamily {
+        TableFamily { id, name, options: ArcSwap::from_pointee(options), sstables: ArcSwap::default(), memtable: Memtable::new(), }
     }
 
-    pub fn new_from_segments_vec(id: u64, name: Bytes, segments: Vec<SSTable>) -> TableFamily {
-        TableFamily { id, name, sstables: ArcSwap::new(Arc::new(segments)), memtable: Memtable::new(), }   
+    pub fn new_from_segments_vec(id: u64, name: Bytes, options: FamilyOptions, segments: Vec<SSTable>) -> TableFamily {
+        TableFamily { id, name, options: ArcSwap::from_pointee(options), sstables: ArcSwap::new(Arc::new(segments)), memtable: Memtable::new(), }   
     }
 
     pub fn get_name(&self) -> Bytes {
         self.name.clone()
     }
 
+    pub fn get_options(&self) -> Arc<FamilyOptions> {
+        self.options.load_full()
+    }
+
+    pub fn set_options(&self, options: FamilyOptions) {
+        self.options.store(Arc::new(options));
+    }
+
     pub fn insert_kv(&self, cell: KeyValue) {
         self.memtable.insert(cell);
     }
@@ -34,12 +45,43 @@ impl TableFamily {
         self.memtable.get_active_size()
     }
 
+    pub fn get_segments(&self) -> Arc<Vec<SSTable>> {
+        self.sstables.load_full()
+    }
+
+    pub fn get_segments_count(&self) -> usize {
+        self.sstables.load().len()
+    }
+
+    pub fn get_disk_size(&self) -> u64 {
+        self.sstables.load().iter().map(|sstable| sstable.get_size()).sum()
+    }
+
+    pub fn get_stats(&self) -> Statistics {
+        let mut stats = Statistics::default();
+        for sstable in self.sstables.load().iter() {
+            if let Some(segment_stats) = sstable.get_stats() {
+                stats.merge(segment_stats);
+            }
+        }
+        stats
+    }
+
+    pub fn delete_segments<P: PersistanceLayer>(&self, table_name: &Bytes, persistance: &P) {
+        let sstables = self.sstables.swap(Arc::new(vec![]));
+        for sstable in sstables.iter() {
+            persistance.delete_segment(table_name, &self.name, sstable.get_segment());
+        }
+        persistance.delete_family(table_name, &self.name);
+    }
+
     pub fn flush_memtable<P: PersistanceLayer>(&self, table_name: &Bytes, persistance: &P) {
+        let timer = metrics::FLUSH_DURATION.start_timer();
         let segment = self.memtable.snapshot();
 
         let segment_name = segment.get_id();
         let mut write = persistance.get_segment_write(table_name, self.get_name().clone(), segment_name);
-        let mut sstable_writer = SSTableWriter::new(&mut write);
+        let mut sstable_writer = SSTableWriter::with_options(&mut write, &self.get_options());
 
         segment.iter().for_each(|kv| {
             sstable_writer.write_kv(kv.value())
@@ -47,25 +89,68 @@ impl TableFamily {
 
         let index = sstable_writer.end();
         let max_mvcc = sstable_writer.get_max_mvcc_id();
+        let size = sstable_writer.get_size();
+        let stats = sstable_writer.get_stats().clone();
+
+        let sstable = SSTable::new(table_name, &self.get_name(), segment_name, index, max_mvcc, size, Some(stats))
+            .with_bloom_filter(sstable_writer.get_bloom_filter());
+        self.add_segments(vec![sstable]);
 
-        let mut sstables: Vec<SSTable> = self.sstables.load_full().iter().map(|ss_table| {
-            ss_table.clone()
-        }).collect_vec();
-        sstables.push(SSTable::new(table_name, &self.get_name(), segment_name, index, max_mvcc));
-        self.sstables.swap(Arc::new(sstables));
+        timer.observe_duration();
+        metrics::FLUSHES.inc();
+        metrics::FLUSH_BYTES.inc_by(size);
     }
 
-    pub fn scan<P: PersistanceLayer>(&self, persistance: &P , start: Option<KeyValue>, end: Option<KeyValue>, read_point: Option<u64>) -> impl Iterator<Item = KeyValue> + '_ {
+    // Segment lists are swapped with rcu, so flushes, ingestion and compactions never lose each other's updates.
+    pub fn add_segments(&self, segments: Vec<SSTable>) {
+        self.sstables.rcu(|sstables| {
+            let mut sstables: Vec<SSTable> = sstables.iter().cloned().collect_vec();
+            sstables.extend(segments.iter().cloned());
+            sstables
+        });
+    }
+
+    pub fn replace_segments(&self, removed: &[Bytes], added: Option<SSTable>) {
+        self.sstables.rcu(|sstables| {
+            let mut sstables: Vec<SSTable> = sstables.iter()
+                .filter(|sstable| !removed.contains(sstable.get_segment()))
+                .cloned()
+                .collect_vec();
+            sstables.extend(added.iter().cloned());
+            sstables
+        });
+    }
+
+    pub fn scan<P: PersistanceLayer>(&self, persistance: &P , start: Option<KeyValue>, end: Option<KeyValue>, time_range: Option<TimeRange>, read_point: Option<u64>, gc_policy: Option<&GcPolicy>) -> Result<impl Iterator<Item = KeyValue> + '_, &'static str> {
         let mut iters = vec![];
         iters.push(self.memtable.scan(start.clone(), end.clone(), read_point).into_iter());
 
+        // Max versions are counted from the newest version, so segments newer than the range cannot be skipped.
+        let pruning_range = match gc_policy.is_some_and(|gc_policy| gc_policy.max_versions.is_some()) {
+            true => time_range.map(|time_range| TimeRange::new(time_range.start, Timestamp::new(u64::MAX))),
+            false => time_range,
+        };
+
+        // Bloom filters hold rows, so they only skip segments for reads of a single row.
+        let row = match (&start, &end) {
+            (Some(start), Some(end)) if start.get_row() == end.get_row() => Some(start.get_row()),
+            _ => None,
+        };
+
         let sstables = self.sstables.load();
         for sstable in sstables.iter() {
+            if !sstable.may_contain_rows(&start, &end) || !sstable.may_contain_time_range(&pruning_range) {
+                continue;
+            }
+            if row.is_some_and(|row| !sstable.may_contain_row(row)) {
+                continue;
+            }
+
             let blocks = sstable.get_blocks(start.clone(), end.clone());
             
             let mut reader = SSTableReader::new(persistance.get_segment_read(sstable.get_table(), sstable.get_family(), sstable.get_segment()));
             let iter = reader
-                .read_blocks(blocks)
+                .read_blocks(blocks)?
                 .into_iter()
                 .skip_while(|kv| { match &start {
                     None => false,
@@ -81,7 +166,7 @@ impl TableFamily {
         }
 
         let iter = kmerge(iters).collect_vec().into_iter();
-        ScanIterator::new(iter, read_point)
+        Ok(ScanIterator::new(iter, read_point))
     }
     
 }
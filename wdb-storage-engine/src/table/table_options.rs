@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Acl;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableOptions {
+    pub change_feed: Option<ChangeFeedOptions>,
+    pub acl: Option<Acl>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeFeedOptions {
+    pub retention_ms: u64,
+}
+
+impl Default for ChangeFeedOptions {
+    fn default() -> Self {
+        ChangeFeedOptions { retention_ms: 24 * 60 * 60 * 1000 }
+    }
+}
\ No newline at end of file
@@ -1,5 +1,7 @@
 pub mod sstable;
 pub mod hashed_bytes;
 mod timestamp;
+mod time_range;
 
-pub use timestamp::*;
\ No newline at end of file
+pub use timestamp::*;
+pub use time_range::TimeRange;
\ No newline at end of file
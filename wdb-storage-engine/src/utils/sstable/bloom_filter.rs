@@ -0,0 +1,63 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+// Row bloom filter of a segment, probed with double hashing over a 64 bit FNV-1a hash of the row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    hashes: u32,
+    bits: Vec<u8>,
+}
+
+impl BloomFilter {
+    pub fn build(row_hashes: &[u64], bits_per_key: u32) -> BloomFilter {
+        let bits_per_key = bits_per_key.clamp(1, 64);
+        // k = bits_per_key * ln(2) hashes minimize the false positive rate.
+        let hashes = ((bits_per_key as f64 * 0.69) as u32).clamp(1, 30);
+        let len = (row_hashes.len() * bits_per_key as usize).max(64).div_ceil(8);
+
+        let mut filter = BloomFilter { hashes, bits: vec![0u8; len] };
+        for hash in row_hashes {
+            for bit in filter.probes(*hash) {
+                filter.bits[bit / 8] |= 1 << (bit % 8);
+            }
+        }
+
+        filter
+    }
+
+    pub fn hash(row: &[u8]) -> u64 {
+        row.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    pub fn may_contain(&self, row: &[u8]) -> bool {
+        self.probes(BloomFilter::hash(row)).all(|bit| {
+            self.bits[bit / 8] & (1 << (bit % 8)) != 0
+        })
+    }
+
+    pub fn to_bytes(&self) -> Bytes {
+        let mut buf = BytesMut::with_capacity(4 + self.bits.len());
+        buf.put_u32(self.hashes);
+        buf.put(&self.bits[..]);
+        buf.freeze()
+    }
+
+    pub fn from_bytes(mut buf: Bytes) -> Result<BloomFilter, &'static str> {
+        if buf.remaining() < 4 {
+            return Err("Invalid SSTable bloom filter block.");
+        }
+        let hashes = buf.get_u32();
+        if hashes == 0 || !buf.has_remaining() {
+            return Err("Invalid SSTable bloom filter block.");
+        }
+
+        Ok(BloomFilter { hashes, bits: buf.to_vec() })
+    }
+
+    fn probes(&self, hash: u64) -> impl Iterator<Item = usize> {
+        let num_bits = self.bits.len() as u64 * 8;
+        let delta = hash.rotate_right(17) | 1;
+        (0..self.hashes as u64).map(move |i| (hash.wrapping_add(i.wrapping_mul(delta)) % num_bits) as usize)
+    }
+}
\ No newline at end of file
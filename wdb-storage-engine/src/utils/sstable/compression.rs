@@ -0,0 +1,36 @@
+use crate::Compression;
+
+impl Compression {
+    pub(crate) fn id(&self) -> u64 {
+        match self {
+            Compression::None => 0,
+            Compression::Lz4 => 1,
+            Compression::Zstd => 2,
+        }
+    }
+
+    pub(crate) fn from_id(id: u64) -> Result<Compression, &'static str> {
+        match id {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Lz4),
+            2 => Ok(Compression::Zstd),
+            _ => Err("Invalid SSTable compression."),
+        }
+    }
+
+    pub(crate) fn compress(&self, data: Vec<u8>) -> Vec<u8> {
+        match self {
+            Compression::None => data,
+            Compression::Lz4 => lz4_flex::compress_prepend_size(&data),
+            Compression::Zstd => zstd::bulk::compress(&data, 3).unwrap(),
+        }
+    }
+
+    pub(crate) fn decompress(&self, data: Vec<u8>) -> Result<Vec<u8>, &'static str> {
+        match self {
+            Compression::None => Ok(data),
+            Compression::Lz4 => lz4_flex::decompress_size_prepended(&data).map_err(|_| "Unable to decompress SSTable data block."),
+            Compression::Zstd => zstd::stream::decode_all(&data[..]).map_err(|_| "Unable to decompress SSTable data block."),
+        }
+    }
+}
\ No newline at end of file
@@ -6,6 +6,7 @@ pub struct DataBlock {
     pub data_size: usize,
     pub key_len: u16,
     pub key: Bytes,
+    pub crc32: Option<u32>,
 }
 
 impl DataBlock {
@@ -16,4 +17,8 @@ impl DataBlock {
     pub fn get_data_size(&self) -> usize {
         self.data_size
     }
+
+    pub fn get_crc32(&self) -> Option<u32> {
+        self.crc32
+    }
 }
\ No newline at end of file
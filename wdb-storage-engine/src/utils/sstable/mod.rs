@@ -2,10 +2,18 @@ mod sstable;
 mod sstable_writer;
 mod sstable_reader;
 mod data_block;
+mod sstable_stats;
+mod sstable_builder;
+mod sstable_inspector;
+mod compression;
+mod bloom_filter;
 
 pub use sstable_writer::SSTableWriter;
 pub use sstable::SSTable;
 pub use sstable_reader::SSTableReader;
+pub use sstable_stats::Statistics;
+pub use sstable_builder::SSTableBuilder;
+pub use sstable_inspector::{SSTableInspector, InspectedFooter, InspectedBlock, InspectedCell, VerifyReport};
 
 // #[cfg(test)]
 // mod tests {
@@ -1,13 +1,13 @@
-use std::{io::{Read, Seek, SeekFrom}, iter, path::PathBuf};
+use std::{io::{Read, Seek, SeekFrom}, iter, path::PathBuf, sync::{Arc, Weak}};
 
 use bytes::Bytes;
 use crossbeam_skiplist::{map::Entry, SkipMap};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use crate::key_value::KeyValue;
+use crate::{cell::Cell, key_value::KeyValue, utils::TimeRange};
 
-use super::{data_block::DataBlock, sstable_reader::SSTableReader};
+use super::{bloom_filter::BloomFilter, data_block::DataBlock, sstable_reader::SSTableReader, sstable_stats::Statistics};
 
 #[derive(Serialize, Deserialize)]
 pub struct SSTableFooter {
@@ -38,28 +38,111 @@ pub struct SSTable {
     segment: Bytes,
     index: SkipMap<KeyValue, DataBlock>,
     max_mvcc_id: u64,
+    size: u64,
+    stats: Option<Statistics>,
+    bloom_filter: Option<Arc<BloomFilter>>,
+    // Shared by every clone, a segment is no longer read once the last clone is dropped.
+    readers: Arc<()>,
 }
 
 impl SSTable {
-    pub fn new(table: &Bytes, family: &Bytes, segment: &Bytes, index: SkipMap<KeyValue, DataBlock>, max_mvcc_id: u64,) -> SSTable {
-        SSTable { table: table.clone(), family: family.clone(), segment: segment.clone(), index, max_mvcc_id }
+    pub const MAGIC_V1: u64 = 0xDB1234AB;
+    pub const MAGIC_V2: u64 = 0xDB1234AC;
+    // Version 3 keeps the version 2 footer and adds cell visibility to every data block entry.
+    pub const MAGIC_V3: u64 = 0xDB1234AD;
+    // Version 4 adds a CRC32 of every data block to its index entry.
+    pub const MAGIC_V4: u64 = 0xDB1234AE;
+    // Version 5 prepends the block compression and the row bloom filter block position to the version 2 footer.
+    pub const MAGIC_V5: u64 = 0xDB1234AF;
+    pub const FOOTER_V1_SIZE: usize = 4 * 8;
+    pub const FOOTER_V2_SIZE: usize = 6 * 8;
+    pub const FOOTER_V5_SIZE: usize = 9 * 8;
+
+    pub fn new(table: &Bytes, family: &Bytes, segment: &Bytes, index: SkipMap<KeyValue, DataBlock>, max_mvcc_id: u64, size: u64, stats: Option<Statistics>) -> SSTable {
+        SSTable { table: table.clone(), family: family.clone(), segment: segment.clone(), index, max_mvcc_id, size, stats, bloom_filter: None, readers: Arc::new(()) }
     }
 
     pub fn read<R: Read + Seek>(table: &Bytes, family: &Bytes, segment: &Bytes, r: R) -> SSTable {
         let mut reader = SSTableReader::new(r);
         
         let index = reader.read_index();
-        SSTable::new(table, family, segment, index, reader.max_mvcc_id())
+        let stats = reader.read_stats();
+        let bloom_filter = reader.read_bloom_filter();
+        SSTable::new(table, family, segment, index, reader.max_mvcc_id(), reader.size(), stats)
+            .with_bloom_filter(bloom_filter)
+    }
+
+    pub fn with_bloom_filter(mut self, bloom_filter: Option<BloomFilter>) -> SSTable {
+        self.bloom_filter = bloom_filter.map(Arc::new);
+        self
+    }
+
+    pub fn get_stats(&self) -> Option<&Statistics> {
+        self.stats.as_ref()
     }
 
     pub fn get_max_mvcc_id(&self) -> u64 {
         self.max_mvcc_id
     }
 
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn may_contain_rows(&self, start: &Option<KeyValue>, end: &Option<KeyValue>) -> bool {
+        let stats = match &self.stats {
+            Some(stats) => stats,
+            None => return true,
+        };
+
+        if let (Some(start), Some(max_row)) = (start, &stats.max_row) {
+            if start.get_row() > &max_row[..] {
+                return false;
+            }
+        }
+
+        if let (Some(end), Some(min_row)) = (end, &stats.min_row) {
+            if end.get_row() < &min_row[..] {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn may_contain_row(&self, row: &[u8]) -> bool {
+        self.bloom_filter.as_ref().is_none_or(|bloom_filter| bloom_filter.may_contain(row))
+    }
+
+    pub fn may_contain_time_range(&self, time_range: &Option<TimeRange>) -> bool {
+        let (stats, time_range) = match (&self.stats, time_range) {
+            (Some(stats), Some(time_range)) => (stats, time_range),
+            _ => return true,
+        };
+
+        // Tombstones older than the range cannot delete anything inside it.
+        if let Some(max_ts) = stats.max_timestamp {
+            if max_ts < time_range.start.into() {
+                return false;
+            }
+        }
+
+        // Column and family tombstones newer than the range still delete older versions inside it.
+        if let Some(min_ts) = stats.min_timestamp {
+            if min_ts > time_range.end.into() && stats.delete_column_count + stats.delete_family_count == 0 {
+                return false;
+            }
+        }
+
+        true
+    }
+
     pub fn get_blocks(&self, start: Option<KeyValue>, end: Option<KeyValue>) -> Vec<DataBlock> {
         let entry = match start {
             Some(start) => {
-                self.index.lower_bound(std::ops::Bound::Included(&start))  
+                // Block keys are the first keys of blocks, so the block holding start begins at or before it.
+                self.index.upper_bound(std::ops::Bound::Included(&start))
+                    .or_else(|| self.index.front())
             },
             None => {
                 self.index.front()
@@ -72,6 +155,12 @@ impl SSTable {
         }).collect_vec()
     }
 
+    pub fn get_index_iter(&self) -> impl Iterator<Item = (KeyValue, DataBlock)> + '_ {
+        self.index.iter().map(|entry| {
+            (entry.key().clone(), entry.value().clone())
+        })
+    }
+
     pub fn get_table(&self) -> &Bytes {
         &self.table
     }
@@ -83,6 +172,10 @@ impl SSTable {
     pub fn get_segment(&self) -> &Bytes {
         &self.segment
     }
+
+    pub fn get_readers(&self) -> Weak<()> {
+        Arc::downgrade(&self.readers)
+    }
 }
 
 impl Clone for SSTable {
@@ -97,6 +190,10 @@ impl Clone for SSTable {
                 })
             ),
             max_mvcc_id: self.max_mvcc_id.clone(),
+            size: self.size,
+            stats: self.stats.clone(),
+            bloom_filter: self.bloom_filter.clone(),
+            readers: self.readers.clone(),
         }
     }
 }
@@ -0,0 +1,54 @@
+use std::io::Write;
+
+use bytes::Bytes;
+
+use crate::{cell::CellType, key_value::KeyValue, visibility::ColumnVisibility, Timestamp};
+
+use super::{SSTableWriter, Statistics};
+
+// Builds segment files offline from input sorted by row, column and descending timestamp.
+pub struct SSTableBuilder<W: Write> {
+    writer: SSTableWriter<W>,
+    family: Bytes,
+    last_kv: Option<KeyValue>,
+}
+
+impl<W: Write> SSTableBuilder<W> {
+    pub fn new(w: W, family: Bytes) -> SSTableBuilder<W> {
+        SSTableBuilder { writer: SSTableWriter::new(w), family, last_kv: None }
+    }
+
+    pub fn put(&mut self, row: &Bytes, column: &Bytes, timestamp: Timestamp, value: &Bytes) -> Result<(), &'static str> {
+        self.add(KeyValue::new(row, &self.family, column, timestamp, &CellType::Put, value))
+    }
+
+    pub fn put_with_visibility(&mut self, row: &Bytes, column: &Bytes, timestamp: Timestamp, value: &Bytes, visibility: &Bytes) -> Result<(), &'static str> {
+        ColumnVisibility::validate(visibility)?;
+        self.add(KeyValue::new_with_visibility(row, &self.family, column, timestamp, &CellType::Put, value, visibility))
+    }
+
+    pub fn delete_column(&mut self, row: &Bytes, column: &Bytes, timestamp: Timestamp) -> Result<(), &'static str> {
+        self.add(KeyValue::new(row, &self.family, column, timestamp, &CellType::DeleteColumn, &Bytes::new()))
+    }
+
+    pub fn finish(mut self) -> Result<Statistics, &'static str> {
+        if self.last_kv.is_none() {
+            return Err("SSTable cannot be empty.");
+        }
+
+        self.writer.end();
+        Ok(self.writer.get_stats().clone())
+    }
+
+    fn add(&mut self, kv: KeyValue) -> Result<(), &'static str> {
+        if let Some(last_kv) = &self.last_kv {
+            if &kv <= last_kv {
+                return Err("Keys must be added in strictly increasing order.");
+            }
+        }
+
+        self.writer.write_kv(&kv);
+        self.last_kv = Some(kv);
+        Ok(())
+    }
+}
\ No newline at end of file
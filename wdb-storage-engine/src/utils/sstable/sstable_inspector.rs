@@ -0,0 +1,207 @@
+use std::io::{Read, Seek};
+
+use crossbeam_skiplist::SkipMap;
+use serde::Serialize;
+
+use crate::{cell::{Cell, CellType}, key_value::KeyValue, Compression};
+
+use super::{data_block::DataBlock, SSTableReader, Statistics};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectedFooter {
+    pub version: u32,
+    pub size: u64,
+    pub index_pos: u64,
+    pub index_len: u64,
+    pub props_pos: Option<u64>,
+    pub props_len: Option<u64>,
+    pub bloom_pos: Option<u64>,
+    pub bloom_len: Option<u64>,
+    pub compression: Compression,
+    pub max_mvcc: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectedBlock {
+    pub offset: u64,
+    pub data_size: u64,
+    pub crc32: Option<u32>,
+    pub first_key: InspectedCell,
+}
+
+// Byte fields are escaped, so binary rows and values stay readable in text and JSON output.
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectedCell {
+    pub row: String,
+    pub family: String,
+    pub column: String,
+    pub timestamp: u64,
+    pub cell_type: String,
+    pub mvcc_id: u64,
+    pub value: String,
+    pub visibility: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    pub blocks: u64,
+    pub cells: u64,
+    pub errors: Vec<String>,
+}
+
+pub struct SSTableInspector<R: Read + Seek> {
+    reader: SSTableReader<R>,
+    index: SkipMap<KeyValue, DataBlock>,
+}
+
+impl<R: Read + Seek> SSTableInspector<R> {
+    pub fn open(r: R) -> Result<SSTableInspector<R>, &'static str> {
+        let mut reader = SSTableReader::try_new(r)?;
+        let index = reader.try_read_index()?;
+
+        Ok(SSTableInspector { reader, index })
+    }
+
+    pub fn get_footer(&self) -> InspectedFooter {
+        InspectedFooter {
+            version: self.reader.version(),
+            size: self.reader.size(),
+            index_pos: self.reader.index_pos(),
+            index_len: self.reader.index_len(),
+            props_pos: self.reader.props().map(|props| props.0),
+            props_len: self.reader.props().map(|props| props.1),
+            bloom_pos: self.reader.bloom().map(|bloom| bloom.0),
+            bloom_len: self.reader.bloom().map(|bloom| bloom.1),
+            compression: self.reader.compression(),
+            max_mvcc: self.reader.max_mvcc_id(),
+        }
+    }
+
+    pub fn get_blocks(&self) -> Vec<InspectedBlock> {
+        self.index.iter().map(|entry| {
+            let block = entry.value();
+            InspectedBlock { 
+                offset: block.get_offset() as u64, 
+                data_size: block.get_data_size() as u64, 
+                crc32: block.get_crc32(),
+                first_key: inspect_cell(entry.key()),
+            }
+        }).collect()
+    }
+
+    pub fn read_block(&mut self, i: usize) -> Result<Vec<InspectedCell>, &'static str> {
+        let entry = self.index.iter().nth(i).ok_or("Block does not exist.")?;
+        let kvs = self.reader.read_block(entry.value())?;
+
+        kvs.iter().map(|kv| {
+            kv.validate_key()?;
+            Ok(inspect_cell(kv))
+        }).collect()
+    }
+
+    pub fn read_stats(&mut self) -> Result<Option<Statistics>, &'static str> {
+        self.reader.try_read_stats()
+    }
+
+    pub fn verify(&mut self) -> VerifyReport {
+        let mut report = VerifyReport::default();
+        let max_mvcc = self.reader.max_mvcc_id();
+
+        let bloom_filter = match self.reader.try_read_bloom_filter() {
+            Ok(bloom_filter) => bloom_filter,
+            Err(err) => {
+                report.errors.push(err.to_string());
+                None
+            }
+        };
+
+        let mut stats = Statistics::default();
+        let mut last_kv: Option<KeyValue> = None;
+        let mut expected_offset = 0;
+        let blocks = self.index.iter().map(|entry| (entry.key().clone(), entry.value().clone())).collect::<Vec<(KeyValue, DataBlock)>>();
+        for (i, (first_key, block)) in blocks.iter().enumerate() {
+            report.blocks += 1;
+            if block.get_offset() != expected_offset {
+                report.errors.push(format!("Block {} starts at {}, expected {}.", i, block.get_offset(), expected_offset));
+            }
+            expected_offset = block.get_offset() + block.get_data_size();
+
+            let kvs = match self.reader.read_block(block) {
+                Ok(kvs) => kvs,
+                Err(err) => {
+                    report.errors.push(format!("Block {}: {}", i, err));
+                    continue;
+                }
+            };
+
+            for (j, kv) in kvs.iter().enumerate() {
+                report.cells += 1;
+                if let Err(err) = kv.validate_key() {
+                    report.errors.push(format!("Block {} cell {}: {}", i, j, err));
+                    continue;
+                }
+                if j == 0 && kv.get_key() != first_key.get_key() {
+                    report.errors.push(format!("Block {}: index key does not match the first cell.", i));
+                }
+                if last_kv.as_ref().is_some_and(|last_kv| kv <= last_kv) {
+                    report.errors.push(format!("Block {} cell {}: key is out of order.", i, j));
+                }
+                if bloom_filter.as_ref().is_some_and(|bloom_filter| !bloom_filter.may_contain(kv.get_row())) {
+                    report.errors.push(format!("Block {} cell {}: row is missing from the bloom filter.", i, j));
+                }
+                if kv.get_mvcc_id() > max_mvcc {
+                    report.errors.push(format!("Block {} cell {}: MVCC id {} is above footer maximum {}.", i, j, kv.get_mvcc_id(), max_mvcc));
+                }
+
+                stats.add_kv(kv);
+                last_kv = Some(kv.clone());
+            }
+        }
+        if expected_offset as u64 != self.reader.index_pos() {
+            report.errors.push(format!("Data blocks end at {}, index starts at {}.", expected_offset, self.reader.index_pos()));
+        }
+
+        // Block checksums are checked by read_block, the properties block cross-checks the cell counts and bounds.
+        match self.reader.try_read_stats() {
+            Err(err) => report.errors.push(err.to_string()),
+            Ok(None) => {},
+            Ok(Some(props)) => {
+                if props.disk_bytes != self.reader.size() {
+                    report.errors.push(format!("Properties report {} bytes on disk, file has {}.", props.disk_bytes, self.reader.size()));
+                }
+                stats.disk_bytes = props.disk_bytes;
+                if stats != props {
+                    report.errors.push("Properties block does not match data blocks.".to_string());
+                }
+            },
+        }
+
+        report
+    }
+}
+
+fn inspect_cell(kv: &KeyValue) -> InspectedCell {
+    let cell_type = match kv.get_cell_type() {
+        CellType::Minimum => "Minimum",
+        CellType::Put => "Put",
+        CellType::Delete => "Delete",
+        CellType::DeleteColumn => "DeleteColumn",
+        CellType::DeleteFamily => "DeleteFamily",
+        CellType::Maximum => "Maximum",
+    };
+
+    InspectedCell {
+        row: escape(kv.get_row()),
+        family: escape(kv.get_cf()),
+        column: escape(kv.get_col()),
+        timestamp: kv.get_timestamp().into(),
+        cell_type: cell_type.to_string(),
+        mvcc_id: kv.get_mvcc_id(),
+        value: escape(kv.get_value()),
+        visibility: escape(kv.get_visibility()),
+    }
+}
+
+fn escape(bytes: &[u8]) -> String {
+    bytes.iter().flat_map(|b| std::ascii::escape_default(*b)).map(char::from).collect()
+}
\ No newline at end of file
@@ -2,17 +2,21 @@ use std::{io::{Read, Seek, SeekFrom}, iter::Skip};
 
 use bytes::{Buf, Bytes};
 use crossbeam_skiplist::SkipMap;
-use itertools::Itertools;
 
-use crate::key_value::KeyValue;
+use crate::{key_value::KeyValue, Compression};
 
-use super::data_block::DataBlock;
+use super::{bloom_filter::BloomFilter, data_block::DataBlock, sstable_stats::Statistics, SSTable};
 
 pub struct SSTableReader<R: Read + Seek> {
     r: R,
+    size: u64,
     index_pos: u64,
     index_len: u64,
     max_mvcc: u64,
+    version: u32,
+    props: Option<(u64, u64)>,
+    bloom: Option<(u64, u64)>,
+    compression: Compression,
 }
 
 
@@ -21,73 +25,234 @@ where
     R: Read + Seek
     {
 
-    pub fn new(mut r: R) -> SSTableReader<R> {
-        r.seek(SeekFrom::End(-4 * 8)).unwrap();
-        let mut buf = [0u8; 4 * 8];
-        r.read_exact(&mut buf).unwrap();
-        let mut buf = Bytes::from(buf.to_vec());
-        if buf.get_u64() != 0xDB1234AB {
-            panic!("Invalid magic number. Not an SSTable file.");
+    pub fn new(r: R) -> SSTableReader<R> {
+        SSTableReader::try_new(r).unwrap()
+    }
+
+    pub fn try_new(mut r: R) -> Result<SSTableReader<R>, &'static str> {
+        let size = r.seek(SeekFrom::End(0)).map_err(|_| "Unable to read SSTable file.")?;
+        if size < SSTable::FOOTER_V1_SIZE as u64 {
+            return Err("Invalid SSTable file. File is too small.");
         }
+
+        r.seek(SeekFrom::End(-(SSTable::FOOTER_V1_SIZE as i64))).map_err(|_| "Unable to read SSTable file.")?;
+        let mut buf = [0u8; SSTable::FOOTER_V1_SIZE];
+        r.read_exact(&mut buf).map_err(|_| "Unable to read SSTable file.")?;
+        let mut buf = Bytes::from(buf.to_vec());
+        let magic = buf.get_u64();
+        let version = match magic {
+            SSTable::MAGIC_V1 => 1,
+            SSTable::MAGIC_V2 => 2,
+            SSTable::MAGIC_V3 => 3,
+            SSTable::MAGIC_V4 => 4,
+            SSTable::MAGIC_V5 => 5,
+            _ => return Err("Invalid magic number. Not an SSTable file."),
+        };
         let index_pos = buf.get_u64();
         let index_len = buf.get_u64();
         let max_mvcc = buf.get_u64();
 
-        SSTableReader { r, index_pos, index_len, max_mvcc }
+        // Version 2 footer prepends the properties block position to the version 1 footer.
+        let mut props = None;
+        if version >= 2 {
+            if size < SSTable::FOOTER_V2_SIZE as u64 {
+                return Err("Invalid SSTable file. File is too small.");
+            }
+            r.seek(SeekFrom::End(-(SSTable::FOOTER_V2_SIZE as i64))).map_err(|_| "Unable to read SSTable file.")?;
+            let mut buf = [0u8; 2 * 8];
+            r.read_exact(&mut buf).map_err(|_| "Unable to read SSTable file.")?;
+            let mut buf = Bytes::from(buf.to_vec());
+            props = Some((buf.get_u64(), buf.get_u64()));
+        }
+
+        // Version 5 footer prepends the bloom filter block position and the block compression.
+        let mut bloom = None;
+        let mut compression = Compression::None;
+        if version >= 5 {
+            if size < SSTable::FOOTER_V5_SIZE as u64 {
+                return Err("Invalid SSTable file. File is too small.");
+            }
+            r.seek(SeekFrom::End(-(SSTable::FOOTER_V5_SIZE as i64))).map_err(|_| "Unable to read SSTable file.")?;
+            let mut buf = [0u8; 3 * 8];
+            r.read_exact(&mut buf).map_err(|_| "Unable to read SSTable file.")?;
+            let mut buf = Bytes::from(buf.to_vec());
+            let (bloom_pos, bloom_len) = (buf.get_u64(), buf.get_u64());
+            if bloom_len > 0 {
+                bloom = Some((bloom_pos, bloom_len));
+            }
+            compression = Compression::from_id(buf.get_u64())?;
+        }
+
+        if index_pos.checked_add(index_len).is_none_or(|end| end > size) {
+            return Err("Invalid SSTable file. Index is out of bounds.");
+        }
+
+        Ok(SSTableReader { r, size, index_pos, index_len, max_mvcc, version, props, bloom, compression })
     }
 
     pub fn max_mvcc_id(&self) -> u64 {
         self.max_mvcc
     }
 
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn index_pos(&self) -> u64 {
+        self.index_pos
+    }
+
+    pub fn index_len(&self) -> u64 {
+        self.index_len
+    }
+
+    pub fn props(&self) -> Option<(u64, u64)> {
+        self.props
+    }
+
+    pub fn bloom(&self) -> Option<(u64, u64)> {
+        self.bloom
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    pub fn read_stats(&mut self) -> Option<Statistics> {
+        self.try_read_stats().unwrap()
+    }
+
+    pub fn try_read_stats(&mut self) -> Result<Option<Statistics>, &'static str> {
+        let (props_pos, props_len) = match self.props {
+            Some(props) => props,
+            None => return Ok(None),
+        };
+        if props_pos.checked_add(props_len).is_none_or(|end| end > self.size) {
+            return Err("Invalid SSTable file. Properties block is out of bounds.");
+        }
+
+        self.r.seek(SeekFrom::Start(props_pos)).map_err(|_| "Unable to read SSTable properties.")?;
+        let mut buf = vec![0u8; props_len as usize];
+        self.r.read_exact(&mut buf).map_err(|_| "Unable to read SSTable properties.")?;
+
+        bincode::deserialize(&buf).map(Some).map_err(|_| "Invalid SSTable properties block.")
+    }
+
+    pub fn read_bloom_filter(&mut self) -> Option<BloomFilter> {
+        self.try_read_bloom_filter().unwrap()
+    }
+
+    pub fn try_read_bloom_filter(&mut self) -> Result<Option<BloomFilter>, &'static str> {
+        let (bloom_pos, bloom_len) = match self.bloom {
+            Some(bloom) => bloom,
+            None => return Ok(None),
+        };
+        if bloom_pos.checked_add(bloom_len).is_none_or(|end| end > self.size) {
+            return Err("Invalid SSTable file. Bloom filter block is out of bounds.");
+        }
+
+        self.r.seek(SeekFrom::Start(bloom_pos)).map_err(|_| "Unable to read SSTable bloom filter.")?;
+        let mut buf = vec![0u8; bloom_len as usize];
+        self.r.read_exact(&mut buf).map_err(|_| "Unable to read SSTable bloom filter.")?;
+
+        BloomFilter::from_bytes(Bytes::from(buf)).map(Some)
+    }
+
     pub fn read_index(&mut self) -> SkipMap<KeyValue, DataBlock>{
+        self.try_read_index().unwrap()
+    }
+
+    pub fn try_read_index(&mut self) -> Result<SkipMap<KeyValue, DataBlock>, &'static str> {
         let result: SkipMap<KeyValue, DataBlock> = SkipMap::new();
 
-        self.r.seek(SeekFrom::Start(self.index_pos)).unwrap();
+        self.r.seek(SeekFrom::Start(self.index_pos)).map_err(|_| "Unable to read SSTable index.")?;
         let mut buf = vec![0u8; self.index_len as usize];
-        self.r.read_exact(&mut buf).unwrap();
+        self.r.read_exact(&mut buf).map_err(|_| "Unable to read SSTable index.")?;
         
         let mut buf = Bytes::from(buf);
 
         while buf.has_remaining() {
+            let crc_len = if self.version >= 4 { 4 } else { 0 };
+            if buf.remaining() < 8 + 8 + crc_len + 2 {
+                return Err("Invalid SSTable index.");
+            }
             let offset = buf.get_u64() as usize;
             let data_size = buf.get_u64() as usize;
+            let crc32 = if self.version >= 4 { Some(buf.get_u32()) } else { None };
 
             let key_len = buf.get_u16();
+            if buf.remaining() < key_len as usize || offset.checked_add(data_size).is_none_or(|end| end > self.index_pos as usize) {
+                return Err("Invalid SSTable index.");
+            }
             
             let key = buf.get(..key_len as usize).unwrap().to_vec();
             buf.advance(key_len as usize);
             let key = Bytes::from(key);
 
-            result.insert(KeyValue::new_from_key(key_len, key.clone()), DataBlock { offset, data_size, key_len, key });
+            let first_key = KeyValue::new_from_key(key_len, key.clone());
+            first_key.validate_key().map_err(|_| "Invalid SSTable index key.")?;
+            result.insert(first_key, DataBlock { offset, data_size, key_len, key, crc32 });
         }
 
-        result
-    }
-
-    pub fn read_blocks(&mut self, blocks: Vec<DataBlock>) -> Vec<KeyValue> {
-        blocks.iter().map(|block| {
-            self.r.seek(SeekFrom::Start(block.get_offset() as u64)).unwrap();
-            let mut buf = vec![0u8; block.get_data_size()];
-            self.r.read_exact(&mut buf).unwrap();
-
-            let mut buf = Bytes::from(buf);
-            let mut results = vec![];
-            while buf.has_remaining() {
-                let key_len = buf.get_u16();
-                let val_len = buf.get_u64();
-                let key = buf.get(..key_len as usize).unwrap().to_vec();
-                buf.advance(key_len as usize);
-                let val = buf.get(..val_len as usize).unwrap().to_vec();
-                buf.advance(val_len as usize);
-                let mvcc_id = buf.get_u64();
-                let mut kv = KeyValue::new_from_kv_bytes(key_len, key, val_len, val);
-                kv.set_mvcc_id(mvcc_id);
-                results.push(kv);
-            }
+        Ok(result)
+    }
 
-            results
-        }).flatten().collect_vec()
+    pub fn read_blocks(&mut self, blocks: Vec<DataBlock>) -> Result<Vec<KeyValue>, &'static str> {
+        let mut results = vec![];
+        for block in blocks.iter() {
+            results.extend(self.read_block(block)?);
+        }
+        Ok(results)
     }
 
-} 
\ No newline at end of file
+    pub fn read_block(&mut self, block: &DataBlock) -> Result<Vec<KeyValue>, &'static str> {
+        self.r.seek(SeekFrom::Start(block.get_offset() as u64)).map_err(|_| "Unable to read SSTable data block.")?;
+        let mut buf = vec![0u8; block.get_data_size()];
+        self.r.read_exact(&mut buf).map_err(|_| "Unable to read SSTable data block.")?;
+        if block.get_crc32().is_some_and(|crc32| crc32 != crc32fast::hash(&buf)) {
+            return Err("SSTable data block checksum mismatch.");
+        }
+
+        let mut buf = Bytes::from(self.compression.decompress(buf)?);
+        let mut results = vec![];
+        while buf.has_remaining() {
+            if buf.remaining() < 2 + 8 {
+                return Err("Invalid SSTable data block.");
+            }
+            let key_len = buf.get_u16();
+            let val_len = buf.get_u64();
+            let cell_len = (key_len as u64).checked_add(val_len).and_then(|len| len.checked_add(8));
+            if cell_len.is_none_or(|cell_len| (buf.remaining() as u64) < cell_len) {
+                return Err("Invalid SSTable data block.");
+            }
+            let key = buf.get(..key_len as usize).unwrap().to_vec();
+            buf.advance(key_len as usize);
+            let val = buf.get(..val_len as usize).unwrap().to_vec();
+            buf.advance(val_len as usize);
+
+            let mut visibility = vec![];
+            if self.version >= 3 {
+                if buf.remaining() < 2 {
+                    return Err("Invalid SSTable data block.");
+                }
+                let visibility_len = buf.get_u16() as usize;
+                if buf.remaining() < visibility_len + 8 {
+                    return Err("Invalid SSTable data block.");
+                }
+                visibility = buf.get(..visibility_len).unwrap().to_vec();
+                buf.advance(visibility_len);
+            }
+
+            let mvcc_id = buf.get_u64();
+            let mut kv = KeyValue::new_from_kv_bytes(key_len, key, val_len, val, visibility);
+            kv.set_mvcc_id(mvcc_id);
+            results.push(kv);
+        }
+
+        Ok(results)
+    }
+}
\ No newline at end of file
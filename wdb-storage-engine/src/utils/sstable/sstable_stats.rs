@@ -0,0 +1,87 @@
+use std::cmp::{max, min};
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::{cell::{Cell, CellType}, key_value::KeyValue};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Statistics {
+    pub cell_count: u64,
+    pub row_count: u64,
+    pub put_count: u64,
+    pub delete_count: u64,
+    pub delete_column_count: u64,
+    pub delete_family_count: u64,
+    pub min_timestamp: Option<u64>,
+    pub max_timestamp: Option<u64>,
+    pub min_row: Option<Bytes>,
+    pub max_row: Option<Bytes>,
+    pub raw_bytes: u64,
+    pub disk_bytes: u64,
+}
+
+impl Statistics {
+    // Cells have to be added in the segment order, otherwise row count and row range are invalid.
+    pub fn add_kv(&mut self, kv: &KeyValue) {
+        self.cell_count += 1;
+        self.raw_bytes += kv.get_size();
+
+        match kv.get_cell_type() {
+            CellType::Put => self.put_count += 1,
+            CellType::Delete => self.delete_count += 1,
+            CellType::DeleteColumn => self.delete_column_count += 1,
+            CellType::DeleteFamily => self.delete_family_count += 1,
+            _ => {},
+        }
+
+        let ts: u64 = kv.get_timestamp().into();
+        self.min_timestamp = Some(self.min_timestamp.map_or(ts, |min_ts| min(min_ts, ts)));
+        self.max_timestamp = Some(self.max_timestamp.map_or(ts, |max_ts| max(max_ts, ts)));
+
+        let is_new_row = match &self.max_row {
+            Some(row) => row[..] != *kv.get_row(),
+            None => true,
+        };
+        if is_new_row {
+            self.row_count += 1;
+            self.max_row = Some(Bytes::copy_from_slice(kv.get_row()));
+        }
+        if self.min_row.is_none() {
+            self.min_row = Some(Bytes::copy_from_slice(kv.get_row()));
+        }
+    }
+
+    pub fn get_tombstone_count(&self) -> u64 {
+        self.delete_count + self.delete_column_count + self.delete_family_count
+    }
+
+    // Rows spanning many segments are counted once per segment, so merged row count is an upper bound.
+    pub fn merge(&mut self, other: &Statistics) {
+        self.cell_count += other.cell_count;
+        self.row_count += other.row_count;
+        self.put_count += other.put_count;
+        self.delete_count += other.delete_count;
+        self.delete_column_count += other.delete_column_count;
+        self.delete_family_count += other.delete_family_count;
+        self.raw_bytes += other.raw_bytes;
+        self.disk_bytes += other.disk_bytes;
+
+        self.min_timestamp = match (self.min_timestamp, other.min_timestamp) {
+            (Some(a), Some(b)) => Some(min(a, b)),
+            (a, b) => a.or(b),
+        };
+        self.max_timestamp = match (self.max_timestamp, other.max_timestamp) {
+            (Some(a), Some(b)) => Some(max(a, b)),
+            (a, b) => a.or(b),
+        };
+        self.min_row = match (self.min_row.take(), other.min_row.clone()) {
+            (Some(a), Some(b)) => Some(min(a, b)),
+            (a, b) => a.or(b),
+        };
+        self.max_row = match (self.max_row.take(), other.max_row.clone()) {
+            (Some(a), Some(b)) => Some(max(a, b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
\ No newline at end of file
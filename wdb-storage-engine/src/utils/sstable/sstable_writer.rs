@@ -1,28 +1,45 @@
-use std::{cell::RefCell, cmp::max, io::{Read, Write}, rc::Rc};
+use std::{cell::RefCell, cmp::max, io::Write, rc::Rc};
 
 use bytes::{BufMut, Bytes, BytesMut};
 use crossbeam_skiplist::SkipMap;
 
-use crate::{cell::Cell, key_value::KeyValue};
+use crate::{cell::Cell, key_value::KeyValue, Compression, FamilyOptions};
 
-use super::data_block::DataBlock;
+use super::{bloom_filter::BloomFilter, data_block::DataBlock, sstable_stats::Statistics, SSTable};
 
-pub struct SSTableWriter<'a, W:Write> {
-    writer: &'a mut W,
+pub struct SSTableWriter<W:Write> {
+    writer: W,
     offset: usize,
     max_mvcc: u64,
+    stats: Statistics,
     data_blocks: Vec<Rc<RefCell<DataBlock>>>,
     curr_data_block: Option<Rc<RefCell<DataBlock>>>,
+    // Cells of the current block, written compressed once the block is full.
+    curr_block_buf: Vec<u8>,
+    compression: Compression,
+    bloom_bits_per_key: Option<u32>,
+    row_hashes: Vec<u64>,
+    bloom_filter: Option<BloomFilter>,
 }
 
-impl<W: Write> SSTableWriter<'_, W> {
-    pub fn new(w: &mut W) -> SSTableWriter<W> {
+impl<W: Write> SSTableWriter<W> {
+    pub fn new(w: W) -> SSTableWriter<W> {
+        SSTableWriter::with_options(w, &FamilyOptions::default())
+    }
+
+    pub fn with_options(w: W, options: &FamilyOptions) -> SSTableWriter<W> {
         SSTableWriter {
             writer: w,
             offset: 0,
             max_mvcc: 0,
+            stats: Statistics::default(),
             data_blocks: vec![],
             curr_data_block: None,
+            curr_block_buf: vec![],
+            compression: options.compression,
+            bloom_bits_per_key: options.bloom_filter.enabled.then_some(options.bloom_filter.bits_per_key),
+            row_hashes: vec![],
+            bloom_filter: None,
         }
     }
 
@@ -33,20 +50,29 @@ impl<W: Write> SSTableWriter<'_, W> {
         self.create_data_block_if_necessary(key_len, &Bytes::from(key));
 
         self.max_mvcc = max(self.max_mvcc, kv.get_mvcc_id());
+        self.stats.add_kv(kv);
 
-        let len = self.writer.write(&kv.as_bytes()).unwrap();
-        self.offset += len;
-        self.curr_data_block.as_ref().unwrap().borrow_mut().data_size += len;
+        // Cells are written in key order, so a row hashes once.
+        if self.bloom_bits_per_key.is_some() {
+            let hash = BloomFilter::hash(kv.get_row());
+            if self.row_hashes.last() != Some(&hash) {
+                self.row_hashes.push(hash);
+            }
+        }
+
+        self.curr_block_buf.extend_from_slice(&kv.as_bytes());
     }
 
     pub fn end(&mut self) -> SkipMap<KeyValue, DataBlock> {
         let index: SkipMap<KeyValue, DataBlock> = SkipMap::new();
+        self.finish_data_block();
 
         let mut buf = BytesMut::new();
         for block in self.data_blocks.iter() {
             let block = block.borrow_mut();
             buf.put_u64(block.offset as u64);
             buf.put_u64(block.data_size as u64);
+            buf.put_u32(block.crc32.unwrap_or_default());
             buf.put_u16(block.key_len);
             buf.put(&block.key[..]);
             index.insert(KeyValue::new_from_key(block.key_len, block.key.clone()), block.clone());
@@ -54,37 +80,90 @@ impl<W: Write> SSTableWriter<'_, W> {
         
         let index_pos = self.offset;
         let len = self.writer.write(&buf.freeze()).unwrap();
+        self.offset += len;
+
+        let bloom_filter = self.bloom_bits_per_key.map(|bits_per_key| BloomFilter::build(&self.row_hashes, bits_per_key));
+        let bloom = bloom_filter.as_ref().map(|bloom_filter| bloom_filter.to_bytes()).unwrap_or_default();
+
+        let props_pos = self.offset;
+        let props_len = bincode::serialized_size(&self.stats).unwrap() as usize;
+        self.stats.disk_bytes = (props_pos + props_len + bloom.len() + SSTable::FOOTER_V5_SIZE) as u64;
+        self.writer.write_all(&bincode::serialize(&self.stats).unwrap()).unwrap();
+        self.offset += props_len;
+
+        let bloom_pos = self.offset;
+        self.writer.write_all(&bloom).unwrap();
+        self.offset += bloom.len();
+        self.bloom_filter = bloom_filter;
 
         let mut buf = BytesMut::new();
-        buf.put_u64(0xDB1234AB); // magic number for validation check
+        buf.put_u64(bloom_pos as u64);
+        buf.put_u64(bloom.len() as u64);
+        buf.put_u64(self.compression.id());
+        buf.put_u64(props_pos as u64);
+        buf.put_u64(props_len as u64);
+        buf.put_u64(SSTable::MAGIC_V5); // magic number for validation check
         buf.put_u64(index_pos as u64);
         buf.put_u64(len as u64);
         buf.put_u64(self.max_mvcc);
 
-        self.writer.write(&buf.freeze()).unwrap();
+        self.offset += self.writer.write(&buf.freeze()).unwrap();
         self.writer.flush().unwrap();
 
         index
     }
 
+    pub fn raise_max_mvcc_id(&mut self, mvcc_id: u64) {
+        self.max_mvcc = max(self.max_mvcc, mvcc_id);
+    }
+
     pub fn get_max_mvcc_id(&self) -> u64 {
         self.max_mvcc
     }
 
+    pub fn get_size(&self) -> u64 {
+        self.offset as u64
+    }
+
+    pub fn get_stats(&self) -> &Statistics {
+        &self.stats
+    }
+
+    pub fn get_bloom_filter(&self) -> Option<BloomFilter> {
+        self.bloom_filter.clone()
+    }
+
     fn create_data_block_if_necessary(&mut self, key_len: u16, key: &Bytes) {
-        if let Some(db) = &self.curr_data_block {
-            if db.borrow_mut().data_size < (2 << 16) {
-                return;
-            }
+        if self.curr_data_block.is_some() && self.curr_block_buf.len() < (2 << 16) {
+            return;
         }
 
+        self.finish_data_block();
         let db = Rc::new(RefCell::new(DataBlock {
-            offset: self.offset,
+            offset: 0,
             data_size: 0,
             key_len,
             key: key.clone(),
+            crc32: None,
         }));
         self.curr_data_block = Some(db.clone());
         self.data_blocks.push(db);
     }
+
+    fn finish_data_block(&mut self) {
+        let db = match self.curr_data_block.take() {
+            Some(db) => db,
+            None => return,
+        };
+
+        // The checksum covers the block as stored, so corruption is caught before decompressing.
+        let bytes = self.compression.compress(std::mem::take(&mut self.curr_block_buf));
+        self.writer.write_all(&bytes).unwrap();
+
+        let mut db = db.borrow_mut();
+        db.offset = self.offset;
+        db.data_size = bytes.len();
+        db.crc32 = Some(crc32fast::hash(&bytes));
+        self.offset += bytes.len();
+    }
 }
\ No newline at end of file
@@ -0,0 +1,17 @@
+use super::Timestamp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: Timestamp,
+    pub end: Timestamp,
+}
+
+impl TimeRange {
+    pub fn new(start: Timestamp, end: Timestamp) -> TimeRange {
+        TimeRange { start, end }
+    }
+
+    pub fn contains(&self, ts: Timestamp) -> bool {
+        self.start <= ts && ts <= self.end
+    }
+}
\ No newline at end of file
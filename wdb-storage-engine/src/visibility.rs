@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+
+use bytes::Bytes;
+
+/// Labels a caller is cleared for, checked against cell visibility expressions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Authorizations {
+    labels: HashSet<Bytes>,
+}
+
+impl Authorizations {
+    pub fn new<I: IntoIterator<Item = T>, T: Into<Bytes>>(labels: I) -> Authorizations {
+        Authorizations { labels: labels.into_iter().map(|label| label.into()).collect() }
+    }
+
+    pub fn contains(&self, label: &[u8]) -> bool {
+        self.labels.contains(label)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VisibilityNode {
+    Term(Bytes),
+    And(Vec<VisibilityNode>),
+    Or(Vec<VisibilityNode>),
+}
+
+/*
+Visibility expression grammar:
+- term: [A-Za-z0-9_-.:/]+
+- expression: term, (expression), expression & expression, expression | expression
+- & and | cannot be mixed on the same level without parentheses
+- empty expression is visible to everyone
+- expressions are limited to MAX_LEN bytes and MAX_DEPTH nested parentheses
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnVisibility {
+    node: Option<VisibilityNode>,
+}
+
+impl ColumnVisibility {
+    // Cells store the expression length as u16.
+    pub const MAX_LEN: usize = u16::MAX as usize;
+    pub const MAX_DEPTH: usize = 64;
+
+    pub fn parse(expression: &[u8]) -> Result<ColumnVisibility, &'static str> {
+        if expression.is_empty() {
+            return Ok(ColumnVisibility { node: None });
+        }
+        if expression.len() > ColumnVisibility::MAX_LEN {
+            return Err("Visibility expression is too long.");
+        }
+
+        let mut pos = 0;
+        let node = ColumnVisibility::parse_expression(expression, &mut pos, 0)?;
+        if pos != expression.len() {
+            return Err("Unbalanced parentheses in visibility expression.");
+        }
+
+        Ok(ColumnVisibility { node: Some(node) })
+    }
+
+    pub fn validate(expression: &[u8]) -> Result<(), &'static str> {
+        ColumnVisibility::parse(expression).map(|_| ())
+    }
+
+    pub fn evaluate(&self, authorizations: &Authorizations) -> bool {
+        match &self.node {
+            None => true,
+            Some(node) => ColumnVisibility::evaluate_node(node, authorizations),
+        }
+    }
+
+    fn evaluate_node(node: &VisibilityNode, authorizations: &Authorizations) -> bool {
+        match node {
+            VisibilityNode::Term(term) => authorizations.contains(term),
+            VisibilityNode::And(nodes) => nodes.iter().all(|node| ColumnVisibility::evaluate_node(node, authorizations)),
+            VisibilityNode::Or(nodes) => nodes.iter().any(|node| ColumnVisibility::evaluate_node(node, authorizations)),
+        }
+    }
+
+    fn parse_expression(expression: &[u8], pos: &mut usize, depth: usize) -> Result<VisibilityNode, &'static str> {
+        let mut nodes = vec![ColumnVisibility::parse_operand(expression, pos, depth)?];
+        let mut operator = None;
+
+        while *pos < expression.len() && expression[*pos] != b')' {
+            let current = expression[*pos];
+            if current != b'&' && current != b'|' {
+                return Err("Expected operator in visibility expression.");
+            }
+            if operator.is_some_and(|operator| operator != current) {
+                return Err("Mixed operators in visibility expression need parentheses.");
+            }
+            operator = Some(current);
+            *pos += 1;
+
+            nodes.push(ColumnVisibility::parse_operand(expression, pos, depth)?);
+        }
+
+        Ok(match operator {
+            None => nodes.pop().unwrap(),
+            Some(b'&') => VisibilityNode::And(nodes),
+            Some(_) => VisibilityNode::Or(nodes),
+        })
+    }
+
+    fn parse_operand(expression: &[u8], pos: &mut usize, depth: usize) -> Result<VisibilityNode, &'static str> {
+        if *pos < expression.len() && expression[*pos] == b'(' {
+            if depth == ColumnVisibility::MAX_DEPTH {
+                return Err("Visibility expression is nested too deeply.");
+            }
+            *pos += 1;
+            let node = ColumnVisibility::parse_expression(expression, pos, depth + 1)?;
+            if *pos >= expression.len() || expression[*pos] != b')' {
+                return Err("Unbalanced parentheses in visibility expression.");
+            }
+            *pos += 1;
+            return Ok(node);
+        }
+
+        let start = *pos;
+        while *pos < expression.len() && ColumnVisibility::is_term_char(expression[*pos]) {
+            *pos += 1;
+        }
+        if start == *pos {
+            return Err("Empty term in visibility expression.");
+        }
+
+        Ok(VisibilityNode::Term(Bytes::copy_from_slice(&expression[start..*pos])))
+    }
+
+    fn is_term_char(c: u8) -> bool {
+        c.is_ascii_alphanumeric() || matches!(c, b'_' | b'-' | b'.' | b':' | b'/')
+    }
+}
\ No newline at end of file
@@ -0,0 +1,5 @@
+mod watch_registry;
+mod watch_filter;
+
+pub use watch_registry::{WatchRegistry, WatchEvent};
+pub use watch_filter::WatchFilter;
\ No newline at end of file
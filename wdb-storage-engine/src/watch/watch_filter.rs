@@ -0,0 +1,53 @@
+use bytes::Bytes;
+
+use crate::RowMutationOp;
+
+#[derive(Debug, Clone, Default)]
+pub struct WatchFilter {
+    pub start_row: Option<Bytes>,
+    pub end_row: Option<Bytes>,
+    pub prefix: Option<Bytes>,
+    pub family: Option<Bytes>,
+    pub column: Option<Bytes>,
+}
+
+impl WatchFilter {
+    pub fn matches_row(&self, row: &Bytes) -> bool {
+        if let Some(start_row) = &self.start_row {
+            if row < start_row {
+                return false;
+            }
+        }
+        if let Some(end_row) = &self.end_row {
+            if row >= end_row {
+                return false;
+            }
+        }
+        if let Some(prefix) = &self.prefix {
+            if !row.starts_with(prefix) {
+                return false;
+            }
+        }
+        true
+    }
+
+    // Family deletes carry no column, so they match any column filter within the family.
+    pub fn matches_op(&self, op: &RowMutationOp) -> bool {
+        let (family, column) = match op {
+            RowMutationOp::Put { family, column, .. } => (family, Some(column)),
+            RowMutationOp::DeleteCell { family, column, .. } => (family, Some(column)),
+            RowMutationOp::DeleteColumn { family, column, .. } => (family, Some(column)),
+            RowMutationOp::DeleteFamily { family, .. } => (family, None),
+        };
+
+        if let Some(filter_family) = &self.family {
+            if family != filter_family {
+                return false;
+            }
+        }
+        match (&self.column, column) {
+            (Some(filter_column), Some(column)) => column == filter_column,
+            _ => true,
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bytes::Bytes;
+use dashmap::DashMap;
+use log::debug;
+use tokio::sync::mpsc;
+
+use crate::{RowMutationOp, Timestamp};
+
+use super::WatchFilter;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    Change {
+        row: Bytes,
+        write_num: u64,
+        commit_ts: Timestamp,
+        ops: Vec<RowMutationOp>,
+    },
+
+    // Last event on a subscription whose buffer filled up; the subscription is closed afterwards.
+    Overflow,
+}
+
+struct Watcher {
+    filter: WatchFilter,
+    tx: mpsc::Sender<WatchEvent>,
+}
+
+pub struct WatchRegistry {
+    next_id: AtomicU64,
+    watchers: DashMap<u64, Watcher>,
+}
+
+impl WatchRegistry {
+    pub fn new() -> WatchRegistry {
+        WatchRegistry {
+            next_id: AtomicU64::new(0),
+            watchers: DashMap::new(),
+        }
+    }
+
+    pub fn subscribe(&self, filter: WatchFilter, buffer_size: usize) -> mpsc::Receiver<WatchEvent> {
+        // One extra slot is kept for the overflow signal.
+        let (tx, rx) = mpsc::channel(buffer_size + 1);
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.watchers.insert(id, Watcher { filter, tx });
+        rx
+    }
+
+    pub fn get_watchers_count(&self) -> usize {
+        self.watchers.len()
+    }
+
+    pub fn publish(&self, row: &Bytes, write_num: u64, ops: &[RowMutationOp]) {
+        if self.watchers.is_empty() {
+            return;
+        }
+
+        let commit_ts = Timestamp::ensure_timestamp(None);
+        self.watchers.retain(|id, watcher| {
+            if watcher.tx.is_closed() {
+                debug!("Removing closed watcher {}", id);
+                return false;
+            }
+            if !watcher.filter.matches_row(row) {
+                return true;
+            }
+
+            let ops = ops.iter().filter(|op| watcher.filter.matches_op(op)).cloned().collect::<Vec<RowMutationOp>>();
+            if ops.is_empty() {
+                return true;
+            }
+
+            if watcher.tx.capacity() <= 1 {
+                debug!("Watcher {} overflowed", id);
+                let _ = watcher.tx.try_send(WatchEvent::Overflow);
+                return false;
+            }
+
+            watcher.tx.try_send(WatchEvent::Change { row: row.clone(), write_num, commit_ts, ops }).is_ok()
+        });
+    }
+}
\ No newline at end of file
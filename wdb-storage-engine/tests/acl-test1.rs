@@ -0,0 +1,44 @@
+mod utils;
+
+use bytes::Bytes;
+use wdb_storage_engine::{Acl, AclEntry, Permission, PersistanceLayer, StorageEngine};
+
+use crate::utils::MemoryPersistance;
+
+#[test]
+fn acl_test() {
+    let acl = Acl::new(vec![
+        AclEntry { member: "user:alice".to_string(), permission: Permission::Write },
+        AclEntry { member: "role:auditors".to_string(), permission: Permission::Read },
+    ]);
+
+    assert!(acl.allows(&["user:alice"], Permission::Read));
+    assert!(acl.allows(&["user:alice"], Permission::Write));
+    assert!(!acl.allows(&["user:alice"], Permission::Admin));
+    assert!(acl.allows(&["user:bob", "role:auditors"], Permission::Read));
+    assert!(!acl.allows(&["user:bob", "role:auditors"], Permission::Write));
+    assert!(!acl.allows(&["user:bob"], Permission::Read));
+
+    let everyone = Acl::new(vec![AclEntry { member: Acl::ALL_MEMBERS.to_string(), permission: Permission::Read }]);
+    assert!(everyone.allows(&["user:bob"], Permission::Read));
+
+    let table_name = Bytes::from("users");
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+
+    assert_eq!(storage_engine.get_table_acl(table_name.clone()).unwrap(), None);
+    storage_engine.set_table_acl(table_name.clone(), Some(acl.clone())).unwrap();
+    assert_eq!(storage_engine.get_table_acl(table_name.clone()).unwrap(), Some(acl.clone()));
+    assert!(storage_engine.set_table_acl(Bytes::from("missing"), None).is_err());
+
+    // Permission checks share the table, so they go through while a reader holds it.
+    let reader = storage_engine.get_tables_iter().next().unwrap();
+    assert_eq!(storage_engine.get_table_acl(table_name.clone()).unwrap(), Some(acl.clone()));
+    drop(reader);
+
+    storage_engine.set_catalog_acl(Some(everyone.clone()));
+    assert_eq!(storage_engine.get_catalog_acl().as_deref(), Some(&everyone));
+    assert_eq!(storage_engine.get_persitance_layer().get_catalog_acl(), Some(everyone));
+    storage_engine.set_catalog_acl(None);
+    assert!(storage_engine.get_catalog_acl().is_none());
+}
\ No newline at end of file
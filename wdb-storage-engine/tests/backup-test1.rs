@@ -0,0 +1,55 @@
+mod utils;
+
+use std::{env, fs};
+
+use bytes::Bytes;
+use wdb_storage_engine::{restore_backup, FSPersistance, RowMutation, RowMutationOp, StorageEngine, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+fn put(row: &str, value: &str) -> RowMutation {
+    RowMutation {
+        table: Bytes::from("users"),
+        row: Bytes::from(row.to_string()),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("name"), timestamp: Some(Timestamp::from(1000)), value: Bytes::from(value.to_string()), visibility: None },
+        ]
+    }
+}
+
+#[test]
+fn backup_and_restore_test() {
+    let table_name = Bytes::from("users");
+    let root = env::temp_dir().join(format!("wdb-backup-test-{}", rand::random::<u64>()));
+    let backup_root = root.join("backups");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.add_family(storage_engine.get_persitance_layer(), Bytes::from("account"), Default::default()).unwrap();
+    drop(table);
+
+    storage_engine.execute_row_mutation(put("user1", "Anna"));
+    let full = storage_engine.create_backup(&backup_root, None).unwrap();
+    assert_eq!(full.parent, None);
+    assert_eq!(full.get_segments_count(), 1);
+    assert_eq!(full.tables[0].mvcc_read_point, 1);
+
+    storage_engine.execute_row_mutation(put("user2", "John"));
+    let incremental = storage_engine.create_backup(&backup_root, Some(&full.id)).unwrap();
+    assert_eq!(incremental.parent, Some(full.id.clone()));
+    assert_eq!(incremental.get_segments_count(), 2);
+    assert_eq!(incremental.tables[0].families[0].segments[0], full.tables[0].families[0].segments[0]);
+
+    let data_root = root.join("data");
+    restore_backup(&backup_root.join(&incremental.id), &data_root).unwrap();
+    assert!(restore_backup(&backup_root.join(&incremental.id), &data_root).is_err());
+
+    let restored = StorageEngine::empty(FSPersistance::with_base(&data_root), false);
+    let result = restored.read_row(table_name.clone(), Bytes::from("user1"), None);
+    assert_eq!(result.cells.len(), 1);
+    let result = restored.read_row(table_name.clone(), Bytes::from("user2"), None);
+    assert_eq!(result.cells.len(), 1);
+
+    fs::remove_dir_all(root).unwrap();
+}
\ No newline at end of file
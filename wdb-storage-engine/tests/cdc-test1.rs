@@ -0,0 +1,87 @@
+mod utils;
+
+use bytes::Bytes;
+use wdb_storage_engine::{ChangeFeedOptions, RowMutation, RowMutationOp, StorageEngine, TableOptions};
+
+use crate::utils::MemoryPersistance;
+
+fn put(row: &str, value: &str) -> RowMutation {
+    RowMutation {
+        table: Bytes::from("users"),
+        row: Bytes::from(row.to_string()),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: None, value: Bytes::from(value.to_string()), visibility: None },
+        ]
+    }
+}
+
+#[test]
+fn change_stream_test() {
+    let table_name = Bytes::from("users");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let mut table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.create_family(Bytes::from("")).unwrap();
+    drop(table);
+
+    storage_engine.execute_row_mutation(put("user1", "Anna"));
+    assert!(storage_engine.read_change_stream(table_name.clone(), 0, 100).is_err());
+
+    storage_engine.set_table_options(table_name.clone(), TableOptions { change_feed: Some(ChangeFeedOptions::default()), ..TableOptions::default() }).unwrap();
+    let mut rx = storage_engine.watch_change_stream(table_name.clone()).unwrap();
+
+    storage_engine.execute_row_mutation(put("user2", "John"));
+    storage_engine.execute_row_mutation(put("user3", "Kate"));
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(*rx.borrow_and_update(), 3);
+
+    let records = storage_engine.read_change_stream(table_name.clone(), 0, 100).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].write_num, 2);
+    assert_eq!(records[0].row, Bytes::from("user2"));
+    assert_eq!(records[1].row, Bytes::from("user3"));
+    match &records[1].ops[0] {
+        RowMutationOp::Put { timestamp, value, .. } => {
+            assert!(timestamp.is_some());
+            assert_eq!(value, &Bytes::from("Kate"));
+        },
+        _ => panic!("Unexpected operation"),
+    }
+
+    let records = storage_engine.read_change_stream(table_name.clone(), 2, 100).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].write_num, 3);
+    assert_eq!(storage_engine.read_change_stream(table_name.clone(), 3, 100).unwrap().len(), 0);
+    assert_eq!(storage_engine.read_change_stream(table_name.clone(), 0, 1).unwrap().len(), 1);
+
+    storage_engine.set_table_options(table_name.clone(), TableOptions { change_feed: None, ..TableOptions::default() }).unwrap();
+    assert_eq!(storage_engine.get_persitance_layer().get_change_logs_count(), 0);
+    assert!(storage_engine.read_change_stream(table_name.clone(), 0, 100).is_err());
+}
+
+#[test]
+fn change_stream_retention_test() {
+    let table_name = Bytes::from("users");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table_with_options(table_name.clone(), TableOptions { change_feed: Some(ChangeFeedOptions::default()), ..TableOptions::default() }).unwrap();
+    let mut table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.create_family(Bytes::from("")).unwrap();
+    drop(table);
+
+    // Every log rolls after 4MB.
+    let value = "a".repeat(1024 * 1024);
+    for i in 0..5 {
+        storage_engine.execute_row_mutation(put(&format!("user{}", i), &value));
+    }
+    assert_eq!(storage_engine.get_persitance_layer().get_change_logs_count(), 2);
+
+    storage_engine.set_table_options(table_name.clone(), TableOptions { change_feed: Some(ChangeFeedOptions { retention_ms: 1 }), ..TableOptions::default() }).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(10));
+
+    // The log being written to is kept.
+    storage_engine.prune_change_feeds();
+    assert_eq!(storage_engine.get_persitance_layer().get_change_logs_count(), 1);
+    assert_eq!(storage_engine.read_change_stream(table_name.clone(), 4, 100).unwrap().len(), 1);
+}
\ No newline at end of file
@@ -0,0 +1,94 @@
+mod utils;
+
+use bytes::Bytes;
+use wdb_storage_engine::{Authorizations, Cell, RowMutation, RowMutationOp, StorageEngine, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+#[test]
+fn compaction_test() {
+    let table_name = Bytes::from("users");
+    let family = Bytes::from("cf");
+    let timestamp = Some(Timestamp::from(1500000000));
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    storage_engine.get_table(table_name.clone()).unwrap().create_family(family.clone()).unwrap();
+
+    let put = |row: &'static str, value: &'static str| RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from(row),
+        ops: vec![RowMutationOp::Put { family: family.clone(), column: Bytes::from("name"), timestamp, value: Bytes::from(value), visibility: None }],
+    };
+
+    storage_engine.execute_row_mutation(put("user1", "John"));
+    storage_engine.flush_table(table_name.clone(), None).unwrap();
+    storage_engine.execute_row_mutation(put("user1", "Johnny"));
+    storage_engine.execute_row_mutation(put("user2", "Jan"));
+    storage_engine.flush_table(table_name.clone(), Some(family.clone())).unwrap();
+    storage_engine.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from("user2"),
+        ops: vec![RowMutationOp::DeleteColumn { family: family.clone(), column: Bytes::from("name"), timestamp: None }],
+    });
+    storage_engine.flush_table(table_name.clone(), None).unwrap();
+
+    let segments = storage_engine.list_segments(table_name.clone(), None).unwrap();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].1, 0);
+    assert_eq!(segments[0].2.len(), 3);
+    drop(segments);
+
+    // The first segment only holds user1, so it is left out and the tombstone has to stay.
+    let results = storage_engine.compact_range(table_name.clone(), None, Some(Bytes::from("user2")), Some(Bytes::from("user2"))).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].removed_segments.len(), 2);
+    assert_eq!((results[0].cells_read, results[0].cells_written), (3, 2));
+    assert_eq!(storage_engine.list_segments(table_name.clone(), None).unwrap()[0].2.len(), 2);
+
+    // A reader holding the old segments keeps them on disk until it is done.
+    let segments = storage_engine.list_segments(table_name.clone(), None).unwrap();
+    let results = storage_engine.compact_range(table_name.clone(), Some(family.clone()), None, None).unwrap();
+    assert_eq!((results[0].cells_read, results[0].cells_written), (3, 1));
+    assert_eq!(storage_engine.get_persitance_layer().get_segments_count(), 3);
+    drop(segments);
+    storage_engine.delete_retired_segments();
+
+    let segments = storage_engine.list_segments(table_name.clone(), Some(family.clone())).unwrap();
+    assert_eq!(segments[0].2.len(), 1);
+    assert_eq!(segments[0].2[0].get_segment(), results[0].added_segment.as_ref().unwrap());
+    assert_eq!(storage_engine.get_persitance_layer().get_segments_count(), 1);
+
+    let cells = storage_engine.read_rows(table_name.clone(), None, None, None, Authorizations::default()).unwrap();
+    assert_eq!(cells.len(), 1);
+    assert_eq!(cells[0].get_row(), b"user1");
+    assert_eq!(cells[0].get_value(), b"Johnny");
+
+    assert!(storage_engine.compact_range(table_name.clone(), Some(Bytes::from("missing")), None, None).is_err());
+}
+
+#[test]
+fn compaction_corrupt_block_test() {
+    let table_name = Bytes::from("users");
+    let family = Bytes::from("cf");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    storage_engine.get_table(table_name.clone()).unwrap().create_family(family.clone()).unwrap();
+
+    for value in ["John", "Johnny"] {
+        storage_engine.execute_row_mutation(RowMutation {
+            table: table_name.clone(),
+            row: Bytes::from("user1"),
+            ops: vec![RowMutationOp::Put { family: family.clone(), column: Bytes::from("name"), timestamp: None, value: Bytes::from(value), visibility: None }],
+        });
+        storage_engine.flush_table(table_name.clone(), None).unwrap();
+    }
+    storage_engine.get_persitance_layer().corrupt_segments();
+
+    // Corrupt blocks fail reads and compactions instead of panicking, and the segments stay in place.
+    assert!(storage_engine.read_rows(table_name.clone(), None, None, None, Authorizations::default()).is_err());
+    assert!(storage_engine.compact_range(table_name.clone(), None, None, None).is_err());
+    assert_eq!(storage_engine.list_segments(table_name.clone(), None).unwrap()[0].2.len(), 2);
+    assert_eq!(storage_engine.get_persitance_layer().get_segments_count(), 2);
+}
\ No newline at end of file
@@ -0,0 +1,62 @@
+mod utils;
+
+use bytes::Bytes;
+use wdb_storage_engine::{RowMutation, RowMutationOp, StorageEngine, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+#[test]
+fn drop_family_and_table_test() {
+    let table_name = Bytes::from("users");
+    let timestamp = Some(Timestamp::from(1500000000));
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let mut table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.create_family(Bytes::from("account")).unwrap();
+    table.create_family(Bytes::from("address")).unwrap();
+    drop(table);
+
+    storage_engine.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from("user1"),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("1234"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("New York"), visibility: None },
+        ]
+    });
+
+    {
+        let table = storage_engine.get_table(table_name.clone()).unwrap();
+        for family in table.get_families_iter() {
+            family.flush_memtable(&table_name, storage_engine.get_persitance_layer());
+            assert_eq!(family.get_segments_count(), 1);
+            assert!(family.get_disk_size() > 0);
+            assert_eq!(family.get_memtable_size(), 0);
+        }
+    }
+    assert_eq!(storage_engine.get_persitance_layer().get_segments_count(), 2);
+
+    {
+        let mut table = storage_engine.get_table(table_name.clone()).unwrap();
+        table.drop_family(storage_engine.get_persitance_layer(), Bytes::from("address")).unwrap();
+        assert!(table.get_family(&Bytes::from("address")).is_none());
+        assert!(table.drop_family(storage_engine.get_persitance_layer(), Bytes::from("address")).is_err());
+    }
+    assert_eq!(storage_engine.get_persitance_layer().get_segments_count(), 1);
+
+    let result = storage_engine.scan(table_name.clone(), None, None, None);
+    assert_eq!(result.len(), 1);
+
+    // The existence check shares the table, so it goes through while a reader holds it.
+    let reader = storage_engine.get_tables_iter().next().unwrap();
+    assert!(storage_engine.has_table(table_name.clone()));
+    assert!(!storage_engine.has_table(Bytes::from("missing")));
+    drop(reader);
+
+    storage_engine.drop_table(table_name.clone()).unwrap();
+    assert!(!storage_engine.has_table(table_name.clone()));
+    assert!(storage_engine.drop_table(table_name.clone()).is_err());
+    assert_eq!(storage_engine.get_persitance_layer().get_segments_count(), 0);
+}
\ No newline at end of file
@@ -0,0 +1,84 @@
+mod utils;
+
+use std::io::Cursor;
+
+use bytes::Bytes;
+use wdb_storage_engine::{Cell, RowMutation, RowMutationOp, StorageEngine, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+fn put(row: &str, ts: u64, value: &[u8]) -> RowMutation {
+    RowMutation {
+        table: Bytes::from("users"),
+        row: Bytes::from(row.to_string()),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("data"), timestamp: Some(Timestamp::from(ts)), value: Bytes::copy_from_slice(value), visibility: None },
+        ]
+    }
+}
+
+#[test]
+fn export_import_test() {
+    let table_name = Bytes::from("users");
+
+    let source = StorageEngine::empty(MemoryPersistance::new(), false);
+    source.create_table(table_name.clone()).unwrap();
+    let table = source.get_table(table_name.clone()).unwrap();
+    table.add_family(source.get_persitance_layer(), Bytes::from("account"), Default::default()).unwrap();
+    drop(table);
+
+    source.execute_row_mutation(put("user1", 100, b"\x00\xff"));
+    source.execute_row_mutation(put("user1", 200, b"second"));
+    source.execute_row_mutation(put("user2", 100, b"John"));
+    source.execute_row_mutation(put("user3", 100, b"Kate"));
+
+    let mut buf = vec![];
+    let count = source.export_table(table_name.clone(), None, None, &mut buf).unwrap();
+    assert_eq!(count, 4);
+    assert_eq!(String::from_utf8(buf.clone()).unwrap().lines().count(), 5);
+
+    let mut range_buf = vec![];
+    let count = source.export_table(table_name.clone(), Some(Bytes::from("user2")), Some(Bytes::from("user3")), &mut range_buf).unwrap();
+    assert_eq!(count, 1);
+
+    let target = StorageEngine::empty(MemoryPersistance::new(), false);
+    let count = target.import_table(Bytes::from("users_copy"), Cursor::new(buf)).unwrap();
+    assert_eq!(count, 4);
+    assert_eq!(target.get_persitance_layer().get_segments_count(), 1);
+
+    let result = target.read_row(Bytes::from("users_copy"), Bytes::from("user1"), None);
+    assert_eq!(result.cells.len(), 2);
+    let result = target.read_row(Bytes::from("users_copy"), Bytes::from("user3"), None);
+    assert_eq!(result.cells.len(), 1);
+
+    assert!(target.import_table(Bytes::from("broken"), Cursor::new(b"{}\n".to_vec())).is_err());
+}
+
+#[test]
+fn export_binary_keys_test() {
+    let table_name = Bytes::from("users");
+
+    let source = StorageEngine::empty(MemoryPersistance::new(), false);
+    source.create_table(table_name.clone()).unwrap();
+    let table = source.get_table(table_name.clone()).unwrap();
+    table.add_family(source.get_persitance_layer(), Bytes::from("account"), Default::default()).unwrap();
+    drop(table);
+
+    source.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from_static(b"user\xff\x00"),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from_static(b"\xc3\x28"), timestamp: Some(Timestamp::from(100)), value: Bytes::from("John"), visibility: Some(Bytes::from("admin")) },
+        ]
+    });
+
+    let mut buf = vec![];
+    assert_eq!(source.export_table(table_name.clone(), None, None, &mut buf).unwrap(), 1);
+
+    let target = StorageEngine::empty(MemoryPersistance::new(), false);
+    assert_eq!(target.import_table(table_name.clone(), Cursor::new(buf)).unwrap(), 1);
+    let result = target.read_row(table_name.clone(), Bytes::from_static(b"user\xff\x00"), None);
+    assert_eq!(result.cells.len(), 1);
+    assert_eq!(result.cells[0].get_col(), b"\xc3\x28");
+    assert_eq!(result.cells[0].get_visibility(), b"admin");
+}
\ No newline at end of file
@@ -1,7 +1,9 @@
 mod utils;
 
+use std::{sync::atomic::{AtomicBool, Ordering}, thread};
+
 use bytes::Bytes;
-use wdb_storage_engine::{RowMutation, RowMutationOp, StorageEngine, Timestamp};
+use wdb_storage_engine::{Authorizations, RowMutation, RowMutationOp, StorageEngine, Timestamp};
 
 use crate::utils::MemoryPersistance;
 
@@ -23,14 +25,14 @@ fn flush_scan_test() {
         table: table_name.clone(),
         row: Bytes::from("user1"),
         ops: vec![
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("John") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Doe") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("john@example.com") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("1234") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("5") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("USA") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("New York") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Wall Street") },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("John"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Doe"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("john@example.com"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("1234"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("5"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("USA"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("New York"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Wall Street"), visibility: None },
         ]
     });
 
@@ -38,14 +40,14 @@ fn flush_scan_test() {
         table: table_name.clone(),
         row: Bytes::from("user2"),
         ops: vec![
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("Jan") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Kowalski") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("jan@example.com") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("250") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("10") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("Poland") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("Warsaw") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Marszalkowska") },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("Jan"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Kowalski"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("jan@example.com"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("250"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("10"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("Poland"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("Warsaw"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Marszalkowska"), visibility: None },
         ]
     });
 
@@ -53,20 +55,20 @@ fn flush_scan_test() {
         table: table_name.clone(),
         row: Bytes::from("user3"),
         ops: vec![
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("Jane") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Smith") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("jane@example.com") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("999") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("20") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("UK") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("London") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Abbey Road") },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("Jane"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Smith"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("jane@example.com"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("999"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("20"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("UK"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("London"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Abbey Road"), visibility: None },
         ]
     });
 
     {
         let mut table = storage_engine.get_table(table_name.clone()).unwrap();
-        table.flush_memtable();
+        table.flush_memtable(storage_engine.get_persitance_layer());
     }
 
     println!("-- FULL SCAN --");
@@ -92,9 +94,9 @@ fn flush_scan_test() {
     //     table: table_name.clone(),
     //     row: Bytes::from("user3"),
     //     ops: vec![
-    //         RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 10)), value: Bytes::from("22") },
-    //         RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 20)), value: Bytes::from("18") },
-    //         RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 30)), value: Bytes::from("10") },
+    //         RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 10)), value: Bytes::from("22"), visibility: None },
+    //         RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 20)), value: Bytes::from("18"), visibility: None },
+    //         RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 30)), value: Bytes::from("10"), visibility: None },
     //     ]
     // });
 
@@ -126,4 +128,49 @@ fn flush_scan_test() {
     // println!("-- FULL SCAN --");
     // let result = storage_engine.scan(table_name.clone(), None, None, None);
     // println!("{:?}", result);
+}
+
+#[test]
+fn concurrent_write_flush_test() {
+    let table_name = Bytes::from("users");
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    storage_engine.get_table(table_name.clone()).unwrap().create_family(Bytes::from("cf")).unwrap();
+
+    // Flushing through the shared table, like the flush agent does, every write has to end up in the memtable or in a flushed segment.
+    let done = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let writers = (0..4).map(|i| {
+            let storage_engine = &storage_engine;
+            scope.spawn(move || {
+                for j in 0..500 {
+                    storage_engine.execute_row_mutation(RowMutation {
+                        table: Bytes::from("users"),
+                        row: Bytes::from(format!("user{}-{}", i, j)),
+                        ops: vec![RowMutationOp::Put { family: Bytes::from("cf"), column: Bytes::from("name"), timestamp: None, value: Bytes::from("John"), visibility: None }],
+                    });
+                }
+            })
+        }).collect::<Vec<_>>();
+
+        let (storage_engine, done) = (&storage_engine, &done);
+        scope.spawn(move || {
+            while !done.load(Ordering::SeqCst) {
+                for table in storage_engine.get_tables_iter() {
+                    for family in table.get_families_iter() {
+                        family.flush_memtable(&table.get_name(), storage_engine.get_persitance_layer());
+                    }
+                }
+            }
+        });
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        done.store(true, Ordering::SeqCst);
+    });
+    storage_engine.flush_table(table_name.clone(), None).unwrap();
+
+    let cells = storage_engine.read_rows(table_name.clone(), None, None, None, Authorizations::default()).unwrap();
+    assert_eq!(cells.len(), 4 * 500);
 }
\ No newline at end of file
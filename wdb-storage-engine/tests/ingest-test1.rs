@@ -0,0 +1,74 @@
+mod utils;
+
+use std::io::Cursor;
+
+use bytes::Bytes;
+use wdb_storage_engine::{Cell, RowMutation, RowMutationOp, SSTableBuilder, StorageEngine, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+fn build_sstable(family: &str, rows: &[&str]) -> Cursor<Vec<u8>> {
+    let mut buf = vec![];
+    let mut builder = SSTableBuilder::new(&mut buf, Bytes::from(family.to_string()));
+    for row in rows {
+        builder.put(&Bytes::from(row.to_string()), &Bytes::from("name"), Timestamp::from(100), &Bytes::from("ingested")).unwrap();
+    }
+    builder.finish().unwrap();
+    Cursor::new(buf)
+}
+
+#[test]
+fn ingest_sstables_test() {
+    let table_name = Bytes::from("users");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.add_family(storage_engine.get_persitance_layer(), Bytes::from("account"), Default::default()).unwrap();
+    drop(table);
+
+    let mut buf = vec![];
+    let mut builder = SSTableBuilder::new(&mut buf, Bytes::from("account"));
+    builder.put(&Bytes::from("user2"), &Bytes::from("name"), Timestamp::from(100), &Bytes::from("John")).unwrap();
+    assert!(builder.put(&Bytes::from("user1"), &Bytes::from("name"), Timestamp::from(100), &Bytes::from("Anna")).is_err());
+
+    let sstables = storage_engine.ingest_sstables(table_name.clone(), Bytes::from("account"), vec![
+        build_sstable("account", &["user1", "user2"]),
+        build_sstable("account", &["user3"]),
+    ]).unwrap();
+    assert_eq!(sstables.len(), 2);
+    assert_eq!(sstables[0].get_max_mvcc_id(), 1);
+    assert_eq!(sstables[1].get_max_mvcc_id(), 2);
+    assert_eq!(storage_engine.get_persitance_layer().get_segments_count(), 2);
+
+    let result = storage_engine.read_row(table_name.clone(), Bytes::from("user3"), None);
+    assert_eq!(result.cells.len(), 1);
+
+    storage_engine.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from("user1"),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("name"), timestamp: Some(Timestamp::from(100)), value: Bytes::from("updated"), visibility: None },
+        ]
+    });
+    let result = storage_engine.read_row(table_name.clone(), Bytes::from("user1"), None);
+    assert_eq!(result.cells.len(), 1);
+    assert_eq!(result.cells[0].get_value(), b"updated");
+
+    let res = storage_engine.ingest_sstables(table_name.clone(), Bytes::from("account"), vec![
+        build_sstable("account", &["user4"]),
+        build_sstable("address", &["user5"]),
+    ]);
+    assert!(res.is_err());
+    let res = storage_engine.ingest_sstables(table_name.clone(), Bytes::from("account"), vec![Cursor::new(b"not an sstable".to_vec())]);
+    assert!(res.is_err());
+
+    // Value length of the first cell overflows when added to the key length.
+    let mut corrupted = build_sstable("account", &["user6"]).into_inner();
+    corrupted[2..10].copy_from_slice(&u64::MAX.to_be_bytes());
+    let res = storage_engine.ingest_sstables(table_name.clone(), Bytes::from("account"), vec![Cursor::new(corrupted)]);
+    assert!(res.is_err());
+
+    assert_eq!(storage_engine.get_persitance_layer().get_segments_count(), 2);
+    assert_eq!(storage_engine.read_row(table_name.clone(), Bytes::from("user4"), None).cells.len(), 0);
+}
\ No newline at end of file
@@ -0,0 +1,190 @@
+mod utils;
+
+use bytes::Bytes;
+use wdb_storage_engine::{Authorizations, BloomFilterOptions, Cell, Compression, FamilyModification, FamilyOptions, GcPolicy, PersistanceLayer, RowMutation, RowMutationOp, SSTable, SSTableInspector, StorageEngine, TimeRange, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+#[test]
+fn modify_families_test() {
+    let table_name = Bytes::from("users");
+    let timestamp = Some(Timestamp::from(1500000000));
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+
+    storage_engine.modify_column_families(table_name.clone(), vec![
+        FamilyModification::Create { family: Bytes::from("account"), options: FamilyOptions::default() },
+        FamilyModification::Create { family: Bytes::from("address"), options: FamilyOptions::default() },
+    ]).unwrap();
+
+    storage_engine.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from("user1"),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("1234"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("New York"), visibility: None },
+        ]
+    });
+
+    let options = FamilyOptions {
+        gc_policy: GcPolicy { max_versions: Some(1), max_age_ms: None },
+        ..FamilyOptions::default()
+    };
+    storage_engine.modify_column_families(table_name.clone(), vec![
+        FamilyModification::Update { family: Bytes::from("account"), options: options.clone() },
+        FamilyModification::Drop { family: Bytes::from("address") },
+    ]).unwrap();
+
+    {
+        let table = storage_engine.get_table(table_name.clone()).unwrap();
+        assert_eq!(*table.get_family(&Bytes::from("account")).unwrap().get_options(), options);
+        assert!(table.get_family(&Bytes::from("address")).is_none());
+    }
+
+    let result = storage_engine.read_row(table_name.clone(), Bytes::from("user1"), None);
+    assert_eq!(result.cells.len(), 1);
+
+    assert!(storage_engine.modify_column_families(table_name.clone(), vec![
+        FamilyModification::Create { family: Bytes::from("account"), options: FamilyOptions::default() },
+    ]).is_err());
+    assert!(storage_engine.modify_column_families(table_name.clone(), vec![
+        FamilyModification::Update { family: Bytes::from("address"), options: FamilyOptions::default() },
+    ]).is_err());
+
+    // A failing modification leaves the earlier ones of the request unapplied.
+    assert!(storage_engine.modify_column_families(table_name.clone(), vec![
+        FamilyModification::Create { family: Bytes::from("history"), options: FamilyOptions::default() },
+        FamilyModification::Drop { family: Bytes::from("address") },
+    ]).is_err());
+    assert!(storage_engine.get_table(table_name.clone()).unwrap().get_family(&Bytes::from("history")).is_none());
+
+    // Modifications share the table, so they go through while a reader holds it.
+    let reader = storage_engine.get_tables_iter().next().unwrap();
+    storage_engine.modify_column_families(table_name.clone(), vec![
+        FamilyModification::Create { family: Bytes::from("history"), options: FamilyOptions::default() },
+    ]).unwrap();
+    assert!(reader.get_family(&Bytes::from("history")).is_some());
+}
+
+#[test]
+fn family_gc_policy_test() {
+    let table_name = Bytes::from("users");
+    let now: u64 = Timestamp::ensure_timestamp(None).into();
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    storage_engine.modify_column_families(table_name.clone(), vec![
+        FamilyModification::Create { family: Bytes::from("account"), options: FamilyOptions { gc_policy: GcPolicy { max_versions: Some(2), max_age_ms: None }, ..FamilyOptions::default() } },
+        FamilyModification::Create { family: Bytes::from("events"), options: FamilyOptions { gc_policy: GcPolicy { max_versions: None, max_age_ms: Some(60000) }, ..FamilyOptions::default() } },
+    ]).unwrap();
+
+    for (i, ts) in [now - 3000, now - 2000, now - 1000].into_iter().enumerate() {
+        storage_engine.execute_row_mutation(RowMutation {
+            table: table_name.clone(),
+            row: Bytes::from("user1"),
+            ops: vec![
+                RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: Some(Timestamp::from(ts)), value: Bytes::from(i.to_string()), visibility: None },
+            ]
+        });
+    }
+    storage_engine.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from("user1"),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("events"), column: Bytes::from("login"), timestamp: Some(Timestamp::from(now - 120000)), value: Bytes::from("old"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("events"), column: Bytes::from("logout"), timestamp: Some(Timestamp::from(now)), value: Bytes::from("new"), visibility: None },
+        ]
+    });
+
+    let values = |storage_engine: &StorageEngine<MemoryPersistance>| {
+        storage_engine.read_row(table_name.clone(), Bytes::from("user1"), None).cells.iter()
+            .map(|cell| Bytes::copy_from_slice(cell.get_value()))
+            .collect::<Vec<Bytes>>()
+    };
+    assert_eq!(values(&storage_engine), vec![Bytes::from("2"), Bytes::from("1"), Bytes::from("new")]);
+
+    storage_engine.flush_table(table_name.clone(), None).unwrap();
+    let results = storage_engine.compact_range(table_name.clone(), None, None, None).unwrap();
+    assert_eq!(results.iter().map(|result| result.cells_written).sum::<u64>(), 3);
+    assert_eq!(values(&storage_engine), vec![Bytes::from("2"), Bytes::from("1"), Bytes::from("new")]);
+
+    // A newer version flushed into its own segment still collects a version in an older time range.
+    storage_engine.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from("user1"),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: Some(Timestamp::from(now - 500)), value: Bytes::from("3"), visibility: None },
+        ]
+    });
+    storage_engine.flush_table(table_name.clone(), None).unwrap();
+    let time_range = TimeRange::new(Timestamp::from(now - 2500), Timestamp::from(now - 1500));
+    assert!(storage_engine.read_rows(table_name.clone(), None, None, Some(time_range), Authorizations::default()).unwrap().is_empty());
+}
+
+#[test]
+fn family_compression_bloom_filter_test() {
+    let table_name = Bytes::from("users");
+    let families = [("lz4", Compression::Lz4), ("zstd", Compression::Zstd)];
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    storage_engine.modify_column_families(table_name.clone(), families.iter().map(|(family, compression)| {
+        let options = FamilyOptions {
+            compression: *compression,
+            bloom_filter: BloomFilterOptions { enabled: true, bits_per_key: 10 },
+            ..FamilyOptions::default()
+        };
+        FamilyModification::Create { family: Bytes::from(*family), options }
+    }).collect()).unwrap();
+
+    // Enough rows for several data blocks.
+    for i in (0..2000).step_by(2) {
+        storage_engine.execute_row_mutation(RowMutation {
+            table: table_name.clone(),
+            row: Bytes::from(format!("user{:04}", i)),
+            ops: families.iter().map(|(family, _)| RowMutationOp::Put {
+                family: Bytes::from(*family), column: Bytes::from("bio"), timestamp: Some(Timestamp::from(100)), value: Bytes::from("lorem ipsum ".repeat(20)), visibility: None,
+            }).collect(),
+        });
+    }
+    storage_engine.flush_table(table_name.clone(), None).unwrap();
+
+    let persistance = storage_engine.get_persitance_layer();
+    {
+        let table = storage_engine.get_table(table_name.clone()).unwrap();
+        for (family, compression) in families.iter() {
+            let family = table.get_family(&Bytes::from(*family)).unwrap();
+            let segments = family.get_segments();
+            let stats = segments[0].get_stats().unwrap();
+            assert!(stats.disk_bytes < stats.raw_bytes / 4);
+
+            let segment_read = persistance.get_segment_read(&table_name, segments[0].get_family(), segments[0].get_segment());
+            let mut inspector = SSTableInspector::open(segment_read).unwrap();
+            assert_eq!(inspector.get_footer().compression, *compression);
+            assert!(inspector.get_footer().bloom_len.is_some());
+            assert!(inspector.get_blocks().len() > 1);
+            let report = inspector.verify();
+            assert!(report.errors.is_empty());
+            assert_eq!(report.cells, 1000);
+
+            // Reopened segments load their bloom filter from the file.
+            let segment_read = persistance.get_segment_read(&table_name, segments[0].get_family(), segments[0].get_segment());
+            let sstable = SSTable::read(&table_name, segments[0].get_family(), segments[0].get_segment(), segment_read);
+            assert!(sstable.may_contain_row(b"user1234"));
+            assert!((1..2000).step_by(2).filter(|i| !sstable.may_contain_row(format!("user{:04}", i).as_bytes())).count() > 900);
+        }
+    }
+
+    let result = storage_engine.read_row(table_name.clone(), Bytes::from("user1234"), None);
+    assert_eq!(result.cells.len(), 2);
+    assert_eq!(storage_engine.read_rows(table_name.clone(), None, None, None, Authorizations::default()).unwrap().len(), 2000);
+
+    // Rows inside the segment bounds but missing from it skip the segment, up to the false positive rate.
+    let reads = persistance.get_reads_count();
+    for i in (1..2000).step_by(2) {
+        let result = storage_engine.read_row(table_name.clone(), Bytes::from(format!("user{:04}", i)), None);
+        assert!(result.cells.is_empty());
+    }
+    assert!(persistance.get_reads_count() - reads < 100);
+}
\ No newline at end of file
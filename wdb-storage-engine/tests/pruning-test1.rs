@@ -0,0 +1,77 @@
+mod utils;
+
+use bytes::Bytes;
+use wdb_storage_engine::{Cell, RowMutation, RowMutationOp, StorageEngine, TimeRange, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+fn put(row: &str, ts: u64) -> RowMutation {
+    RowMutation {
+        table: Bytes::from("users"),
+        row: Bytes::from(row.to_string()),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: Some(Timestamp::from(ts)), value: Bytes::from("John"), visibility: None },
+        ]
+    }
+}
+
+fn flush(storage_engine: &StorageEngine<MemoryPersistance>) {
+    let table_name = Bytes::from("users");
+    let table = storage_engine.get_table(table_name.clone()).unwrap();
+    for family in table.get_families_iter() {
+        family.flush_memtable(&table_name, storage_engine.get_persitance_layer());
+    }
+}
+
+#[test]
+fn segment_pruning_test() {
+    let table_name = Bytes::from("users");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let mut table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.create_family(Bytes::from("")).unwrap();
+    drop(table);
+
+    for row in ["user1", "user2", "user3"] {
+        storage_engine.execute_row_mutation(put(row, 100));
+    }
+    flush(&storage_engine);
+
+    for row in ["user7", "user8", "user9"] {
+        storage_engine.execute_row_mutation(put(row, 1000));
+    }
+    flush(&storage_engine);
+
+    let persistance = storage_engine.get_persitance_layer();
+
+    let reads = persistance.get_reads_count();
+    let result = storage_engine.read_row(table_name.clone(), Bytes::from("user2"), None);
+    assert_eq!(result.cells.len(), 1);
+    assert_eq!(persistance.get_reads_count() - reads, 1);
+
+    let reads = persistance.get_reads_count();
+    let result = storage_engine.read_row(table_name.clone(), Bytes::from("user5"), None);
+    assert_eq!(result.cells.len(), 0);
+    assert_eq!(persistance.get_reads_count() - reads, 0);
+
+    let reads = persistance.get_reads_count();
+    let result = storage_engine.scan_time_range(table_name.clone(), None, None, TimeRange::new(Timestamp::from(900), Timestamp::from(1100)));
+    assert_eq!(result.len(), 3);
+    assert!(result.iter().all(|cell| cell.get_timestamp() == Timestamp::from(1000)));
+    assert_eq!(persistance.get_reads_count() - reads, 1);
+
+    storage_engine.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from("user8"),
+        ops: vec![
+            RowMutationOp::DeleteColumn { family: Bytes::from(""), column: Bytes::from("name"), timestamp: Some(Timestamp::from(5000)) },
+        ]
+    });
+    flush(&storage_engine);
+
+    let reads = persistance.get_reads_count();
+    let result = storage_engine.scan_time_range(table_name.clone(), None, None, TimeRange::new(Timestamp::from(900), Timestamp::from(1100)));
+    assert_eq!(result.len(), 2);
+    assert_eq!(persistance.get_reads_count() - reads, 2);
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+mod utils;
+
+use std::{collections::HashSet, sync::atomic::{AtomicBool, Ordering}, thread};
+
+use bytes::Bytes;
+use wdb_storage_engine::{Authorizations, Cell, RowMutation, RowMutationOp, StorageEngine};
+
+use crate::utils::MemoryPersistance;
+
+fn put(row: String, column: String) -> RowMutation {
+    RowMutation {
+        table: Bytes::from("users"),
+        row: Bytes::from(row),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("cf"), column: Bytes::from(column), timestamp: None, value: Bytes::from("value"), visibility: None },
+        ]
+    }
+}
+
+#[test]
+fn row_lock_test() {
+    let table_name = Bytes::from("users");
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    storage_engine.get_table(table_name.clone()).unwrap().create_family(Bytes::from("cf")).unwrap();
+
+    thread::scope(|scope| {
+        for i in 0..8 {
+            let storage_engine = &storage_engine;
+            scope.spawn(move || {
+                for j in 0..50 {
+                    storage_engine.execute_row_mutation(put(format!("user{}", i % 4), format!("col{}", j)));
+                }
+            });
+        }
+    });
+
+    let cells = storage_engine.read_rows(table_name.clone(), None, None, None, Authorizations::default()).unwrap();
+    assert_eq!(cells.iter().map(|cell| cell.get_row().to_vec()).collect::<HashSet<_>>().len(), 4);
+
+    // Locks of finished writers are reclaimed.
+    let table = storage_engine.get_table(table_name.clone()).unwrap();
+    assert_eq!(table.get_row_locks_count(), 0);
+
+    // Overlapping rows requested in different orders are locked in key order, so none of these deadlock.
+    let busy = AtomicBool::new(false);
+    let (a, b, c) = (Bytes::from("a"), Bytes::from("b"), Bytes::from("c"));
+    thread::scope(|scope| {
+        for rows in [vec![a.clone(), b.clone()], vec![b.clone(), a.clone()], vec![b.clone(), c.clone(), a.clone(), b.clone()]] {
+            let (table, busy) = (&table, &busy);
+            scope.spawn(move || {
+                for _ in 0..200 {
+                    let _rows_lock = table.lock_rows(&rows);
+                    assert!(!busy.swap(true, Ordering::SeqCst));
+                    busy.store(false, Ordering::SeqCst);
+                }
+            });
+        }
+    });
+
+    // A single guard holds every distinct row and releases them all at once.
+    let rows_lock = table.lock_rows(&[b.clone(), c.clone(), a.clone(), b.clone()]);
+    assert_eq!(rows_lock.get_rows_count(), 3);
+    assert_eq!(table.get_row_locks_count(), 3);
+    drop(rows_lock);
+    assert_eq!(table.get_row_locks_count(), 0);
+}
\ No newline at end of file
@@ -0,0 +1,49 @@
+mod utils;
+
+use bytes::Bytes;
+use wdb_storage_engine::{RowMutation, RowMutationOp, StorageEngine, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+#[test]
+fn sample_row_keys_test() {
+    let table_name = Bytes::from("users");
+    let timestamp = Some(Timestamp::from(1500000000));
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let mut table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.create_family(Bytes::from("")).unwrap();
+    table.create_family(Bytes::from("account")).unwrap();
+    drop(table);
+
+    for i in 0..200 {
+        storage_engine.execute_row_mutation(RowMutation {
+            table: table_name.clone(),
+            row: Bytes::from(format!("user{:04}", i)),
+            ops: vec![
+                RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("avatar"), timestamp: timestamp, value: Bytes::from(vec![0u8; 4096]), visibility: None },
+                RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("1234"), visibility: None },
+            ]
+        });
+    }
+
+    assert!(storage_engine.sample_row_keys(table_name.clone(), 256 * 1024).unwrap().is_empty());
+
+    {
+        let table = storage_engine.get_table(table_name.clone()).unwrap();
+        for family in table.get_families_iter() {
+            family.flush_memtable(&table_name, storage_engine.get_persitance_layer());
+        }
+    }
+
+    let samples = storage_engine.sample_row_keys(table_name.clone(), 256 * 1024).unwrap();
+    assert!(samples.len() >= 2);
+    for pair in samples.windows(2) {
+        assert!(pair[0].0 < pair[1].0);
+        assert!(pair[1].1 - pair[0].1 >= 256 * 1024);
+    }
+
+    assert!(storage_engine.sample_row_keys(Bytes::from("missing"), 1024).is_err());
+}
\ No newline at end of file
@@ -23,14 +23,14 @@ fn in_memory_test() {
         table: table_name.clone(),
         row: Bytes::from("user1"),
         ops: vec![
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("John") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Doe") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("john@example.com") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("1234") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("5") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("USA") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("New York") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Wall Street") },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("John"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Doe"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("john@example.com"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("1234"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("5"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("USA"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("New York"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Wall Street"), visibility: None },
         ]
     });
 
@@ -38,14 +38,14 @@ fn in_memory_test() {
         table: table_name.clone(),
         row: Bytes::from("user2"),
         ops: vec![
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("Jan") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Kowalski") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("jan@example.com") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("250") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("10") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("Poland") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("Warsaw") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Marszalkowska") },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("Jan"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Kowalski"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("jan@example.com"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("250"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("10"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("Poland"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("Warsaw"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Marszalkowska"), visibility: None },
         ]
     });
 
@@ -53,14 +53,14 @@ fn in_memory_test() {
         table: table_name.clone(),
         row: Bytes::from("user3"),
         ops: vec![
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("Jane") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Smith") },
-            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("jane@example.com") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("999") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("20") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("UK") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("London") },
-            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Abbey Road") },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: timestamp, value: Bytes::from("Jane"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("surname"), timestamp: timestamp, value: Bytes::from("Smith"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("email"), timestamp: timestamp, value: Bytes::from("jane@example.com"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: timestamp, value: Bytes::from("999"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: timestamp, value: Bytes::from("20"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("country"), timestamp: timestamp, value: Bytes::from("UK"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("city"), timestamp: timestamp, value: Bytes::from("London"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("address"), column: Bytes::from("street"), timestamp: timestamp, value: Bytes::from("Abbey Road"), visibility: None },
         ]
     });
     
@@ -83,9 +83,9 @@ fn in_memory_test() {
         table: table_name.clone(),
         row: Bytes::from("user3"),
         ops: vec![
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 10)), value: Bytes::from("22") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 20)), value: Bytes::from("18") },
-            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 30)), value: Bytes::from("10") },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 10)), value: Bytes::from("22"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 20)), value: Bytes::from("18"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("tokens"), timestamp: Some(Timestamp::from(1500000000 + 30)), value: Bytes::from("10"), visibility: None },
         ]
     });
 
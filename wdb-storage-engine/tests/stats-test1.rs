@@ -0,0 +1,68 @@
+mod utils;
+
+use bytes::Bytes;
+use wdb_storage_engine::{PersistanceLayer, RowMutation, RowMutationOp, SSTable, StorageEngine, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+#[test]
+fn flush_stats_test() {
+    let table_name = Bytes::from("users");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let mut table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.create_family(Bytes::from("")).unwrap();
+    table.create_family(Bytes::from("account")).unwrap();
+    drop(table);
+
+    storage_engine.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from("user1"),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: Some(Timestamp::from(100)), value: Bytes::from("John"), visibility: None },
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from("saldo"), timestamp: Some(Timestamp::from(200)), value: Bytes::from("1234"), visibility: None },
+        ]
+    });
+    storage_engine.execute_row_mutation(RowMutation {
+        table: table_name.clone(),
+        row: Bytes::from("user2"),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from(""), column: Bytes::from("name"), timestamp: Some(Timestamp::from(300)), value: Bytes::from("Jan"), visibility: None },
+            RowMutationOp::DeleteColumn { family: Bytes::from(""), column: Bytes::from("email"), timestamp: Some(Timestamp::from(400)) },
+            RowMutationOp::DeleteFamily { family: Bytes::from("account"), timestamp: Some(Timestamp::from(500)) },
+        ]
+    });
+
+    {
+        let table = storage_engine.get_table(table_name.clone()).unwrap();
+        for family in table.get_families_iter() {
+            family.flush_memtable(&table_name, storage_engine.get_persitance_layer());
+        }
+    }
+
+    let (stats, families_stats) = storage_engine.get_table_stats(table_name.clone()).unwrap();
+    assert_eq!(stats.cell_count, 5);
+    assert_eq!(stats.put_count, 3);
+    assert_eq!(stats.delete_column_count, 1);
+    assert_eq!(stats.delete_family_count, 1);
+    assert_eq!(stats.get_tombstone_count(), 2);
+    assert_eq!(stats.min_timestamp, Some(100));
+    assert_eq!(stats.max_timestamp, Some(500));
+    assert_eq!(stats.min_row, Some(Bytes::from("user1")));
+    assert_eq!(stats.max_row, Some(Bytes::from("user2")));
+    assert_eq!(families_stats.len(), 2);
+
+    let table = storage_engine.get_table(table_name.clone()).unwrap();
+    let family = table.get_family(&Bytes::from("")).unwrap();
+    assert_eq!(family.get_stats().row_count, 2);
+    assert_eq!(family.get_stats().disk_bytes, family.get_disk_size());
+
+    for sstable in family.get_segments().iter() {
+        let r = storage_engine.get_persitance_layer().get_segment_read(&table_name, sstable.get_family(), sstable.get_segment());
+        let read = SSTable::read(&table_name, sstable.get_family(), sstable.get_segment(), r);
+        assert_eq!(read.get_stats(), sstable.get_stats());
+        assert_eq!(read.get_size(), sstable.get_size());
+    }
+}
\ No newline at end of file
@@ -1,23 +1,169 @@
-use std::{collections::HashMap, io::{Cursor, Write}};
+use std::{collections::HashMap, fs, io::{Cursor, Read, Seek, Write}, path::Path, sync::{atomic::{AtomicUsize, Ordering}, Arc, Mutex}};
 
 use bytes::Bytes;
-use wdb_storage_engine::PersistanceLayer;
+use wdb_storage_engine::{Acl, ChangeLogWrite, FamilyOptions, PersistanceLayer, SSTable, TableOptions};
+
+type SegmentKey = (Bytes, Bytes, Bytes);
+type ChangeLogKey = (Bytes, Bytes);
 
 pub struct MemoryPersistance {
-    sstable_files: HashMap<Bytes, Cursor<Vec<u8>>>
+    sstable_files: Arc<Mutex<HashMap<SegmentKey, Vec<u8>>>>,
+    change_logs: Arc<Mutex<HashMap<ChangeLogKey, Vec<u8>>>>,
+    catalog_acl: Mutex<Option<Acl>>,
+    reads: AtomicUsize,
 }
 
 impl MemoryPersistance {
     pub fn new() -> MemoryPersistance {
         MemoryPersistance {
-            sstable_files: HashMap::new()
+            sstable_files: Arc::new(Mutex::new(HashMap::new())),
+            change_logs: Arc::new(Mutex::new(HashMap::new())),
+            catalog_acl: Mutex::new(None),
+            reads: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn get_segments_count(&self) -> usize {
+        self.sstable_files.lock().unwrap().len()
+    }
+
+    pub fn get_reads_count(&self) -> usize {
+        self.reads.load(Ordering::Relaxed)
+    }
+
+    // Flips the first byte of every segment, which falls into its first data block.
+    pub fn corrupt_segments(&self) {
+        for file in self.sstable_files.lock().unwrap().values_mut() {
+            file[0] ^= 0xFF;
         }
     }
+
+    pub fn get_change_logs_count(&self) -> usize {
+        self.change_logs.lock().unwrap().len()
+    }
+}
+
+pub struct MemorySegmentWrite {
+    key: SegmentKey,
+    buf: Vec<u8>,
+    sstable_files: Arc<Mutex<HashMap<SegmentKey, Vec<u8>>>>,
+}
+
+impl Write for MemorySegmentWrite {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.sstable_files.lock().unwrap().insert(self.key.clone(), self.buf.clone());
+        Ok(())
+    }
+}
+
+pub struct MemoryChangeLogWrite {
+    key: ChangeLogKey,
+    change_logs: Arc<Mutex<HashMap<ChangeLogKey, Vec<u8>>>>,
+}
+
+impl Write for MemoryChangeLogWrite {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.change_logs.lock().unwrap().entry(self.key.clone()).or_default().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl ChangeLogWrite for MemoryChangeLogWrite {
+    fn sync(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 impl PersistanceLayer for MemoryPersistance {
-    fn get_sstable_write(&mut self, name: Bytes) -> &mut impl Write {
-        let c = self.sstable_files.entry(name.clone()).or_insert_with(|| Cursor::new(Vec::new()));
-        c
+    fn get_segment_write(&self, table: &Bytes, family: Bytes, segment: &Bytes) -> impl Write {
+        MemorySegmentWrite {
+            key: (table.clone(), family, segment.clone()),
+            buf: vec![],
+            sstable_files: self.sstable_files.clone(),
+        }
+    }
+
+    fn get_segment_read(&self, table: &Bytes, family: &Bytes, segment: &Bytes) -> impl Read + Seek {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        let files = self.sstable_files.lock().unwrap();
+        Cursor::new(files.get(&(table.clone(), family.clone(), segment.clone())).unwrap().clone())
+    }
+
+    fn commit_segment(&self, table: &Bytes, family: &Bytes, tmp_segment: &Bytes, segment: &Bytes) -> std::io::Result<()> {
+        let mut files = self.sstable_files.lock().unwrap();
+        let file = files.remove(&(table.clone(), family.clone(), tmp_segment.clone())).unwrap();
+        files.insert((table.clone(), family.clone(), segment.clone()), file);
+        Ok(())
+    }
+
+    fn link_segment(&self, table: &Bytes, family: &Bytes, segment: &Bytes, dest: &Path) -> std::io::Result<()> {
+        let files = self.sstable_files.lock().unwrap();
+        fs::create_dir_all(dest.parent().unwrap())?;
+        fs::write(dest, files.get(&(table.clone(), family.clone(), segment.clone())).unwrap())
+    }
+
+    fn get_tables_list(&self) -> Vec<(Bytes, u64, TableOptions, Vec<(Bytes, FamilyOptions, Vec<SSTable>)>)> {
+        vec![]
+    }
+
+    fn write_table_options(&self, _table: &Bytes, _options: &TableOptions) {}
+
+    fn write_family_options(&self, _table: &Bytes, _family: &Bytes, _options: &FamilyOptions) {}
+
+    fn get_catalog_acl(&self) -> Option<Acl> {
+        self.catalog_acl.lock().unwrap().clone()
+    }
+
+    fn write_catalog_acl(&self, acl: Option<&Acl>) {
+        *self.catalog_acl.lock().unwrap() = acl.cloned();
+    }
+
+    fn delete_segment(&self, table: &Bytes, family: &Bytes, segment: &Bytes) {
+        self.sstable_files.lock().unwrap().remove(&(table.clone(), family.clone(), segment.clone()));
+    }
+
+    fn delete_family(&self, table: &Bytes, family: &Bytes) {
+        self.sstable_files.lock().unwrap().retain(|key, _| !(key.0 == table && key.1 == family));
+    }
+
+    fn delete_table(&self, table: &Bytes) {
+        self.sstable_files.lock().unwrap().retain(|key, _| key.0 != table);
+    }
+
+    fn get_change_log_write(&self, table: &Bytes, log: &Bytes) -> impl ChangeLogWrite + Send + 'static {
+        self.change_logs.lock().unwrap().entry((table.clone(), log.clone())).or_default();
+        MemoryChangeLogWrite {
+            key: (table.clone(), log.clone()),
+            change_logs: self.change_logs.clone(),
+        }
+    }
+
+    fn get_change_log_read(&self, table: &Bytes, log: &Bytes) -> Option<impl Read> {
+        let logs = self.change_logs.lock().unwrap();
+        logs.get(&(table.clone(), log.clone())).map(|buf| Cursor::new(buf.clone()))
+    }
+
+    fn get_change_logs_list(&self, table: &Bytes) -> Vec<Bytes> {
+        self.change_logs.lock().unwrap().keys().filter(|key| key.0 == table).map(|key| key.1.clone()).collect()
+    }
+
+    fn delete_change_log(&self, table: &Bytes, log: &Bytes) {
+        self.change_logs.lock().unwrap().remove(&(table.clone(), log.clone()));
+    }
+
+    fn sync_table(&self, _table: &Bytes) {}
+
+    fn sync_catalog(&self) {}
+
+    fn check_writable(&self) -> std::io::Result<()> {
+        Ok(())
     }
 }
\ No newline at end of file
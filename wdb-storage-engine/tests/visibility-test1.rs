@@ -0,0 +1,89 @@
+mod utils;
+
+use std::io::Cursor;
+
+use bytes::Bytes;
+use wdb_storage_engine::{Authorizations, Cell, ColumnVisibility, RowMutation, RowMutationOp, StorageEngine, Timestamp};
+
+use crate::utils::MemoryPersistance;
+
+fn put(column: &str, visibility: Option<&str>) -> RowMutation {
+    RowMutation {
+        table: Bytes::from("users"),
+        row: Bytes::from("user1"),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from("account"), column: Bytes::from(column.to_string()), timestamp: Some(Timestamp::from(100)), value: Bytes::from("value"), visibility: visibility.map(|visibility| Bytes::from(visibility.to_string())) },
+        ]
+    }
+}
+
+fn visible_columns(storage_engine: &StorageEngine<MemoryPersistance>, table: &str, labels: &[&str]) -> Vec<String> {
+    let authorizations = Authorizations::new(labels.iter().map(|label| label.to_string()));
+    let result = storage_engine.read_row_with_authorizations(Bytes::from(table.to_string()), Bytes::from("user1"), None, authorizations).unwrap();
+    result.cells.iter().map(|cell| String::from_utf8(cell.get_col().to_vec()).unwrap()).collect()
+}
+
+#[test]
+fn visibility_expression_test() {
+    let authorizations = Authorizations::new(["audit", "eu"]);
+
+    assert!(ColumnVisibility::parse(b"").unwrap().evaluate(&authorizations));
+    assert!(ColumnVisibility::parse(b"audit").unwrap().evaluate(&authorizations));
+    assert!(ColumnVisibility::parse(b"audit&eu").unwrap().evaluate(&authorizations));
+    assert!(!ColumnVisibility::parse(b"audit&us").unwrap().evaluate(&authorizations));
+    assert!(ColumnVisibility::parse(b"admin|(audit&eu)").unwrap().evaluate(&authorizations));
+    assert!(!ColumnVisibility::parse(b"admin|(audit&us)").unwrap().evaluate(&authorizations));
+
+    assert!(ColumnVisibility::parse(b"audit&eu|admin").is_err());
+    assert!(ColumnVisibility::parse(b"(audit").is_err());
+    assert!(ColumnVisibility::parse(b"audit)").is_err());
+    assert!(ColumnVisibility::parse(b"audit&").is_err());
+    assert!(ColumnVisibility::parse(b"a b").is_err());
+
+    let nested = |depth: usize| format!("{}audit{}", "(".repeat(depth), ")".repeat(depth)).into_bytes();
+    assert!(ColumnVisibility::parse(&nested(ColumnVisibility::MAX_DEPTH)).unwrap().evaluate(&authorizations));
+    assert_eq!(ColumnVisibility::parse(&nested(ColumnVisibility::MAX_DEPTH + 1)), Err("Visibility expression is nested too deeply."));
+    assert!(ColumnVisibility::parse(&nested(10_000)).is_err());
+    assert!(ColumnVisibility::parse(&vec![b'a'; ColumnVisibility::MAX_LEN + 1]).is_err());
+}
+
+#[test]
+fn visibility_scan_test() {
+    let table_name = Bytes::from("users");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.add_family(storage_engine.get_persitance_layer(), Bytes::from("account"), Default::default()).unwrap();
+    drop(table);
+
+    storage_engine.execute_row_mutation(put("name", None));
+    storage_engine.execute_row_mutation(put("saldo", Some("audit")));
+    storage_engine.execute_row_mutation(put("card", Some("admin|(audit&eu)")));
+    let too_long = "a".repeat(ColumnVisibility::MAX_LEN + 1);
+    assert_eq!(storage_engine.try_execute_row_mutation(put("pin", Some(&too_long))), Err("Visibility expression is too long."));
+
+    assert_eq!(visible_columns(&storage_engine, "users", &[]), ["name"]);
+    assert_eq!(visible_columns(&storage_engine, "users", &["audit"]), ["name", "saldo"]);
+    assert_eq!(visible_columns(&storage_engine, "users", &["audit", "eu"]), ["card", "name", "saldo"]);
+    assert_eq!(storage_engine.read_row(table_name.clone(), Bytes::from("user1"), None).cells.len(), 3);
+    assert!(storage_engine.read_row_with_authorizations(Bytes::from("missing"), Bytes::from("user1"), None, Authorizations::default()).is_err());
+
+    // Labels survive the flush to a segment.
+    let table = storage_engine.get_table(table_name.clone()).unwrap();
+    for family in table.get_families_iter() {
+        family.flush_memtable(&table_name, storage_engine.get_persitance_layer());
+    }
+    drop(table);
+    assert_eq!(visible_columns(&storage_engine, "users", &["audit"]), ["name", "saldo"]);
+
+    let mut buf = vec![];
+    storage_engine.export_table(table_name.clone(), None, None, &mut buf).unwrap();
+
+    let target = StorageEngine::empty(MemoryPersistance::new(), false);
+    assert_eq!(target.import_table(Bytes::from("users_copy"), Cursor::new(buf)).unwrap(), 3);
+    assert_eq!(visible_columns(&target, "users_copy", &["admin"]), ["card", "name"]);
+
+    let result = target.read_row(Bytes::from("users_copy"), Bytes::from("user1"), None);
+    assert_eq!(result.cells[0].get_visibility(), b"admin|(audit&eu)");
+}
\ No newline at end of file
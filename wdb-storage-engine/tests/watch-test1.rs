@@ -0,0 +1,89 @@
+mod utils;
+
+use bytes::Bytes;
+use wdb_storage_engine::{RowMutation, RowMutationOp, StorageEngine, WatchEvent, WatchFilter};
+
+use crate::utils::MemoryPersistance;
+
+fn put(row: &str, family: &str, column: &str) -> RowMutation {
+    RowMutation {
+        table: Bytes::from("users"),
+        row: Bytes::from(row.to_string()),
+        ops: vec![
+            RowMutationOp::Put { family: Bytes::from(family.to_string()), column: Bytes::from(column.to_string()), timestamp: None, value: Bytes::from("value"), visibility: None },
+        ]
+    }
+}
+
+#[test]
+fn watch_filters_test() {
+    let table_name = Bytes::from("users");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let mut table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.create_family(Bytes::from("account")).unwrap();
+    table.create_family(Bytes::from("address")).unwrap();
+    drop(table);
+
+    let mut prefix_rx = storage_engine.watch(table_name.clone(), WatchFilter { 
+        prefix: Some(Bytes::from("user#42#")), 
+        ..Default::default() 
+    }, 16).unwrap();
+    let mut column_rx = storage_engine.watch(table_name.clone(), WatchFilter { 
+        start_row: Some(Bytes::from("user#1")), 
+        end_row: Some(Bytes::from("user#5")), 
+        family: Some(Bytes::from("address")), 
+        column: Some(Bytes::from("city")), 
+        ..Default::default() 
+    }, 16).unwrap();
+
+    storage_engine.execute_row_mutation(put("user#42#1", "account", "saldo"));
+    storage_engine.execute_row_mutation(put("user#43#1", "address", "city"));
+    storage_engine.execute_row_mutation(put("user#42#2", "address", "street"));
+    storage_engine.execute_row_mutation(put("user#7", "address", "city"));
+
+    match prefix_rx.try_recv().unwrap() {
+        WatchEvent::Change { row, write_num, ops, .. } => {
+            assert_eq!(row, Bytes::from("user#42#1"));
+            assert_eq!(write_num, 1);
+            assert_eq!(ops.len(), 1);
+        },
+        _ => panic!("Unexpected event"),
+    }
+    match prefix_rx.try_recv().unwrap() {
+        WatchEvent::Change { row, .. } => assert_eq!(row, Bytes::from("user#42#2")),
+        _ => panic!("Unexpected event"),
+    }
+    assert!(prefix_rx.try_recv().is_err());
+
+    match column_rx.try_recv().unwrap() {
+        WatchEvent::Change { row, .. } => assert_eq!(row, Bytes::from("user#43#1")),
+        _ => panic!("Unexpected event"),
+    }
+    assert!(column_rx.try_recv().is_err());
+}
+
+#[test]
+fn watch_overflow_test() {
+    let table_name = Bytes::from("users");
+
+    let storage_engine = StorageEngine::empty(MemoryPersistance::new(), false);
+    storage_engine.create_table(table_name.clone()).unwrap();
+    let mut table = storage_engine.get_table(table_name.clone()).unwrap();
+    table.create_family(Bytes::from("account")).unwrap();
+    drop(table);
+
+    let mut rx = storage_engine.watch(table_name.clone(), WatchFilter::default(), 2).unwrap();
+    for _ in 0..5 {
+        storage_engine.execute_row_mutation(put("user1", "account", "saldo"));
+    }
+
+    assert!(matches!(rx.try_recv().unwrap(), WatchEvent::Change { .. }));
+    assert!(matches!(rx.try_recv().unwrap(), WatchEvent::Change { .. }));
+    assert_eq!(rx.try_recv().unwrap(), WatchEvent::Overflow);
+    assert!(rx.try_recv().is_err());
+
+    let table = storage_engine.get_table(table_name.clone()).unwrap();
+    assert_eq!(table.get_watch_registry().get_watchers_count(), 0);
+}
\ No newline at end of file